@@ -0,0 +1,130 @@
+use crate::utils::{fs as utils_fs, hash, metadata};
+use crate::utils::metadata::{Commit, IndexEntry};
+use crate::utils::repository::{GIT_DIR_NAME, Repository};
+use crate::RustGitResult;
+use std::collections::{BTreeMap, BTreeSet};
+use walkdir::WalkDir;
+
+/// 文件相对于比较基准的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileState {
+    Added,
+    Modified,
+    Deleted,
+    Untracked,
+}
+
+impl FileState {
+    /// porcelain 格式中的单字符状态码（未跟踪为 `?`）
+    pub fn code(self) -> char {
+        match self {
+            FileState::Added => 'A',
+            FileState::Modified => 'M',
+            FileState::Deleted => 'D',
+            FileState::Untracked => '?',
+        }
+    }
+}
+
+/// 单个文件的状态：暂存区相对 HEAD 的变更，以及工作区相对暂存区的变更
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusEntry {
+    pub path: String,                  // 相对仓库根目录的路径
+    pub staged: Option<FileState>,     // 暂存区 vs HEAD（要提交的变更）
+    pub unstaged: Option<FileState>,   // 工作区 vs 暂存区（未暂存的变更；未跟踪文件为 Untracked）
+}
+
+/// 计算工作区状态，按路径排序返回有变化的文件（大小和修改时间未变的文件跳过内容哈希）
+pub fn status(repo: &Repository) -> RustGitResult<Vec<StatusEntry>> {
+    // 裸仓库没有工作区
+    repo.ensure_work_tree()?;
+
+    // 只有拿到暂存区锁时才回写刷新后的元数据（其他进程正在修改暂存区时只读不写）
+    let lock = utils_fs::IndexLock::acquire(repo).ok();
+    let mut index = repo.index()?;
+    let head_map = hash::read_head_tree()?;
+    let mut states: BTreeMap<String, (Option<FileState>, Option<FileState>)> = BTreeMap::new();
+
+    // 1. 暂存区 vs 最新提交：要提交的变更
+    for entry in &index {
+        match head_map.get(&entry.path) {
+            None => states.entry(entry.path.clone()).or_default().0 = Some(FileState::Added),
+            Some(head_hash) if *head_hash != entry.hash => {
+                states.entry(entry.path.clone()).or_default().0 = Some(FileState::Modified);
+            }
+            _ => {}
+        }
+    }
+    for path in head_map.keys() {
+        if !index.iter().any(|entry| &entry.path == path) {
+            states.entry(path.clone()).or_default().0 = Some(FileState::Deleted);
+        }
+    }
+
+    // 2. 工作区 vs 暂存区：尚未暂存的变更
+    let mut refreshed = false;
+    for entry in index.iter_mut() {
+        let abs_path = repo.root().join(&entry.path);
+        if !abs_path.is_file() {
+            states.entry(entry.path.clone()).or_default().1 = Some(FileState::Deleted);
+        } else if !entry.matches_file(&abs_path)? {
+            states.entry(entry.path.clone()).or_default().1 = Some(FileState::Modified);
+        } else if metadata::file_stat(&abs_path)? != (entry.size, entry.mtime) {
+            // 内容一致但记录的元数据已过期：刷新后下次可直接命中快速路径
+            entry.refresh_stat(&abs_path)?;
+            refreshed = true;
+        }
+    }
+    if refreshed && lock.is_some() {
+        repo.write_index(&index)?;
+    }
+    drop(lock);
+
+    // 3. 工作区中不在暂存区的文件：未跟踪文件
+    let tracked: BTreeSet<&str> = index.iter().map(|entry| entry.path.as_str()).collect();
+    for entry in WalkDir::new(repo.root())
+        .into_iter()
+        .filter_entry(|e| e.file_name() != GIT_DIR_NAME) // 忽略仓库内部文件
+        .filter_map(|e| e.ok())
+    {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let rel_path = repo.strip_root(entry.path())?;
+        if !tracked.contains(rel_path.as_str()) {
+            states.entry(rel_path).or_default().1 = Some(FileState::Untracked);
+        }
+    }
+
+    Ok(states
+        .into_iter()
+        .map(|(path, (staged, unstaged))| StatusEntry { path, staged, unstaged })
+        .collect())
+}
+
+/// 按路径排序返回暂存区中的全部条目
+pub fn ls_files(repo: &Repository) -> RustGitResult<Vec<IndexEntry>> {
+    let mut entries = repo.index()?;
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(entries)
+}
+
+/// 从 HEAD 沿第一父提交链返回提交历史（最新在前，数量受 max_count 限制；尚无提交时为空）
+pub fn log(repo: &Repository, max_count: Option<usize>) -> RustGitResult<Vec<Commit>> {
+    let mut commits = Vec::new();
+    let mut next = repo.head()?;
+    while let Some(commit_id) = next {
+        if max_count.is_some_and(|max_count| commits.len() >= max_count) {
+            break;
+        }
+        let commit = hash::read_commit(&commit_id)?;
+        next = commit.first_parent().map(str::to_string);
+        commits.push(commit);
+    }
+    Ok(commits)
+}
+
+/// 解析修订（HEAD / 分支名 / 标签 / 哈希 / HEAD~n）并读取对应提交
+pub fn read_commit(rev: &str) -> RustGitResult<Commit> {
+    hash::read_commit(&utils_fs::resolve_rev(rev)?)
+}
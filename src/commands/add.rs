@@ -1,103 +1,256 @@
 use anyhow::Context;
 use walkdir::WalkDir;
+use crate::utils::config;
+use crate::utils::eol;
 use crate::utils::fs as utils_fs;
-use crate::utils::hash;
-use crate::RustGitResult;
-use serde_json::Value;
+use crate::utils::hash::{self, ObjectType};
+use crate::utils::ignore::IgnoreRules;
+use crate::utils::metadata::{self, IndexEntry};
+use crate::utils::repository::Repository;
+use crate::{RustGitResult, Verbosity};
+use similar::{DiffOp, TextDiff};
+use std::collections::BTreeSet;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-/// 实现 git add 核心逻辑
-pub fn add(path: &str) -> RustGitResult<()> {
-    // 检查仓库是否初始化
-    if !utils_fs::is_repo_initialized() {
-        return Err(anyhow::anyhow!("未初始化 rust-git 仓库，请先执行 `rust-git init`"));
+/// 按块暂存时划分块使用的上下文行数（与 diff 输出的块一致）
+const PATCH_CONTEXT: usize = 3;
+
+/// 实现 git add 核心逻辑（all 时忽略 paths，暂存整个工作区的新增、修改和删除；
+/// dry_run 时只列出将被暂存的文件，不存储对象也不修改暂存区；Verbose 时逐个输出有变化的文件）
+pub fn add(repo: &Repository, paths: &[String], all: bool, dry_run: bool, verbosity: Verbosity) -> RustGitResult<()> {
+    // 裸仓库没有工作区
+    repo.ensure_work_tree()?;
+
+    // 持有暂存区锁直到命令结束，防止并发进程交错读写暂存区
+    let _lock = utils_fs::IndexLock::acquire(repo)?;
+
+    // 获取绝对路径并标准化（先全部校验，避免只添加了一部分）
+    let mut abs_paths = Vec::new();
+    if all {
+        abs_paths.push(repo.root().to_path_buf());
+    }
+    for path in paths.iter().filter(|_| !all) {
+        let abs_path = utils_fs::get_absolute_path(path)?;
+        if !abs_path.exists() {
+            return Err(anyhow::anyhow!("文件/目录不存在：{}", abs_path.display()));
+        }
+        // 拒绝仓库根目录之外的路径（如 ../other）
+        repo.ensure_inside(&abs_path)?;
+        abs_paths.push(abs_path);
+    }
+
+    // 读取暂存区（所有路径共享一次读写）
+    let mut index = repo.index()?;
+
+    // 读取忽略规则（只编译一次）
+    let ignore_rules = IgnoreRules::load(repo.root())?;
+
+    // 大小写不敏感的文件系统上，仅大小写不同的路径视为同一文件
+    let ignore_case = config::get_bool("core.ignorecase", false)?;
+
+    // 展开目录，得到全部待添加的文件
+    let mut files = Vec::new();
+    for abs_path in &abs_paths {
+        collect_files(repo, abs_path, &ignore_rules, &mut files)?;
+    }
+
+    if dry_run {
+        for file in &files {
+            let rel_path = repo.strip_root(file)?;
+            let unchanged = match index.iter().find(|entry| utils_fs::paths_equal(&entry.path, &rel_path, ignore_case)) {
+                Some(entry) => entry.matches_file(file)? && entry.mode == metadata::file_mode(file)?,
+                None => false,
+            };
+            if !unchanged {
+                println!("would add: {}", rel_path);
+            }
+        }
+        if all {
+            for entry in index.iter().filter(|entry| !repo.root().join(&entry.path).is_file()) {
+                println!("would remove: {}", entry.path);
+            }
+        }
+        return Ok(());
+    }
+
+    for file in &files {
+        if add_single_file(repo, file, ignore_case, &mut index)? && verbosity == Verbosity::Verbose {
+            println!("添加：{}", repo.strip_root(file)?);
+        }
+    }
+
+    // 工作区中已删除的已跟踪文件：从暂存区移除
+    if all {
+        index.retain(|entry| {
+            let exists = repo.root().join(&entry.path).is_file();
+            if !exists {
+                println!("删除：{}", entry.path);
+            }
+            exists
+        });
+    }
+
+    // 写入更新后的暂存区
+    repo.write_index(&index)?;
+    for abs_path in &abs_paths {
+        println!("已将 {} 添加到暂存区", abs_path.display());
     }
 
-    // 获取绝对路径并标准化
-    let abs_path = utils_fs::get_absolute_path(path)?;
-    if !abs_path.exists() {
-        return Err(anyhow::anyhow!("文件/目录不存在：{}", abs_path.display()));
+    Ok(())
+}
+
+/// 实现 git add --patch 的非交互版本：只暂存文件相对暂存区版本的指定块（块编号从 1 开始，与 diff 输出的块顺序一致）
+pub fn add_hunks(repo: &Repository, paths: &[String], hunks: &[usize]) -> RustGitResult<()> {
+    // 裸仓库没有工作区
+    repo.ensure_work_tree()?;
+
+    // 持有暂存区锁直到命令结束，防止并发进程交错读写暂存区
+    let _lock = utils_fs::IndexLock::acquire(repo)?;
+
+    let [path] = paths else {
+        return Err(anyhow::anyhow!("--patch 只能指定一个文件"));
+    };
+    let rel_path = repo.relative_path(path)?;
+    let abs_path = repo.root().join(&rel_path);
+    let mut index = repo.index()?;
+    // core.ignorecase 时忽略大小写匹配暂存区条目（与 add 一致，保留条目原有的路径写法）
+    let ignore_case = config::get_bool("core.ignorecase", false)?;
+    let entry = index
+        .iter_mut()
+        .find(|entry| utils_fs::paths_equal(&entry.path, &rel_path, ignore_case))
+        .ok_or_else(|| anyhow::anyhow!("文件 {} 未被跟踪，无法按块暂存（请先使用 add 添加整个文件）", rel_path))?;
+    if !abs_path.is_file() {
+        return Err(anyhow::anyhow!("文件 {} 已在工作区中删除，无法按块暂存", rel_path));
+    }
+
+    // 暂存区版本与工作区版本（按 core.autocrlf 转换后）按行比较
+    let staged = hash::read_object(&entry.hash)?;
+    let working = eol::to_repo(
+        fs::read(&abs_path).context(format!("读取文件失败：{}", abs_path.display()))?,
+    )?;
+    let (Ok(old_text), Ok(new_text)) = (std::str::from_utf8(&staged), std::str::from_utf8(&working)) else {
+        return Err(anyhow::anyhow!("文件 {} 是二进制文件，不支持按块暂存", rel_path));
+    };
+    let text_diff = TextDiff::from_lines(old_text, new_text);
+    let groups = text_diff.grouped_ops(PATCH_CONTEXT);
+    if groups.is_empty() {
+        return Err(anyhow::anyhow!("文件 {} 没有未暂存的修改", rel_path));
     }
 
-    // 读取暂存区（修复核心：拆分可变借用，避免冲突）
-    let mut index = utils_fs::read_index()?;
-    
-    // 步骤1：确保 index 是数组类型（一次性完成，无重复借用）
-    if !index.is_array() {
-        index = Value::Array(Vec::new());
+    let selected: BTreeSet<usize> = hunks.iter().copied().collect();
+    if let Some(&out_of_range) = selected.iter().find(|&&hunk| hunk == 0 || hunk > groups.len()) {
+        return Err(anyhow::anyhow!(
+            "块编号 {} 超出范围（文件 {} 共 {} 个块）",
+            out_of_range,
+            rel_path,
+            groups.len()
+        ));
     }
-    
-    // 步骤2：获取数组的可变引用（此时只有一个可变借用）
-    let index_array = index.as_array_mut().unwrap();
 
-    // 处理文件/目录
+    // 以暂存区版本为基础，只应用选中块中的修改
+    let (old_lines, new_lines) = (text_diff.old_slices(), text_diff.new_slices());
+    let mut content = String::new();
+    let mut pos = 0;
+    for (number, group) in groups.iter().enumerate() {
+        let apply = selected.contains(&(number + 1));
+        for op in group {
+            let old_range = op.old_range();
+            content.extend(old_lines[pos..old_range.start].iter().copied());
+            if apply && !matches!(op, DiffOp::Equal { .. }) {
+                content.extend(new_lines[op.new_range()].iter().copied());
+            } else {
+                content.extend(old_lines[old_range.clone()].iter().copied());
+            }
+            pos = old_range.end;
+        }
+    }
+    content.extend(old_lines[pos..].iter().copied());
+
+    entry.hash = hash::store_object(ObjectType::Blob, content.as_bytes())?;
+    // 暂存区内容不再对应工作区文件，清除元数据避免被误判为未修改
+    entry.clear_stat();
+    repo.write_index(&index)?;
+
+    let list: Vec<String> = selected.iter().map(usize::to_string).collect();
+    println!("已暂存 {} 的第 {} 块（共 {} 块）", rel_path, list.join(", "), groups.len());
+    Ok(())
+}
+
+/// 收集文件/目录下待添加的文件（目录递归处理）
+fn collect_files(
+    repo: &Repository,
+    abs_path: &Path,
+    ignore_rules: &IgnoreRules,
+    files: &mut Vec<PathBuf>,
+) -> RustGitResult<()> {
     if abs_path.is_file() {
-        add_single_file(&abs_path, index_array)?;
+        files.push(abs_path.to_path_buf());
     } else if abs_path.is_dir() {
-        // 递归遍历目录下所有文件（跳过 .rust-git 目录）
-        for entry in WalkDir::new(&abs_path)
+        // 递归遍历目录下所有文件（跳过 .rust-git 目录和被忽略的路径）
+        for entry in WalkDir::new(abs_path)
             .into_iter()
+            .filter_entry(|e| {
+                if e.file_name() == ".rust-git" {
+                    return false; // 忽略仓库内部文件
+                }
+                match e.path().strip_prefix(repo.root()).ok().and_then(|p| p.to_str()) {
+                    Some(rel_path) if !rel_path.is_empty() => !ignore_rules.is_ignored(
+                        &utils_fs::normalize_path(rel_path),
+                        e.file_type().is_dir(),
+                    ),
+                    _ => true,
+                }
+            })
             .filter_map(|e| e.ok())
-            .filter(|e| !e.path().starts_with(".rust-git")) // 忽略仓库内部文件
         {
             let entry_path = entry.path();
             if entry_path.is_file() {
-                add_single_file(entry_path, index_array)?;
+                files.push(entry_path.to_path_buf());
             }
         }
     }
 
-    // 写入更新后的暂存区
-    utils_fs::write_index(&index)?;
-    println!("已将 {} 添加到暂存区", abs_path.display());
-
     Ok(())
 }
 
-/// 添加单个文件到暂存区
-fn add_single_file(file_path: &Path, index_array: &mut Vec<Value>) -> RustGitResult<()> {
-    // 1. 计算文件内容的哈希值
-    let file_hash = hash::hash_file(file_path)
-        .context(format!("计算文件哈希失败：{}", file_path.display()))?;
-    
-    // 2. 将文件内容存储为 Git 对象
-    let file_content = fs::read(file_path)
-        .context(format!("读取文件失败：{}", file_path.display()))?;
-    hash::store_object(&file_hash, &file_content)
+/// 添加单个文件到暂存区，返回暂存区条目是否有变化（新增或哈希、模式改变）
+fn add_single_file(
+    repo: &Repository,
+    file_path: &Path,
+    ignore_case: bool,
+    index: &mut Vec<IndexEntry>,
+) -> RustGitResult<bool> {
+    // 1. 将文件内容存储为 blob 对象，得到其哈希值（流式读取，计算哈希与写入对象一次完成）
+    let file_hash = hash::store_file(file_path)
         .context(format!("存储文件对象失败：{}", file_path.display()))?;
 
-    // 3. 获取仓库根目录，计算相对路径（标准化分隔符）
-    let repo_root = utils_fs::get_repo_root()?;
-    let rel_path = file_path.strip_prefix(&repo_root)
-        .context(format!(
-            "文件 {} 不在 rust-git 仓库目录 {} 下",
-            file_path.display(),
-            repo_root.display()
-        ))?
-        .to_str()
-        .ok_or_else(|| anyhow::anyhow!("路径转换为字符串失败：{}", file_path.display()))?;
-    let normalized_rel_path = utils_fs::normalize_path(rel_path); // 统一路径分隔符
-
-    // 4. 更新暂存区：存在则更新哈希，不存在则新增
-    let mut entry_updated = false;
-    for entry in index_array.iter_mut() {
-        // 匹配标准化后的路径
-        if entry["path"].as_str() == Some(&normalized_rel_path) {
-            entry["hash"] = Value::String(file_hash.clone());
-            entry_updated = true;
-            break;
-        }
-    }
+    // 2. 计算相对仓库根目录的路径（标准化分隔符）
+    let normalized_rel_path = repo.strip_root(file_path)?;
 
-    // 新增暂存区条目
-    if !entry_updated {
-        let new_entry = serde_json::json!({
-            "path": normalized_rel_path,
-            "hash": file_hash
-        });
-        index_array.push(new_entry);
-    }
+    // 3. 读取文件模式（可执行位）
+    let mode = metadata::file_mode(file_path)?;
 
-    Ok(())
-}
\ No newline at end of file
+    // 4. 更新暂存区：存在则更新哈希和模式（保留已有条目的路径写法），不存在则新增；同时记录文件大小和修改时间
+    let (size, mtime) = metadata::file_stat(file_path)?;
+    match index.iter_mut().find(|entry| utils_fs::paths_equal(&entry.path, &normalized_rel_path, ignore_case)) {
+        Some(entry) => {
+            let changed = entry.hash != file_hash || entry.mode != mode;
+            entry.hash = file_hash;
+            entry.mode = mode;
+            entry.size = size;
+            entry.mtime = mtime;
+            Ok(changed)
+        }
+        None => {
+            index.push(IndexEntry {
+                path: normalized_rel_path,
+                hash: file_hash,
+                mode,
+                size,
+                mtime,
+            });
+            Ok(true)
+        }
+    }
+}
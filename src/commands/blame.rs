@@ -0,0 +1,116 @@
+use crate::utils::{fs as utils_fs, hash};
+use crate::utils::metadata::Commit;
+use crate::RustGitResult;
+use chrono::TimeZone;
+use similar::{ChangeTag, TextDiff};
+
+/// 实现 git blame 核心逻辑（为文件每一行找出最后引入它的提交）
+pub fn blame(path: &str) -> RustGitResult<()> {
+    // 检查仓库是否初始化
+    if !utils_fs::is_repo_initialized() {
+        return Err(anyhow::anyhow!("未初始化 rust-git 仓库，请先执行 `rust-git init`"));
+    }
+
+    let rel_path = utils_fs::get_repo_relative_path(path)?;
+    let head = utils_fs::resolve_head()?
+        .ok_or_else(|| anyhow::anyhow!("暂无提交记录"))?;
+
+    let mut commit = hash::read_commit(&head)?;
+    let mut content = read_file_text(&commit.id, &rel_path)?
+        .ok_or_else(|| anyhow::anyhow!("文件 {} 未在 HEAD 中找到", rel_path))?;
+    let lines: Vec<String> = content.lines().map(str::to_string).collect();
+
+    // 每行的归属提交；pending 记录尚未归属的行：(最终行号, 在当前提交版本中的行号)
+    let mut owners: Vec<Option<Commit>> = vec![None; lines.len()];
+    let mut pending: Vec<(usize, usize)> = (0..lines.len()).map(|line| (line, line)).collect();
+
+    while !pending.is_empty() {
+        // 父提交中不存在该文件（或已到根提交）时，剩余行都归属当前提交
+        let parent = match commit.first_parent() {
+            Some(parent_id) => read_file_text(parent_id, &rel_path)?
+                .map(|parent_content| (parent_id.to_string(), parent_content)),
+            None => None,
+        };
+        let (parent_id, parent_content) = match parent {
+            Some(parent) => parent,
+            None => {
+                for (final_line, _) in pending.drain(..) {
+                    owners[final_line] = Some(commit.clone());
+                }
+                break;
+            }
+        };
+
+        // 对比父版本：未变化的行继续向前追溯，新增的行归属当前提交
+        let new_to_old = map_unchanged_lines(&parent_content, &content);
+        let mut still_pending = Vec::new();
+        for (final_line, line) in pending {
+            match new_to_old.get(line).copied().flatten() {
+                Some(old_line) => still_pending.push((final_line, old_line)),
+                None => owners[final_line] = Some(commit.clone()),
+            }
+        }
+        pending = still_pending;
+
+        commit = hash::read_commit(&parent_id)?;
+        content = parent_content;
+    }
+
+    // 作者列按最长作者名对齐
+    let names: Vec<&str> = owners
+        .iter()
+        .map(|owner| owner.as_ref().map_or("", |commit| author_name(&commit.author)))
+        .collect();
+    let name_width = names.iter().map(|name| name.chars().count()).max().unwrap_or(0);
+
+    for ((line, owner), name) in lines.iter().zip(&owners).zip(&names) {
+        let commit = owner.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("无法确定行的归属提交"))?;
+        let date = chrono::Local
+            .timestamp_opt(commit.timestamp, 0)
+            .single()
+            .unwrap_or_else(chrono::Local::now);
+        println!(
+            "{} ({:<name_width$} {}) {}",
+            &commit.id[..7],
+            name,
+            date.format("%Y-%m-%d"),
+            line
+        );
+    }
+
+    Ok(())
+}
+
+/// 读取提交中指定文件的文本内容（文件不存在时返回 None）
+fn read_file_text(commit_id: &str, rel_path: &str) -> RustGitResult<Option<String>> {
+    let tree = hash::read_commit_tree(commit_id)?;
+    let file_hash = match tree.get(rel_path) {
+        Some(file_hash) => file_hash,
+        None => return Ok(None),
+    };
+    let content = hash::read_object(file_hash)?;
+    String::from_utf8(content)
+        .map(Some)
+        .map_err(|_| anyhow::anyhow!("文件 {} 是二进制文件，无法逐行追溯", rel_path))
+}
+
+/// 逐行对比新旧版本，返回新版本每行对应的旧版本行号（新增的行为 None）
+fn map_unchanged_lines(old_text: &str, new_text: &str) -> Vec<Option<usize>> {
+    let text_diff = TextDiff::from_lines(old_text, new_text);
+    let mut new_to_old = vec![None; new_text.lines().count()];
+    for change in text_diff.iter_all_changes() {
+        if change.tag() == ChangeTag::Equal
+            && let (Some(old_index), Some(new_index)) = (change.old_index(), change.new_index())
+            && new_index < new_to_old.len()
+        {
+            new_to_old[new_index] = Some(old_index);
+        }
+    }
+    new_to_old
+}
+
+/// 从 `Name <email>` 格式的作者信息中提取名字
+fn author_name(author: &str) -> &str {
+    author.split(" <").next().unwrap_or(author).trim()
+}
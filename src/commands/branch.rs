@@ -1,8 +1,18 @@
-use crate::utils::fs;
+use anyhow::Context;
+use crate::utils::{color, fs, hash, metadata};
+use globset::Glob;
 use crate::RustGitResult;
 
-/// 实现 git branch 核心逻辑
-pub fn branch(name: Option<String>, delete: Option<String>) -> RustGitResult<()> {
+/// 实现 git branch 核心逻辑（list 或 verbose 时 name 作为过滤分支名的 glob 模式；
+/// force 时删除分支不检查其提交是否已合并到其他分支）
+pub fn branch(
+    name: Option<String>,
+    delete: Option<String>,
+    force: bool,
+    rename: Option<String>,
+    list: bool,
+    verbose: bool,
+) -> RustGitResult<()> {
     // 检查仓库是否初始化
     if !fs::is_repo_initialized() {
         return Err(anyhow::anyhow!("未初始化 rust-git 仓库，请先执行 `rust-git init`"));
@@ -10,28 +20,74 @@ pub fn branch(name: Option<String>, delete: Option<String>) -> RustGitResult<()>
 
     // 处理删除分支
     if let Some(branch_to_delete) = delete {
-        fs::delete_branch(&branch_to_delete)?;
+        fs::delete_branch(&branch_to_delete, force)?;
         println!("已删除分支：{}", branch_to_delete);
         return Ok(());
     }
 
+    // 处理重命名分支（未指定旧分支名时重命名当前分支）
+    if let Some(new_name) = rename {
+        let old_name = match name {
+            Some(old_name) => old_name,
+            None => fs::get_current_branch()?,
+        };
+        fs::rename_branch(&old_name, &new_name)?;
+        let commit_id = fs::read_branch_commit(&new_name)?;
+        metadata::append_reflog(
+            Some(&commit_id),
+            &commit_id,
+            &new_name,
+            &format!("branch: renamed {} to {}", old_name, new_name),
+        )?;
+        println!("已将分支 {} 重命名为 {}", old_name, new_name);
+        return Ok(());
+    }
+
     // 处理创建分支
-    if let Some(branch_name) = name {
-        fs::create_branch(&branch_name)?;
+    if let Some(branch_name) = name.as_ref().filter(|_| !list && !verbose) {
+        fs::create_branch(branch_name)?;
+        let commit_id = fs::read_branch_commit(branch_name)?;
+        metadata::append_reflog(None, &commit_id, branch_name, "branch: Created from HEAD")?;
         println!("已创建分支：{}", branch_name);
         return Ok(());
     }
 
-    // 列出所有分支
-    let branches = fs::list_branches()?;
+    // 列出分支（指定模式时只列出匹配的分支）
+    let mut branches = fs::list_branches()?;
+    if let Some(pattern) = &name {
+        let matcher = Glob::new(pattern)
+            .context(format!("分支过滤模式不合法：{}", pattern))?
+            .compile_matcher();
+        branches.retain(|branch| matcher.is_match(branch));
+    }
     let current_branch = fs::get_current_branch()?;
     println!("本地分支：");
-    for branch in branches {
-        if branch == current_branch {
-            println!("* {}", branch); // 当前分支标星
-        } else {
-            println!("  {}", branch);
+    if current_branch == fs::DETACHED_HEAD
+        && let Some(commit_id) = fs::resolve_head()?
+    {
+        println!("* (HEAD detached at {})", &commit_id[..commit_id.len().min(7)]);
+    }
+    let name_width = branches.iter().map(|branch| branch.len()).max().unwrap_or(0);
+    for branch in &branches {
+        let marker = if *branch == current_branch { '*' } else { ' ' }; // 当前分支标星
+        if !verbose {
+            println!("{} {}", marker, branch);
+            continue;
+        }
+        // -v：追加最新提交的短哈希和标题
+        let commit_id = fs::read_branch_commit(branch)?;
+        if commit_id.is_empty() {
+            println!("{} {}", marker, branch);
+            continue;
         }
+        let commit = hash::parse_commit_full(&hash::read_object(&commit_id)?)?;
+        println!(
+            "{} {:<name_width$} {} {}",
+            marker,
+            branch,
+            color::yellow(&commit_id[..7]),
+            commit.message.lines().next().unwrap_or(""),
+        );
     }
 
     Ok(())
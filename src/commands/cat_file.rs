@@ -0,0 +1,44 @@
+use crate::utils::fs as utils_fs;
+use crate::utils::hash::{self, ObjectType};
+use crate::RustGitResult;
+use std::io::Write;
+
+/// 实现 git cat-file 核心逻辑（-t 输出对象类型，-p 格式化输出对象内容）
+pub fn cat_file(object_hash: &str, pretty: bool, type_only: bool) -> RustGitResult<()> {
+    // 检查仓库是否初始化
+    if !utils_fs::is_repo_initialized() {
+        return Err(anyhow::anyhow!("未初始化 rust-git 仓库，请先执行 `rust-git init`"));
+    }
+
+    if !pretty && !type_only {
+        return Err(anyhow::anyhow!("请指定 -t（输出类型）或 -p（输出内容）"));
+    }
+
+    // 支持缩写哈希
+    let object_hash = hash::resolve_hash(object_hash)?;
+    let (obj_type, content) = hash::read_object_with_type(&object_hash)?;
+
+    // -t：只输出对象类型（旧版无对象头的对象类型未知）
+    if type_only {
+        println!("{}", obj_type.map_or("unknown", |t| t.as_str()));
+        return Ok(());
+    }
+
+    // -p：按对象类型格式化输出
+    match obj_type {
+        Some(ObjectType::Tree) => {
+            let tree: serde_json::Value = serde_json::from_slice(&content)
+                .map_err(|e| anyhow::anyhow!("解析目录树对象失败：{}", e))?;
+            println!("{}", serde_json::to_string_pretty(&tree)?);
+        }
+        Some(ObjectType::Commit) | Some(ObjectType::Tag) => {
+            println!("{}", String::from_utf8_lossy(&content));
+        }
+        // blob 及未知类型：原样输出字节
+        _ => {
+            std::io::stdout().write_all(&content)?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,29 +1,74 @@
 use anyhow::Context;
-use crate::utils::hash;
+use crate::utils::hash::{self, ObjectType};
+use crate::utils::eol;
 use crate::utils::fs as utils_fs;
+use crate::utils::metadata::{self, IndexEntry};
+use crate::utils::repository::Repository;
 use crate::RustGitResult;
 use serde_json::Value;
+use std::collections::BTreeSet;
 use std::fs;
 
 /// 实现 git checkout 核心逻辑（切换分支/恢复文件）
-pub fn checkout(target: &str) -> RustGitResult<()> {
-    // 检查仓库是否初始化
-    if !utils_fs::is_repo_initialized() {
-        return Err(anyhow::anyhow!("未初始化 rust-git 仓库，请先执行 `rust-git init`"));
+///
+/// `--` 之后的 paths 始终按文件恢复（即使存在同名分支）；target 为 `-` 时切换回上一次所在的分支（或提交），
+/// 否则 target 依次按分支、标签、修订、文件解析。
+pub fn checkout(repo: &Repository, target: Option<&str>, paths: &[String]) -> RustGitResult<()> {
+    // 裸仓库没有工作区
+    repo.ensure_work_tree()?;
+
+    // 持有暂存区锁直到命令结束，防止并发进程交错读写暂存区
+    let _lock = utils_fs::IndexLock::acquire(repo)?;
+
+    let Some(target) = target else {
+        // 路径规格按暂存区和最新提交中的文件展开（支持目录和 glob 模式）
+        let mut candidates: Vec<String> = repo.index()?.into_iter().map(|entry| entry.path).collect();
+        candidates.extend(hash::read_head_tree()?.into_keys());
+        let entries = utils_fs::expand_pathspecs(paths, &candidates)?
+            .iter()
+            .map(|rel_path| file_entry(repo, rel_path))
+            .collect::<RustGitResult<Vec<IndexEntry>>>()?;
+        hash::ensure_objects_exist(entries.iter().map(|entry| entry.hash.as_str()))?;
+        for entry in &entries {
+            write_file_entry(repo, entry)?;
+        }
+        return Ok(());
+    };
+
+    // `-`：回到上一次切换前的位置
+    if target == "-" {
+        let previous = previous_head(repo)?;
+        if utils_fs::list_branches()?.contains(&previous) {
+            return checkout_branch(repo, &previous, false);
+        }
+        return checkout_commit(repo, &previous, false);
     }
 
     // 先尝试切换分支
     let branches = utils_fs::list_branches()?;
     if branches.contains(&target.to_string()) {
-        return checkout_branch(target);
+        return checkout_branch(repo, target, false);
+    }
+
+    // 标签：切换到标签指向的提交（分离 HEAD 状态）
+    if utils_fs::list_tags()?.iter().any(|tag| tag == target) {
+        return checkout_commit(repo, &utils_fs::read_tag_commit(target)?, false);
+    }
+
+    // 若不是已存在的文件，尝试按修订（缩写哈希、HEAD~n 等）切换到分离 HEAD 状态
+    if !std::path::Path::new(target).exists()
+        && let Ok(commit_id) = utils_fs::resolve_rev(target)
+        && hash::read_object_with_type(&commit_id)?.0 == Some(ObjectType::Commit)
+    {
+        return checkout_commit(repo, &commit_id, false);
     }
 
     // 若不是分支，尝试恢复文件
-    checkout_file(target)
+    checkout_file(repo, &repo.relative_path(target)?)
 }
 
-/// 切换分支
-fn checkout_branch(branch_name: &str) -> RustGitResult<()> {
+/// 切换分支（force 为 false 时，若会覆盖未提交的修改则拒绝切换）
+pub fn checkout_branch(repo: &Repository, branch_name: &str, force: bool) -> RustGitResult<()> {
     // 检查分支是否存在
     let branches = utils_fs::list_branches()?;
     if !branches.contains(&branch_name.to_string()) {
@@ -31,82 +76,128 @@ fn checkout_branch(branch_name: &str) -> RustGitResult<()> {
     }
 
     // 获取当前分支
-    let current_branch = utils_fs::get_current_branch()?;
+    let current_branch = repo.current_branch()?;
     if current_branch == branch_name {
         println!("已在分支 {} 上", branch_name);
         return Ok(());
     }
 
-    // 读取目标分支的提交ID
+    // 读取切换前后的提交ID
+    let old_commit_id = repo.head()?;
     let commit_id = utils_fs::read_branch_commit(branch_name)?;
+    ensure_no_uncommitted_changes(repo, old_commit_id.as_deref(), &commit_id, force)?;
+    // 从提交恢复工作区（简化版：恢复暂存区所有文件）；目标分支与当前指向同一提交时保留工作区
+    // 先恢复再移动 HEAD：对象缺失时 HEAD 保持不变
+    if old_commit_id.as_deref() != Some(commit_id.as_str()) {
+        restore_working_dir(repo, &commit_id)?;
+    }
     // 更新 HEAD 指向目标分支
-    let head_content = format!("ref: refs/heads/{}", branch_name);
-    fs::write(".rust-git/HEAD", head_content)
-        .context("更新 HEAD 指向分支失败")?;
-
-    // 从提交恢复工作区（简化版：恢复暂存区所有文件）
-    restore_working_dir(&commit_id)?;
+    record_previous_head(repo)?;
+    repo.set_head_branch(branch_name)?;
+    metadata::append_reflog(
+        old_commit_id.as_deref(),
+        &commit_id,
+        branch_name,
+        &format!("checkout: moving from {} to {}", current_branch, branch_name),
+    )?;
 
     println!("已切换到分支 {}", branch_name);
     Ok(())
 }
 
-/// 恢复文件（从最新提交/暂存区）
-fn checkout_file(file_path: &str) -> RustGitResult<()> {
-    // 获取绝对路径
-    let abs_path = utils_fs::get_absolute_path(file_path)?;
-    let repo_root_local = std::env::current_dir()?;
-    let rel_path = utils_fs::normalize_path(
-        abs_path.strip_prefix(&repo_root_local)?
-            .to_str()
-            .ok_or_else(|| anyhow::anyhow!("路径转换失败"))?
-    );
-
-    // 读取当前 HEAD 指向的提交ID
-    let current_branch = utils_fs::get_current_branch()?;
-    let commit_id = utils_fs::read_branch_commit(&current_branch)?;
-
-    // 读取提交对象，获取目录树哈希
-    let commit_content = hash::read_object(&commit_id)?;
-    let tree_hash = hash::parse_commit(&commit_content)?;
+/// 切换到指定提交（分离 HEAD 状态）
+fn checkout_commit(repo: &Repository, commit_id: &str, force: bool) -> RustGitResult<()> {
+    let current_branch = repo.current_branch()?;
+    let old_commit_id = repo.head()?;
+    ensure_no_uncommitted_changes(repo, old_commit_id.as_deref(), commit_id, force)?;
 
-    // 读取目录树（暂存区内容）
-    let tree = hash::parse_tree(&tree_hash)?;
-    let index_array = if tree.is_array() {
-        tree
+    // 从提交恢复工作区（先于移动 HEAD）
+    restore_working_dir(repo, commit_id)?;
+
+    // HEAD 直接存储提交ID
+    record_previous_head(repo)?;
+    repo.set_head_detached(commit_id)?;
+    metadata::append_reflog(
+        old_commit_id.as_deref(),
+        commit_id,
+        utils_fs::DETACHED_HEAD,
+        &format!("checkout: moving from {} to {}", current_branch, commit_id),
+    )?;
+
+    println!("HEAD 目前处于分离状态，位于 {}", &commit_id[..7]);
+    Ok(())
+}
+
+/// 在移动 HEAD 之前记录当前位置（分支名；分离 HEAD 时为提交ID）
+fn record_previous_head(repo: &Repository) -> RustGitResult<()> {
+    let current_branch = repo.current_branch()?;
+    let previous = if current_branch == utils_fs::DETACHED_HEAD {
+        match repo.head()? {
+            Some(commit_id) => commit_id,
+            None => return Ok(()),
+        }
     } else {
-        return Err(anyhow::anyhow!("目录树格式错误"));
+        current_branch
     };
+    utils_fs::write_atomic(&repo.previous_head_path(), previous)
+        .context("记录上一次的 HEAD 位置失败")
+}
 
-    // 查找文件条目
-    let mut file_entry: Option<Value> = None;
-    if let Value::Array(entries) = &index_array {
-        for entry in entries {
-            if entry["path"] == rel_path {
-                file_entry = Some(entry.clone());
-                break;
-            }
-        }
+/// 读取上一次切换前的 HEAD 位置（分支名或提交ID）
+fn previous_head(repo: &Repository) -> RustGitResult<String> {
+    let path = repo.previous_head_path();
+    if !path.is_file() {
+        return Err(anyhow::anyhow!("没有上一个分支：尚未切换过分支"));
     }
+    let previous = fs::read_to_string(&path)
+        .context("读取上一次的 HEAD 位置失败")?
+        .trim()
+        .to_string();
+    if previous.is_empty() {
+        return Err(anyhow::anyhow!("没有上一个分支：尚未切换过分支"));
+    }
+    Ok(previous)
+}
 
-    if file_entry.is_none() {
-        return Err(anyhow::anyhow!("文件 {} 未在提交中找到", file_path));
+/// 恢复文件（rel_path 相对仓库根目录）：优先使用暂存区中的版本（丢弃工作区修改），未跟踪时使用最新提交中的版本
+fn checkout_file(repo: &Repository, rel_path: &str) -> RustGitResult<()> {
+    let entry = file_entry(repo, rel_path)?;
+    hash::ensure_objects_exist([entry.hash.as_str()])?;
+    write_file_entry(repo, &entry)
+}
+
+/// 查找要恢复的文件版本：优先暂存区，其次最新提交
+fn file_entry(repo: &Repository, rel_path: &str) -> RustGitResult<IndexEntry> {
+    if let Some(entry) = repo.index()?.into_iter().find(|entry| entry.path == rel_path) {
+        return Ok(entry);
     }
+    let commit_id = repo.head()?
+        .ok_or_else(|| anyhow::anyhow!("暂无提交记录，无法恢复文件"))?;
+    hash::read_tree_entries(&hash::read_commit(&commit_id)?.tree_hash)?
+        .into_iter()
+        .find(|entry| entry.path == rel_path)
+        .ok_or_else(|| anyhow::anyhow!("文件 {} 未在暂存区或提交中找到", rel_path))
+}
 
-    // 读取文件对象内容并写入工作区
-    let entry = file_entry.unwrap();
-    let file_hash = entry["hash"].as_str()
-        .ok_or_else(|| anyhow::anyhow!("文件哈希格式错误"))?;
-    let file_content = hash::read_object(file_hash)?;
-    fs::write(&abs_path, file_content)
+/// 读取文件对象内容并写入工作区
+fn write_file_entry(repo: &Repository, entry: &IndexEntry) -> RustGitResult<()> {
+    let abs_path = repo.root().join(&entry.path);
+    if let Some(parent) = abs_path.parent()
+        && !parent.exists()
+    {
+        fs::create_dir_all(parent)
+            .context(format!("创建目录 {} 失败", parent.display()))?;
+    }
+    fs::write(&abs_path, eol::read_blob_for_work_tree(&entry.hash)?)
         .context(format!("恢复文件 {} 失败", abs_path.display()))?;
+    metadata::apply_file_mode(&abs_path, entry.mode)?;
 
     println!("已恢复文件：{}", abs_path.display());
     Ok(())
 }
 
-/// 从提交恢复工作区（简化版）
-fn restore_working_dir(commit_id: &str) -> RustGitResult<()> {
+/// 从提交恢复工作区（简化版），并将暂存区同步为该提交的目录树
+pub fn restore_working_dir(repo: &Repository, commit_id: &str) -> RustGitResult<()> {
     // 读取提交对象
     let commit_content = hash::read_object(commit_id)?;
     let tree_hash = hash::parse_commit(&commit_content)?;
@@ -119,7 +210,13 @@ fn restore_working_dir(commit_id: &str) -> RustGitResult<()> {
         return Err(anyhow::anyhow!("目录树格式错误"));
     };
 
-    // 遍历所有文件条目，恢复到工作区
+    // 写入前确认全部文件对象都存在，避免只恢复一部分
+    if let Value::Array(entries) = &index_array {
+        hash::ensure_objects_exist(entries.iter().filter_map(|entry| entry["hash"].as_str()))?;
+    }
+
+    // 遍历所有文件条目，恢复到仓库根目录下的对应位置
+    let repo_root = repo.root();
     if let Value::Array(entries) = &index_array {
         for entry in entries {
             let rel_path = entry["path"].as_str()
@@ -129,24 +226,78 @@ fn restore_working_dir(commit_id: &str) -> RustGitResult<()> {
             let abs_path = repo_root.join(rel_path);
 
             // 创建父目录
-            if let Some(parent) = abs_path.parent() {
-                if !parent.exists() {
-                    fs::create_dir_all(parent)
-                        .context(format!("创建目录 {} 失败", parent.display()))?;
-                }
+            if let Some(parent) = abs_path.parent()
+                && !parent.exists()
+            {
+                fs::create_dir_all(parent)
+                    .context(format!("创建目录 {} 失败", parent.display()))?;
             }
 
             // 写入文件内容
-            let file_content = hash::read_object(file_hash)?;
+            let file_content = eol::read_blob_for_work_tree(file_hash)?;
             fs::write(&abs_path, file_content)
                 .context(format!("恢复文件 {} 失败", abs_path.display()))?;
+            apply_entry_mode(&abs_path, entry)?;
         }
     }
 
+    // 目录树与暂存区格式相同，直接写回暂存区
+    let index: Vec<IndexEntry> = serde_json::from_value(index_array)
+        .context("解析目录树条目失败")?;
+    repo.write_index(&index)?;
+
     Ok(())
 }
 
-// 补充 repo_root 变量（函数内使用）
-lazy_static::lazy_static! {
-    static ref repo_root: std::path::PathBuf = std::env::current_dir().unwrap();
-}
\ No newline at end of file
+/// 切换前检查：目标提交与当前不同且存在未提交的修改时中止，避免覆盖工作区
+fn ensure_no_uncommitted_changes(
+    repo: &Repository,
+    old_commit_id: Option<&str>,
+    new_commit_id: &str,
+    force: bool,
+) -> RustGitResult<()> {
+    if force || old_commit_id == Some(new_commit_id) {
+        return Ok(());
+    }
+
+    let changed = uncommitted_changes(repo)?;
+    if changed.is_empty() {
+        return Ok(());
+    }
+    let list: Vec<String> = changed.iter().map(|path| format!("  {}", path)).collect();
+    Err(anyhow::anyhow!(
+        "以下文件有未提交的修改，切换将覆盖它们，请先提交或储藏（commit or stash your changes）：\n{}",
+        list.join("\n")
+    ))
+}
+
+/// 列出尚未提交的已跟踪文件：工作区与暂存区不一致，或暂存区与 HEAD 不一致（切换提交会覆盖这些修改）
+pub fn uncommitted_changes(repo: &Repository) -> RustGitResult<Vec<String>> {
+    let head_tree = hash::read_head_tree()?;
+    let index = repo.index()?;
+
+    let mut changed = BTreeSet::new();
+    for entry in &index {
+        let abs_path = repo.root().join(&entry.path);
+        let working_changed = !abs_path.is_file() || !entry.matches_file(&abs_path)?;
+        let staged_changed = head_tree.get(&entry.path) != Some(&entry.hash);
+        if working_changed || staged_changed {
+            changed.insert(entry.path.clone());
+        }
+    }
+    // 已暂存的删除
+    for path in head_tree.keys() {
+        if !index.iter().any(|entry| &entry.path == path) {
+            changed.insert(path.clone());
+        }
+    }
+
+    Ok(changed.into_iter().collect())
+}
+
+/// 按目录树条目中记录的模式设置文件权限（旧版条目无模式时视为普通文件）
+fn apply_entry_mode(abs_path: &std::path::Path, entry: &Value) -> RustGitResult<()> {
+    let mode = entry["mode"].as_u64()
+        .map_or(metadata::MODE_REGULAR, |mode| mode as u32);
+    metadata::apply_file_mode(abs_path, mode)
+}
@@ -0,0 +1,57 @@
+use anyhow::Context;
+use crate::commands::{checkout, revert};
+use crate::utils::{fs as utils_fs, hash, metadata};
+use crate::utils::repository::Repository;
+use crate::RustGitResult;
+
+/// 实现 git cherry-pick 核心逻辑（将单个提交引入的变更重放到当前分支，沿用原提交信息和作者）
+pub fn cherry_pick(repo: &Repository, target: &str) -> RustGitResult<()> {
+    // 裸仓库没有工作区
+    repo.ensure_work_tree()?;
+
+    // 持有暂存区锁直到命令结束，防止并发进程交错读写暂存区
+    let _lock = utils_fs::IndexLock::acquire(repo)?;
+
+    let head = repo.head()?;
+    let commit_id = utils_fs::resolve_rev(target)?;
+    let commit = hash::read_commit(&commit_id)?;
+    if commit.parents.len() > 1 {
+        return Err(anyhow::anyhow!("提交 {} 是合并提交，暂不支持拣选合并提交", &commit_id[..7]));
+    }
+
+    // 重放会修改工作区和暂存区，要求当前没有未提交的修改
+    let changed = checkout::uncommitted_changes(repo)?;
+    if !changed.is_empty() {
+        let list: Vec<String> = changed.iter().map(|path| format!("  {}", path)).collect();
+        return Err(anyhow::anyhow!(
+            "以下文件有未提交的修改，请先提交或储藏（commit or stash your changes）：\n{}",
+            list.join("\n")
+        ));
+    }
+
+    // 正向应用：从父提交的目录树变为目标提交的目录树
+    let parent_entries = match commit.first_parent() {
+        Some(parent) => hash::read_tree_entries(&hash::read_commit(parent)?.tree_hash)?,
+        None => Vec::new(),
+    };
+    let commit_entries = hash::read_tree_entries(&commit.tree_hash)?;
+    let short_id = &commit_id[..7];
+    revert::apply_tree_diff(repo, &parent_entries, &commit_entries)
+        .context(format!("无法拣选提交 {}", short_id))?;
+
+    let new_commit = metadata::create_commit(&commit.message, Some(&commit.author), None)?;
+    metadata::save_commit(&new_commit)?;
+
+    let current_branch = repo.current_branch()?;
+    metadata::update_branch_commit(&current_branch, &new_commit.id)?;
+    let subject = commit.message.lines().next().unwrap_or("");
+    metadata::append_reflog(
+        head.as_deref(),
+        &new_commit.id,
+        &current_branch,
+        &format!("cherry-pick: {}", subject),
+    )?;
+
+    println!("[{} {}] {}", current_branch, &new_commit.id[..7], subject);
+    Ok(())
+}
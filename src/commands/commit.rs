@@ -1,39 +1,242 @@
-use crate::utils::{fs, metadata};
-use crate::RustGitResult;
+use crate::commands::merge;
+use crate::utils::{fs as utils_fs, hash, hooks, metadata};
+use crate::utils::metadata::{CommitDates, IndexEntry};
+use crate::utils::repository::Repository;
+use crate::{RustGitResult, Verbosity};
+use anyhow::Context;
 use chrono::TimeZone;
+use std::collections::BTreeMap;
+use std::fs;
 
-/// 实现 git commit 核心逻辑
-pub fn commit(message: &str) -> RustGitResult<()> {
-    // 检查仓库是否初始化
-    if !fs::is_repo_initialized() {
-        return Err(anyhow::anyhow!("未初始化 rust-git 仓库，请先执行 `rust-git init`"));
+/// 实现 git commit 核心逻辑（paths 非空时只提交这些路径；Quiet 时不输出提交摘要；
+/// 存在未完成的合并时，冲突全部解决后创建以 MERGE_HEAD 为第二父提交的合并提交）
+pub fn commit(
+    repo: &Repository,
+    message: &str,
+    allow_empty: bool,
+    author: Option<&str>,
+    date: Option<&str>,
+    paths: &[String],
+    verbosity: Verbosity,
+) -> RustGitResult<()> {
+    // 裸仓库没有工作区
+    repo.ensure_work_tree()?;
+
+    // 持有暂存区锁直到命令结束，防止并发进程交错读写暂存区
+    let _lock = utils_fs::IndexLock::acquire(repo)?;
+
+    // 提前校验作者和日期格式，避免修改暂存区后才失败
+    if let Some(author) = author {
+        metadata::validate_author(author)?;
     }
+    let date = date.map(metadata::parse_date).transpose()?;
+
+    // pre-commit 钩子：退出码非零时中止提交（此时尚未修改暂存区）
+    hooks::run_hook(repo, "pre-commit", &[]).context("pre-commit 钩子未通过，已中止提交")?;
+
+    // 未完成的合并只能整体提交
+    let merge_head = if merge::merge_in_progress(repo) {
+        if !paths.is_empty() {
+            return Err(anyhow::anyhow!("合并尚未完成，不能只提交部分路径"));
+        }
+        Some(merge::read_merge_head(repo)?)
+    } else {
+        None
+    };
 
     // 检查暂存区是否为空
-    let index = fs::read_index()?;
-    if index.as_array().unwrap().is_empty() {
+    let mut index = repo.index()?;
+    if index.is_empty() && !allow_empty && paths.is_empty() {
         return Err(anyhow::anyhow!("暂存区为空，无内容可提交"));
     }
 
-    // 创建提交对象
-    let commit = metadata::create_commit(message)?;
-    
+    // 工作区中已删除的已跟踪文件视为删除：从本次提交的目录树中移除（指定路径时只处理这些路径下的文件）；
+    // 暂存区在提交创建成功后才写入，提交失败时保持不变
+    let repo_root = repo.root();
+    let selected = paths
+        .iter()
+        .map(|path| repo.relative_path(path))
+        .collect::<Result<Vec<_>, _>>()?;
+    let (deleted, kept): (Vec<_>, Vec<_>) = index.into_iter().partition(|entry| {
+        !repo_root.join(&entry.path).is_file()
+            && (selected.is_empty() || selected.iter().any(|rel_path| path_matches(rel_path, &entry.path)))
+    });
+    index = kept;
+    // 冲突文件必须去除冲突标记并暂存后才能提交合并结果
+    if merge_head.is_some() {
+        merge::ensure_conflicts_resolved(repo, &index)?;
+    }
+    if !deleted.is_empty() {
+        if verbosity != Verbosity::Quiet {
+            for entry in &deleted {
+                println!("删除：{}", entry.path);
+            }
+        }
+        if index.is_empty() && !allow_empty {
+            return Err(anyhow::anyhow!("所有已跟踪文件均已删除，如需提交空目录树请使用 --allow-empty"));
+        }
+    }
+
+    // 检查已暂存文件在工作区中是否又被修改（仍提交暂存区中的版本；未提交的已删除文件跳过）
+    let mut drifted = Vec::new();
+    for entry in &index {
+        let abs_path = repo_root.join(&entry.path);
+        if abs_path.is_file() && !entry.matches_file(&abs_path)? {
+            drifted.push(entry.path.as_str());
+        }
+    }
+    if !drifted.is_empty() {
+        println!("警告：以下文件在暂存后又被修改，本次提交使用暂存区中的版本：");
+        for path in &drifted {
+            println!("  {}", path);
+        }
+    }
+
+    // 创建提交对象（指定路径时只提交这些路径的暂存变更，其余变更保留在暂存区）
+    let tree_entries = if paths.is_empty() {
+        index.clone()
+    } else {
+        select_staged_paths(repo, &index, paths)?
+    };
+
+    // 目录树与父提交相同即没有变更，除非 --allow-empty 否则拒绝提交（合并提交除外）
+    if !allow_empty
+        && merge_head.is_none()
+        && let Some(head) = repo.head()?
+        && metadata::write_tree_from_entries(&tree_entries)? == hash::read_commit(&head)?.tree_hash
+    {
+        return Err(anyhow::anyhow!("没有需要提交的变更（如需创建空提交请使用 --allow-empty）"));
+    }
+    let message = run_commit_msg_hook(repo, message)?;
+    let commit = match &merge_head {
+        Some(merge_head) => {
+            let head = repo.head()?
+                .ok_or_else(|| anyhow::anyhow!("暂无提交记录，无法提交合并结果"))?;
+            metadata::create_commit_with_parents(&tree_entries, &message, author, CommitDates::at(date), &[head, merge_head.clone()])?
+        }
+        None => metadata::create_commit_from_entries(&tree_entries, &message, author, date)?,
+    };
+
     // 保存提交记录
     metadata::save_commit(&commit)?;
 
+    // 更新当前分支指向最新提交（首次提交时分支文件为空，直接覆盖写入）
+    let current_branch = repo.current_branch()?;
+    metadata::update_branch_commit(&current_branch, &commit.id)?;
+    // 提交成功后再从暂存区移除已删除的文件
+    if !deleted.is_empty() {
+        repo.write_index(&index)?;
+    }
+    let subject = commit.message.lines().next().unwrap_or("");
+    metadata::append_reflog(
+        commit.first_parent(),
+        &commit.id,
+        &current_branch,
+        &format!("{}: {}", if merge_head.is_some() { "commit (merge)" } else { "commit" }, subject),
+    )?;
+    if merge_head.is_some() {
+        merge::clear_merge_state(repo)?;
+    }
+
     // 打印提交信息
-    println!("[提交 {}] {}", commit.id, commit.message);
+    if verbosity == Verbosity::Quiet {
+        return Ok(());
+    }
+    println!("[提交 {}] {}", commit.id, subject);
     println!(" 作者: {}", commit.author);
     let time = chrono::Local
         .timestamp_opt(commit.timestamp, 0)
         .single()
-        .unwrap_or_else(|| chrono::Local::now());
+        .unwrap_or_else(chrono::Local::now);
     println!(" 时间: {}", time.format("%Y-%m-%d %H:%M:%S"));
     println!(" 目录树哈希: {}", commit.tree_hash);
 
-    // 更新当前分支指向最新提交
-    let current_branch = crate::utils::fs::get_current_branch()?;
-    metadata::update_branch_commit(&current_branch, &commit.id)?;
-
     Ok(())
-}
\ No newline at end of file
+}
+
+/// 确定提交信息：来自命令行参数或文件（`-` 表示标准输入），去除末尾空白；除非允许，否则拒绝空信息
+pub fn read_message(message: Option<&str>, file: Option<&str>, allow_empty_message: bool) -> RustGitResult<String> {
+    let message = match (message, file) {
+        (Some(message), _) => message.to_string(),
+        (None, Some("-")) => std::io::read_to_string(std::io::stdin())
+            .context("从标准输入读取提交信息失败")?,
+        (None, Some(file)) => fs::read_to_string(file)
+            .context(format!("读取提交信息文件失败：{}", file))?,
+        (None, None) => String::new(),
+    };
+    let message = message.trim_end().to_string();
+    if message.trim().is_empty() && !allow_empty_message {
+        return Err(anyhow::anyhow!("提交信息为空，已中止提交（如需空信息请使用 --allow-empty-message）"));
+    }
+    Ok(message)
+}
+
+/// commit-msg 钩子：将提交信息写入 COMMIT_EDITMSG 并以其路径作为参数调用钩子，钩子可改写该文件
+///
+/// 钩子退出码非零时中止提交；钩子把非空信息改为空时同样中止。
+fn run_commit_msg_hook(repo: &Repository, message: &str) -> RustGitResult<String> {
+    let msg_path = repo.git_dir().join("COMMIT_EDITMSG");
+    fs::write(&msg_path, format!("{}\n", message))
+        .context(format!("写入提交信息文件失败：{}", msg_path.display()))?;
+
+    let msg_arg = msg_path.to_string_lossy();
+    if !hooks::run_hook(repo, "commit-msg", &[&msg_arg]).context("commit-msg 钩子未通过，已中止提交")? {
+        return Ok(message.to_string());
+    }
+
+    let rewritten = fs::read_to_string(&msg_path)
+        .context(format!("读取提交信息文件失败：{}", msg_path.display()))?
+        .trim_end()
+        .to_string();
+    if rewritten.trim().is_empty() && !message.trim().is_empty() {
+        return Err(anyhow::anyhow!("commit-msg 钩子清空了提交信息，已中止提交"));
+    }
+    Ok(rewritten)
+}
+
+/// 以 HEAD 的目录树为基础，只应用指定路径（文件或目录）的暂存变更；路径没有暂存变更时报错
+fn select_staged_paths(repo: &Repository, index: &[IndexEntry], paths: &[String]) -> RustGitResult<Vec<IndexEntry>> {
+    let mut tree: BTreeMap<String, IndexEntry> = BTreeMap::new();
+    if let Some(head) = repo.head()? {
+        for entry in hash::read_tree_entries(&hash::read_commit(&head)?.tree_hash)? {
+            tree.insert(entry.path.clone(), entry);
+        }
+    }
+
+    let mut selected = Vec::new();
+    for path in paths {
+        let rel_path = repo.relative_path(path)?;
+        let matches = |candidate: &str| path_matches(&rel_path, candidate);
+
+        // 暂存区与 HEAD 不一致的匹配路径即为已暂存的变更（新增、修改或删除）
+        let mut changed: Vec<(String, Option<&IndexEntry>)> = index
+            .iter()
+            .filter(|entry| matches(&entry.path))
+            .filter(|entry| tree.get(&entry.path).is_none_or(|head_entry| head_entry.hash != entry.hash || head_entry.mode != entry.mode))
+            .map(|entry| (entry.path.clone(), Some(entry)))
+            .collect();
+        changed.extend(
+            tree.keys()
+                .filter(|head_path| matches(head_path))
+                .filter(|head_path| !index.iter().any(|entry| &entry.path == *head_path))
+                .map(|head_path| (head_path.clone(), None)),
+        );
+        if changed.is_empty() {
+            return Err(anyhow::anyhow!("路径 {} 没有已暂存的变更", path));
+        }
+        selected.extend(changed.into_iter().map(|(path, entry)| (path, entry.cloned())));
+    }
+
+    for (path, entry) in selected {
+        match entry {
+            Some(entry) => tree.insert(path, entry),
+            None => tree.remove(&path),
+        };
+    }
+    Ok(tree.into_values().collect())
+}
+
+/// candidate 是否为 rel_path 本身或位于 rel_path 目录下
+fn path_matches(rel_path: &str, candidate: &str) -> bool {
+    candidate == rel_path || candidate.starts_with(&format!("{}/", rel_path))
+}
@@ -0,0 +1,34 @@
+use crate::utils::{config, fs};
+use crate::RustGitResult;
+
+/// 实现 git config 核心逻辑（list 时列出全部配置；给定 value 时设置，否则打印）
+pub fn config(key: Option<&str>, value: Option<&str>, list: bool) -> RustGitResult<()> {
+    // 检查仓库是否初始化
+    if !fs::is_repo_initialized() {
+        return Err(anyhow::anyhow!("未初始化 rust-git 仓库，请先执行 `rust-git init`"));
+    }
+
+    // --list：按键名排序输出 key=value
+    if list {
+        for (key, value) in config::read_config()? {
+            println!("{}={}", key, value);
+        }
+        return Ok(());
+    }
+
+    let key = key.ok_or_else(|| anyhow::anyhow!("请指定配置项名称（或使用 --list）"))?;
+    // 配置项格式：section.name
+    if !key.contains('.') || key.starts_with('.') || key.ends_with('.') {
+        return Err(anyhow::anyhow!("配置项名称不合法：{}（格式应为 section.name）", key));
+    }
+
+    match value {
+        Some(value) => config::set(key, value)?,
+        None => match config::get(key)? {
+            Some(value) => println!("{}", value),
+            None => return Err(anyhow::anyhow!("配置项 {} 未设置", key)),
+        },
+    }
+
+    Ok(())
+}
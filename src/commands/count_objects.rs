@@ -0,0 +1,57 @@
+use anyhow::Context;
+use crate::utils::fs as utils_fs;
+use crate::utils::hash;
+use crate::RustGitResult;
+use std::collections::BTreeMap;
+use std::fs;
+
+/// 无法从对象头判断类型的对象（旧版无对象头或无法读取）
+const UNKNOWN_TYPE: &str = "unknown";
+
+/// count-objects 的统计结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ObjectCounts {
+    pub count: usize,                                  // 松散对象数
+    pub size: u64,                                     // 松散对象占用的磁盘字节数（压缩后）
+    pub by_type: BTreeMap<&'static str, (usize, u64)>, // 按对象类型统计的对象数和字节数
+}
+
+/// 实现 git count-objects 核心逻辑（统计松散对象数及磁盘占用；verbose 时按对象类型分别统计）
+pub fn count_objects(verbose: bool) -> RustGitResult<()> {
+    // 检查仓库是否初始化
+    if !utils_fs::is_repo_initialized() {
+        return Err(anyhow::anyhow!("未初始化 rust-git 仓库，请先执行 `rust-git init`"));
+    }
+
+    let counts = collect_counts()?;
+    println!("count: {}, size: {} bytes", counts.count, counts.size);
+    if verbose {
+        for (obj_type, (count, size)) in &counts.by_type {
+            println!("{}: {}, size: {} bytes", obj_type, count, size);
+        }
+    }
+    Ok(())
+}
+
+/// 遍历对象目录，统计全部松散对象
+pub fn collect_counts() -> RustGitResult<ObjectCounts> {
+    let mut counts = ObjectCounts::default();
+    for object_id in hash::list_objects()? {
+        let obj_path = hash::object_path(&object_id)?;
+        let size = fs::metadata(&obj_path)
+            .context(format!("读取对象文件信息失败：{}", obj_path.display()))?
+            .len();
+
+        // 类型取自对象头（只解压对象头），无对象头或无法读取的对象归为 unknown
+        let obj_type = match hash::read_object_type(&object_id) {
+            Ok(Some(obj_type)) => obj_type.as_str(),
+            _ => UNKNOWN_TYPE,
+        };
+        let entry = counts.by_type.entry(obj_type).or_default();
+        entry.0 += 1;
+        entry.1 += size;
+        counts.count += 1;
+        counts.size += size;
+    }
+    Ok(counts)
+}
@@ -0,0 +1,45 @@
+use crate::utils::{fs, hash};
+use crate::RustGitResult;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// 实现 git describe 核心逻辑（查找从修订可达的最近标签）
+///
+/// 修订本身有标签时输出 `<tag>`，否则输出 `<tag>-<n>-g<短哈希>`，n 为距离该标签的提交数。
+pub fn describe(rev: Option<&str>) -> RustGitResult<()> {
+    // 检查仓库是否初始化
+    if !fs::is_repo_initialized() {
+        return Err(anyhow::anyhow!("未初始化 rust-git 仓库，请先执行 `rust-git init`"));
+    }
+
+    let commit_id = fs::resolve_rev(rev.unwrap_or("HEAD"))?;
+
+    // 提交哈希 -> 标签名（同一提交有多个标签时取名称最小的）
+    let mut tagged: HashMap<String, String> = HashMap::new();
+    for tag in fs::list_tags()? {
+        let tag_commit = fs::read_tag_commit(&tag)?;
+        tagged.entry(tag_commit).or_insert(tag);
+    }
+
+    // 按距离由近及远广度优先遍历父提交
+    let mut visited = HashSet::new();
+    let mut pending = VecDeque::from([(commit_id.clone(), 0usize)]);
+    while let Some((current, distance)) = pending.pop_front() {
+        if !visited.insert(current.clone()) {
+            continue;
+        }
+        if let Some(tag) = tagged.get(&current) {
+            if distance == 0 {
+                println!("{}", tag);
+            } else {
+                println!("{}-{}-g{}", tag, distance, &commit_id[..7]);
+            }
+            return Ok(());
+        }
+        let content = hash::read_object(&current)?;
+        for parent in hash::parse_parents(&content) {
+            pending.push_back((parent, distance + 1));
+        }
+    }
+
+    Err(anyhow::anyhow!("no tags found（从 {} 出发找不到任何标签）", &commit_id[..7]))
+}
@@ -0,0 +1,285 @@
+use crate::utils::fs as utils_fs;
+use crate::utils::{color, eol, hash};
+use crate::RustGitResult;
+use similar::{ChangeTag, TextDiff};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+
+/// 单个文件的变更：路径、旧内容、新内容
+pub type FileChange = (String, Vec<u8>, Vec<u8>);
+
+/// --stat 图示（+/-）的最大宽度
+const STAT_GRAPH_WIDTH: usize = 40;
+
+/// 实现 git diff 核心逻辑（暂存区 vs 工作区，或两个修订之间）
+pub fn diff(a: Option<&str>, b: Option<&str>, stat: bool) -> RustGitResult<()> {
+    // 检查仓库是否初始化
+    if !utils_fs::is_repo_initialized() {
+        return Err(anyhow::anyhow!("未初始化 rust-git 仓库，请先执行 `rust-git init`"));
+    }
+
+    // 指定两个修订时比较它们的目录树，否则比较暂存区与工作区
+    let changes = match (a, b) {
+        (Some(a), Some(b)) => revision_changes(a, b)?,
+        _ => {
+            // 裸仓库没有工作区
+            utils_fs::ensure_work_tree()?;
+            working_changes()?
+        }
+    };
+
+    if stat {
+        print!("{}", colorize_stat(&render_stat(&changes)));
+    } else {
+        for (path, old_content, new_content) in &changes {
+            print!("{}", colorize_diff(&render_file_diff(path, old_content, new_content)));
+        }
+    }
+
+    Ok(())
+}
+
+/// 收集暂存区与工作区之间内容不同的文件（路径、暂存区版本、工作区版本）
+fn working_changes() -> RustGitResult<Vec<FileChange>> {
+    let repo_root = utils_fs::get_repo_root()?;
+    let index = utils_fs::read_index()?;
+    let entries = match index.as_array() {
+        Some(entries) => entries,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut changes = Vec::new();
+    for entry in entries {
+        let rel_path = entry["path"].as_str()
+            .ok_or_else(|| anyhow::anyhow!("文件路径格式错误"))?;
+        let file_hash = entry["hash"].as_str()
+            .ok_or_else(|| anyhow::anyhow!("文件哈希格式错误"))?;
+
+        // 暂存区中的版本
+        let old_content = hash::read_object(file_hash)?;
+        // 工作区中的版本（文件已删除时视为空）
+        let abs_path = repo_root.join(rel_path);
+        let new_content = if abs_path.is_file() {
+            eol::to_repo(fs::read(&abs_path)?)?
+        } else {
+            Vec::new()
+        };
+
+        if old_content != new_content {
+            changes.push((rel_path.to_string(), old_content, new_content));
+        }
+    }
+
+    Ok(changes)
+}
+
+/// 收集两个修订（HEAD / 分支名 / 标签名 / 哈希 / HEAD~n）目录树之间的变更
+fn revision_changes(a: &str, b: &str) -> RustGitResult<Vec<FileChange>> {
+    let old_tree = hash::read_commit_tree(&utils_fs::resolve_rev(a)?)?;
+    let new_tree = hash::read_commit_tree(&utils_fs::resolve_rev(b)?)?;
+    tree_changes(&old_tree, &new_tree)
+}
+
+/// 两棵目录树（路径 -> 哈希）之间发生变化的文件路径（新增、删除或内容改变），按路径排序
+pub fn changed_paths(
+    old_tree: &BTreeMap<String, String>,
+    new_tree: &BTreeMap<String, String>,
+) -> Vec<String> {
+    let paths: BTreeSet<&String> = old_tree.keys().chain(new_tree.keys()).collect();
+    paths
+        .into_iter()
+        .filter(|path| old_tree.get(*path) != new_tree.get(*path))
+        .cloned()
+        .collect()
+}
+
+/// 收集两棵目录树之间发生变化的文件（包括新增和删除的文件，不存在的一侧视为空内容）
+fn tree_changes(
+    old_tree: &BTreeMap<String, String>,
+    new_tree: &BTreeMap<String, String>,
+) -> RustGitResult<Vec<FileChange>> {
+    let mut changes = Vec::new();
+    for path in changed_paths(old_tree, new_tree) {
+        let old_hash = old_tree.get(&path);
+        let new_hash = new_tree.get(&path);
+        let old_content = match old_hash {
+            Some(file_hash) => hash::read_object(file_hash)?,
+            None => Vec::new(),
+        };
+        let new_content = match new_hash {
+            Some(file_hash) => hash::read_object(file_hash)?,
+            None => Vec::new(),
+        };
+        changes.push((path, old_content, new_content));
+    }
+
+    Ok(changes)
+}
+
+/// 生成两棵目录树之间的差异（包括新增和删除的文件）
+pub fn render_tree_diff(
+    old_tree: &BTreeMap<String, String>,
+    new_tree: &BTreeMap<String, String>,
+) -> RustGitResult<String> {
+    let mut output = String::new();
+    for (path, old_content, new_content) in tree_changes(old_tree, new_tree)? {
+        output.push_str(&render_file_diff(&path, &old_content, &new_content));
+    }
+    Ok(output)
+}
+
+/// 生成 --stat 摘要：每个文件一行增删统计，末尾一行汇总
+pub fn render_stat(changes: &[FileChange]) -> String {
+    // 统计每个文件的新增/删除行数（二进制文件记为 None）
+    let stats: Vec<(&str, Option<(usize, usize)>)> = changes
+        .iter()
+        .filter(|(_, old_content, new_content)| old_content != new_content)
+        .map(|(path, old_content, new_content)| (path.as_str(), count_changes(old_content, new_content)))
+        .collect();
+    if stats.is_empty() {
+        return String::new();
+    }
+
+    let name_width = stats.iter().map(|(path, _)| path.len()).max().unwrap_or(0);
+    let max_changed = stats
+        .iter()
+        .filter_map(|(_, counts)| counts.map(|(insertions, deletions)| insertions + deletions))
+        .max()
+        .unwrap_or(0);
+    let count_width = max_changed.to_string().len();
+
+    let mut output = String::new();
+    let (mut total_insertions, mut total_deletions) = (0, 0);
+    for (path, counts) in &stats {
+        match counts {
+            Some((insertions, deletions)) => {
+                total_insertions += insertions;
+                total_deletions += deletions;
+                // 变更行数过多时按比例缩放图示宽度
+                let (plus, minus) = if max_changed > STAT_GRAPH_WIDTH {
+                    (
+                        insertions * STAT_GRAPH_WIDTH / max_changed,
+                        deletions * STAT_GRAPH_WIDTH / max_changed,
+                    )
+                } else {
+                    (*insertions, *deletions)
+                };
+                output.push_str(&format!(
+                    " {:<name_width$} | {:>count_width$} {}{}\n",
+                    path,
+                    insertions + deletions,
+                    "+".repeat(plus),
+                    "-".repeat(minus),
+                ));
+            }
+            None => output.push_str(&format!(" {:<name_width$} | Bin\n", path)),
+        }
+    }
+
+    output.push_str(&format!(" {}\n", stat_summary(stats.len(), total_insertions, total_deletions)));
+    output
+}
+
+/// 生成汇总行，如 `2 files changed, 5 insertions(+), 1 deletion(-)`（数量为 0 的部分省略）
+fn stat_summary(files: usize, insertions: usize, deletions: usize) -> String {
+    let plural = |count: usize| if count == 1 { "" } else { "s" };
+    let mut summary = format!("{} file{} changed", files, plural(files));
+    if insertions > 0 {
+        summary.push_str(&format!(", {} insertion{}(+)", insertions, plural(insertions)));
+    }
+    if deletions > 0 {
+        summary.push_str(&format!(", {} deletion{}(-)", deletions, plural(deletions)));
+    }
+    summary
+}
+
+/// 根据 similar 的逐行差异统计新增和删除的行数（非 UTF-8 内容返回 None）
+fn count_changes(old_content: &[u8], new_content: &[u8]) -> Option<(usize, usize)> {
+    let old_text = std::str::from_utf8(old_content).ok()?;
+    let new_text = std::str::from_utf8(new_content).ok()?;
+
+    let text_diff = TextDiff::from_lines(old_text, new_text);
+    let (mut insertions, mut deletions) = (0, 0);
+    for change in text_diff.iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Insert => insertions += 1,
+            ChangeTag::Delete => deletions += 1,
+            ChangeTag::Equal => {}
+        }
+    }
+    Some((insertions, deletions))
+}
+
+/// 生成单个文件的统一格式差异（内容相同时返回空字符串）
+pub fn render_file_diff(path: &str, old_content: &[u8], new_content: &[u8]) -> String {
+    if old_content == new_content {
+        return String::new();
+    }
+
+    let mut output = format!("diff --git a/{} b/{}\n", path, path);
+
+    // 非 UTF-8 内容视为二进制文件
+    let (old_text, new_text) = match (std::str::from_utf8(old_content), std::str::from_utf8(new_content)) {
+        (Ok(old_text), Ok(new_text)) => (old_text, new_text),
+        _ => {
+            output.push_str(&format!("Binary files a/{} and b/{} differ\n", path, path));
+            return output;
+        }
+    };
+
+    let text_diff = TextDiff::from_lines(old_text, new_text);
+    let unified = text_diff
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("a/{}", path), &format!("b/{}", path))
+        .missing_newline_hint(false)
+        .to_string();
+    output.push_str(&unified);
+    if !output.ends_with('\n') {
+        output.push('\n');
+    }
+    output
+}
+
+/// 为统一格式差异着色：文件头粗体，块头青色，新增行绿色，删除行红色
+pub fn colorize_diff(diff: &str) -> String {
+    if !color::enabled() {
+        return diff.to_string();
+    }
+    let mut output = String::new();
+    for line in diff.lines() {
+        let colored = if line.starts_with("diff --git") || line.starts_with("+++") || line.starts_with("---") {
+            color::bold(line)
+        } else if line.starts_with("@@") {
+            color::cyan(line)
+        } else if line.starts_with('+') {
+            color::green(line)
+        } else if line.starts_with('-') {
+            color::red(line)
+        } else {
+            line.to_string()
+        };
+        output.push_str(&colored);
+        output.push('\n');
+    }
+    output
+}
+
+/// 为 --stat 摘要的图示着色：`+` 绿色，`-` 红色
+fn colorize_stat(stat: &str) -> String {
+    if !color::enabled() {
+        return stat.to_string();
+    }
+    let mut output = String::new();
+    for line in stat.lines() {
+        match line.rsplit_once(' ') {
+            Some((prefix, graph)) if line.contains(" | ") && !graph.is_empty() && graph.chars().all(|c| c == '+' || c == '-') => {
+                let plus = graph.chars().filter(|&c| c == '+').count();
+                output.push_str(&format!("{} {}{}", prefix, color::green(&graph[..plus]), color::red(&graph[plus..])));
+            }
+            _ => output.push_str(line),
+        }
+        output.push('\n');
+    }
+    output
+}
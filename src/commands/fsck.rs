@@ -0,0 +1,82 @@
+use crate::utils::fs as utils_fs;
+use crate::utils::hash::{self, ObjectType};
+use crate::RustGitResult;
+use sha1::{Digest, Sha1};
+
+/// fsck 的检查结果统计
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FsckStats {
+    pub checked: usize,        // 检查的对象数
+    pub corrupt: usize,        // 无法读取或内容哈希不一致的对象数
+    pub dangling: usize,       // 引用了不存在对象的悬空引用数
+    pub problems: Vec<String>, // 每个问题的说明
+}
+
+/// 实现 git fsck 核心逻辑（校验对象内容哈希及提交/目录树引用；verbose 时输出统计）
+pub fn fsck(verbose: bool) -> RustGitResult<()> {
+    // 检查仓库是否初始化
+    if !utils_fs::is_repo_initialized() {
+        return Err(anyhow::anyhow!("未初始化 rust-git 仓库，请先执行 `rust-git init`"));
+    }
+
+    let stats = check_objects()?;
+    for problem in &stats.problems {
+        println!("{}", problem);
+    }
+    if verbose {
+        println!(
+            "检查对象 {} 个，损坏 {} 个，悬空引用 {} 个",
+            stats.checked, stats.corrupt, stats.dangling
+        );
+    }
+
+    if !stats.problems.is_empty() {
+        return Err(anyhow::anyhow!("发现 {} 个问题", stats.problems.len()));
+    }
+    println!("对象检查完成，未发现问题");
+    Ok(())
+}
+
+/// 校验全部对象，返回统计结果
+pub fn check_objects() -> RustGitResult<FsckStats> {
+    let mut stats = FsckStats::default();
+    for object_id in hash::list_objects()? {
+        stats.checked += 1;
+
+        // 1. 内容哈希必须与文件名一致
+        let data = match hash::read_object_raw(&object_id) {
+            Ok(data) => data,
+            Err(_) => {
+                stats.problems.push(format!("无法读取对象：{}", object_id));
+                stats.corrupt += 1;
+                continue;
+            }
+        };
+        let mut hasher = Sha1::new();
+        hasher.update(&data);
+        if format!("{:x}", hasher.finalize()) != object_id {
+            stats.problems.push(format!("损坏的对象：{}", object_id));
+            stats.corrupt += 1;
+            continue;
+        }
+
+        // 2. 提交和目录树引用的对象必须存在
+        let (obj_type, content) = hash::read_object_with_type(&object_id)?;
+        for (kind, referenced) in hash::referenced_objects(obj_type, &content) {
+            if !hash::object_exists(&referenced) {
+                stats.problems.push(format!(
+                    "悬空引用：{} {} 引用的 {} {} 不存在",
+                    type_name(obj_type), object_id, kind, referenced
+                ));
+                stats.dangling += 1;
+            }
+        }
+    }
+
+    Ok(stats)
+}
+
+/// 对象类型名（旧版无对象头的对象为 unknown）
+fn type_name(obj_type: Option<ObjectType>) -> &'static str {
+    obj_type.map_or("unknown", |t| t.as_str())
+}
@@ -0,0 +1,153 @@
+use anyhow::Context;
+use crate::commands::{merge, stash};
+use crate::utils::fs as utils_fs;
+use crate::utils::hash;
+use crate::utils::repository::Repository;
+use crate::RustGitResult;
+use std::collections::HashSet;
+use std::fs;
+
+/// gc 的执行结果统计
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GcStats {
+    pub scanned: usize,        // 扫描的对象数
+    pub reachable: usize,      // 可达（保留）的对象数
+    pub removed: Vec<String>,  // 删除（dry_run 时为将删除）的不可达对象
+    pub bytes_reclaimed: u64,  // 回收（dry_run 时为可回收）的磁盘字节数
+    pub pruned_dirs: usize,    // 删除的空对象子目录数（dry_run 时不删除）
+}
+
+/// 实现 git gc 核心逻辑（删除不可达对象并清理空对象子目录；dry_run 时只列出；verbose 时输出统计）
+pub fn gc(dry_run: bool, verbose: bool) -> RustGitResult<()> {
+    // 检查仓库是否初始化
+    if !utils_fs::is_repo_initialized() {
+        return Err(anyhow::anyhow!("未初始化 rust-git 仓库，请先执行 `rust-git init`"));
+    }
+
+    let stats = collect_garbage(dry_run)?;
+    for object_id in &stats.removed {
+        if dry_run {
+            println!("将删除：{}", object_id);
+        } else {
+            println!("已删除：{}", object_id);
+        }
+    }
+
+    if dry_run {
+        println!("共 {} 个不可达对象", stats.removed.len());
+    } else {
+        println!("共删除 {} 个不可达对象", stats.removed.len());
+    }
+    if verbose {
+        println!(
+            "扫描对象 {} 个，可达 {} 个，{} {} 个，{}回收 {} 字节",
+            stats.scanned,
+            stats.reachable,
+            if dry_run { "可删除" } else { "已删除" },
+            stats.removed.len(),
+            if dry_run { "可" } else { "已" },
+            stats.bytes_reclaimed,
+        );
+        if !dry_run {
+            println!("清理空对象目录 {} 个", stats.pruned_dirs);
+        }
+    }
+    Ok(())
+}
+
+/// 删除不可达对象（dry_run 时只统计不删除），返回统计结果
+pub fn collect_garbage(dry_run: bool) -> RustGitResult<GcStats> {
+    let reachable = collect_reachable()?;
+
+    let mut stats = GcStats::default();
+    for object_id in hash::list_objects()? {
+        stats.scanned += 1;
+        if reachable.contains(&object_id) {
+            stats.reachable += 1;
+            continue;
+        }
+        let obj_path = hash::object_path(&object_id)?;
+        stats.bytes_reclaimed += fs::metadata(&obj_path)
+            .context(format!("读取对象元数据失败：{}", obj_path.display()))?
+            .len();
+        if !dry_run {
+            fs::remove_file(&obj_path)
+                .context(format!("删除对象失败：{}", obj_path.display()))?;
+        }
+        stats.removed.push(object_id);
+    }
+    if !dry_run {
+        stats.pruned_dirs = prune_empty_object_dirs()?;
+    }
+
+    Ok(stats)
+}
+
+/// 删除对象目录下已空的两位十六进制子目录，返回删除的目录数（不删除 objects 目录本身及其他文件）
+pub fn prune_empty_object_dirs() -> RustGitResult<usize> {
+    let objects_dir = Repository::open()?.objects_dir();
+    if !objects_dir.is_dir() {
+        return Ok(0);
+    }
+
+    let mut pruned = 0;
+    for dir_entry in fs::read_dir(&objects_dir).context("读取对象目录失败")? {
+        let dir_entry = dir_entry?;
+        let dir_name = dir_entry.file_name().to_string_lossy().to_string();
+        if !dir_entry.file_type()?.is_dir()
+            || dir_name.len() != 2
+            || !dir_name.chars().all(|c| c.is_ascii_hexdigit())
+        {
+            continue;
+        }
+        let dir_path = dir_entry.path();
+        let is_empty = fs::read_dir(&dir_path)
+            .context(format!("读取对象目录失败：{}", dir_path.display()))?
+            .next()
+            .is_none();
+        if is_empty {
+            fs::remove_dir(&dir_path)
+                .context(format!("删除空对象目录失败：{}", dir_path.display()))?;
+            pruned += 1;
+        }
+    }
+    Ok(pruned)
+}
+
+/// 从所有分支、标签、HEAD、暂存区及储藏出发，收集可达对象（提交 -> 父提交/目录树 -> 子目录树/文件）
+fn collect_reachable() -> RustGitResult<HashSet<String>> {
+    let mut pending = Vec::new();
+    for branch in utils_fs::list_branches()? {
+        pending.push(utils_fs::read_branch_commit(&branch).unwrap_or_default());
+    }
+    for tag in utils_fs::list_tags()? {
+        // 附注标签的标签对象本身同样需要保留
+        pending.push(utils_fs::read_tag_ref(&tag)?);
+    }
+    // 分离 HEAD 指向的提交和已暂存但未提交的文件同样需要保留
+    if let Some(head) = utils_fs::resolve_head()? {
+        pending.push(head);
+    }
+    for entry in utils_fs::read_index_typed()? {
+        pending.push(entry.hash);
+    }
+    // 储藏栈中的提交，以及未完成合并中被合并的提交（MERGE_HEAD）
+    let repo = Repository::open()?;
+    pending.extend(stash::read_stash_stack(&repo)?);
+    if merge::merge_in_progress(&repo) {
+        pending.push(merge::read_merge_head(&repo)?);
+    }
+
+    let mut reachable = HashSet::new();
+    while let Some(object_id) = pending.pop() {
+        if object_id.is_empty() || !hash::object_exists(&object_id) || !reachable.insert(object_id.clone()) {
+            continue;
+        }
+        let (obj_type, content) = hash::read_object_with_type(&object_id)?;
+        for (_, referenced) in hash::referenced_objects(obj_type, &content) {
+            pending.push(referenced);
+        }
+    }
+
+    Ok(reachable)
+}
@@ -1,17 +1,48 @@
+use anyhow::Context;
 use crate::utils::fs;
 use crate::RustGitResult;
+use std::path::PathBuf;
+
+/// 实现 git init 核心逻辑（未指定路径时在当前目录初始化；bare 时创建无工作区的裸仓库；
+/// initial_branch 为 HEAD 指向的初始分支名，默认 master）
+pub fn init(path: Option<&str>, bare: bool, initial_branch: Option<&str>) -> RustGitResult<()> {
+    // 先校验初始分支名，避免创建出不完整的仓库
+    let initial_branch = initial_branch.unwrap_or(fs::DEFAULT_BRANCH);
+    fs::validate_ref_name(initial_branch)?;
+
+    // 目标目录不存在时先创建
+    let base = match path {
+        Some(path) => {
+            let base = PathBuf::from(path);
+            std::fs::create_dir_all(&base)
+                .context(format!("创建目录失败：{}", base.display()))?;
+            base
+        }
+        None => PathBuf::from("."),
+    };
+    let display_path = base.canonicalize()
+        .context(format!("转换为绝对路径失败：{}", base.display()))?;
+
+    // 裸仓库：直接在目标目录下创建对象/引用结构
+    if bare {
+        if base.join("objects").is_dir() && base.join("HEAD").is_file() {
+            println!("重新初始化已存在的 rust-git 裸仓库于：{}", display_path.display());
+            return Ok(());
+        }
+        fs::create_bare_repo_dirs(&base, initial_branch)?;
+        println!("初始化空的 rust-git 裸仓库于：{}", display_path.display());
+        return Ok(());
+    }
 
-/// 实现 git init 核心逻辑
-pub fn init() -> RustGitResult<()> {
     // 检查仓库是否已初始化
-    if fs::is_repo_initialized() {
-        println!("重新初始化已存在的 rust-git 仓库于：{}", std::env::current_dir()?.display());
+    if base.join(".rust-git").exists() {
+        println!("重新初始化已存在的 rust-git 仓库于：{}", display_path.display());
         return Ok(());
     }
 
     // 创建仓库目录结构
-    fs::create_repo_dirs()?;
-    println!("初始化空的 rust-git 仓库于：{}", std::env::current_dir()?.display());
+    fs::create_repo_dirs(&base, initial_branch)?;
+    println!("初始化空的 rust-git 仓库于：{}", display_path.display());
 
     Ok(())
-}
\ No newline at end of file
+}
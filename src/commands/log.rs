@@ -1,24 +1,270 @@
-use crate::utils::metadata;
+use anyhow::Context;
+use chrono::TimeZone;
+use crate::api;
+use crate::commands::diff;
+use crate::utils::{fs, hash, metadata};
+use crate::utils::metadata::Commit;
+use crate::utils::repository::Repository;
 use crate::RustGitResult;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-/// 实现 git log 核心逻辑
-pub fn log() -> RustGitResult<()> {
+/// 实现 git log 核心逻辑（从当前分支最新提交沿父提交链遍历；graph 时绘制 ASCII 提交图；
+/// 指定 path 时只显示该文件/目录内容有变化的提交；name_only 时在每个提交后列出其修改的文件；
+/// since / until 按提交时间（committer，而非作者时间）限定范围，两端均包含）
+pub fn log(
+    oneline: bool,
+    max_count: Option<usize>,
+    graph: bool,
+    path: Option<&str>,
+    name_only: bool,
+    since: Option<&str>,
+    until: Option<&str>,
+) -> RustGitResult<()> {
     // 检查仓库是否初始化
-    if !crate::utils::fs::is_repo_initialized() {
+    if !fs::is_repo_initialized() {
         return Err(anyhow::anyhow!("未初始化 rust-git 仓库，请先执行 `rust-git init`"));
     }
 
-    // 读取所有提交
-    let commits = metadata::read_all_commits()?;
-    if commits.is_empty() {
-        println!("暂无提交记录");
+    // 从当前分支的最新提交开始
+    let head = match fs::resolve_head()? {
+        Some(head) => head,
+        None => {
+            println!("暂无提交记录");
+            return Ok(());
+        }
+    };
+
+    if graph {
+        return log_graph(&head, oneline, max_count);
+    }
+
+    // 沿父提交链遍历，输出数量受 max_count 限制
+    let repo = Repository::open()?;
+    let since = since.map(|date| parse_bound(date, false)).transpose()?;
+    let until = until.map(|date| parse_bound(date, true)).transpose()?;
+    if path.is_none() && since.is_none() && until.is_none() {
+        for commit in api::log(&repo, max_count)? {
+            print_entry(&commit, oneline, name_only)?;
+        }
         return Ok(());
     }
 
-    // 格式化输出
-    for commit in commits {
-        println!("{}", metadata::format_commit(&commit));
+    let rel_path = path.map(|path| repo.relative_path(path)).transpose()?;
+    let mut count = 0;
+    for commit in api::log(&repo, None)? {
+        if max_count.is_some_and(|max_count| count >= max_count) {
+            break;
+        }
+        let in_range = since.is_none_or(|since| commit.committer_timestamp >= since)
+            && until.is_none_or(|until| commit.committer_timestamp <= until);
+        if !in_range {
+            continue;
+        }
+        if let Some(rel_path) = &rel_path
+            && !touches_path(&commit, rel_path)?
+        {
+            continue;
+        }
+        print_entry(&commit, oneline, name_only)?;
+        count += 1;
+    }
+
+    Ok(())
+}
+
+/// 解析 --since / --until 的时间：除 RFC3339 和 Unix 时间戳外还支持 YYYY-MM-DD（本地时间，
+/// 作为上界时取当天最后一秒，使当天的提交都包含在内）
+fn parse_bound(date: &str, end_of_day: bool) -> RustGitResult<i64> {
+    if let Ok(day) = chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d") {
+        let (hour, min, sec) = if end_of_day { (23, 59, 59) } else { (0, 0, 0) };
+        return day
+            .and_hms_opt(hour, min, sec)
+            .and_then(|time| chrono::Local.from_local_datetime(&time).earliest())
+            .map(|time| time.timestamp())
+            .ok_or_else(|| anyhow::anyhow!("日期不合法：{}", date));
+    }
+    metadata::parse_date(date).context("--since / --until 的日期格式不合法（应为 RFC3339、Unix 时间戳或 YYYY-MM-DD）")
+}
+
+/// 判断提交相对其父提交是否修改了 rel_path（文件本身或目录下的任意文件）
+fn touches_path(commit: &Commit, rel_path: &str) -> RustGitResult<bool> {
+    let dir_prefix = format!("{}/", rel_path);
+    Ok(changed_files(commit)?
+        .iter()
+        .any(|path| path == rel_path || path.starts_with(&dir_prefix)))
+}
+
+/// 提交相对其父提交修改的文件（根提交为其全部文件）
+fn changed_files(commit: &Commit) -> RustGitResult<Vec<String>> {
+    let parent_tree = match commit.first_parent() {
+        Some(parent) => hash::read_commit_tree(parent)?,
+        None => BTreeMap::new(),
+    };
+    Ok(diff::changed_paths(&parent_tree, &hash::read_commit_tree(&commit.id)?))
+}
+
+/// 输出单个提交；name_only 时其后逐行列出修改的文件（完整格式下以空行分隔）
+fn print_entry(commit: &Commit, oneline: bool, name_only: bool) -> RustGitResult<()> {
+    println!("{}", format_entry(commit, oneline));
+    if name_only {
+        for path in changed_files(commit)? {
+            println!("{}", path);
+        }
+        if !oneline {
+            println!();
+        }
+    }
+    Ok(())
+}
+
+/// 格式化单个提交（完整格式或单行格式）
+fn format_entry(commit: &Commit, oneline: bool) -> String {
+    if oneline {
+        metadata::format_commit_oneline(commit)
+    } else {
+        metadata::format_commit(commit)
+    }
+}
+
+/// 绘制 ASCII 提交图：每列对应一条待显示的提交线，`*` 为提交节点，`\` 为分叉，`/` 为汇合
+fn log_graph(head: &str, oneline: bool, max_count: Option<usize>) -> RustGitResult<()> {
+    let (commits, parents) = collect_history(head)?;
+    let order = topo_order(head, &commits, &parents);
+
+    let mut columns: Vec<String> = Vec::new();
+    for (count, commit_id) in order.iter().enumerate() {
+        if max_count.is_some_and(|max_count| count >= max_count) {
+            break;
+        }
+
+        // 多条线汇合到同一提交：先画 `/` 合并重复的列
+        let col = match columns.iter().position(|id| id == commit_id) {
+            Some(col) => col,
+            None => {
+                columns.push(commit_id.clone());
+                columns.len() - 1
+            }
+        };
+        while let Some(dup) = columns.iter().rposition(|id| id == commit_id).filter(|&dup| dup != col) {
+            println!("{}", collapse_line(columns.len(), dup));
+            columns.remove(dup);
+        }
+
+        // 提交节点行；完整格式的后续行用竖线延续各列
+        let commit = &commits[commit_id];
+        let commit_parents = &parents[commit_id];
+        let text = format_entry(commit, oneline);
+        let mut lines = text.lines();
+        println!("{} {}", node_line(columns.len(), col, '*'), lines.next().unwrap_or(""));
+        let continuation = node_line(columns.len(), col, if commit_parents.is_empty() { ' ' } else { '|' });
+        for line in lines {
+            println!("{}", format!("{} {}", continuation, line).trim_end());
+        }
+
+        // 用父提交替换当前列；合并提交的其余父提交插入右侧并画 `\`
+        match commit_parents.split_first() {
+            Some((first, rest)) => {
+                columns[col] = first.clone();
+                for (offset, parent) in rest.iter().enumerate() {
+                    println!("{}", branch_line(columns.len(), col + offset));
+                    columns.insert(col + offset + 1, parent.clone());
+                }
+            }
+            None => {
+                columns.remove(col);
+            }
+        }
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// 提交历史：提交哈希 -> 提交，提交哈希 -> 父提交列表
+type History = (HashMap<String, Commit>, HashMap<String, Vec<String>>);
+
+/// 从 head 出发收集所有可达提交及其父提交列表
+fn collect_history(head: &str) -> RustGitResult<History> {
+    let mut commits = HashMap::new();
+    let mut parents = HashMap::new();
+    let mut pending = vec![head.to_string()];
+    while let Some(commit_id) = pending.pop() {
+        if commits.contains_key(&commit_id) {
+            continue;
+        }
+        let commit = hash::read_commit(&commit_id)?;
+        pending.extend(commit.parents.iter().cloned());
+        parents.insert(commit_id.clone(), commit.parents.clone());
+        commits.insert(commit_id, commit);
+    }
+    Ok((commits, parents))
+}
+
+/// 拓扑排序（子提交总在父提交之前），可选提交中优先输出时间最新的
+fn topo_order(
+    head: &str,
+    commits: &HashMap<String, Commit>,
+    parents: &HashMap<String, Vec<String>>,
+) -> Vec<String> {
+    // 统计每个提交尚未输出的子提交数
+    let mut pending_children: HashMap<&str, usize> = HashMap::new();
+    for commit_parents in parents.values() {
+        for parent in commit_parents {
+            *pending_children.entry(parent.as_str()).or_default() += 1;
+        }
+    }
+
+    let mut order = Vec::new();
+    let mut shown = HashSet::new();
+    let mut ready = vec![head.to_string()];
+    while !ready.is_empty() {
+        // 选出时间最新的可输出提交（时间相同时取最后加入的）
+        let next = ready
+            .iter()
+            .enumerate()
+            .max_by_key(|(i, id)| (commits[id.as_str()].timestamp, *i))
+            .map(|(i, _)| i)
+            .unwrap_or(0);
+        let commit_id = ready.remove(next);
+        if !shown.insert(commit_id.clone()) {
+            continue;
+        }
+        for parent in &parents[&commit_id] {
+            let remaining = pending_children.entry(parent.as_str()).or_default();
+            *remaining = remaining.saturating_sub(1);
+            if *remaining == 0 {
+                ready.push(parent.clone());
+            }
+        }
+        order.push(commit_id);
+    }
+    order
+}
+
+/// 节点行前缀：当前列为 mark，其余列为 `|`
+fn node_line(width: usize, col: usize, mark: char) -> String {
+    (0..width)
+        .map(|i| if i == col { mark } else { '|' })
+        .map(String::from)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 汇合行：第 dup 列并入左侧一列，其右侧各列随之左移
+fn collapse_line(width: usize, dup: usize) -> String {
+    let mut line = "| ".repeat(dup).trim_end().to_string();
+    line.push('/');
+    for _ in dup + 1..width {
+        line.push_str(" /");
+    }
+    line
+}
+
+/// 分叉行：第 col 列分出新的一列，其右侧各列随之右移
+fn branch_line(width: usize, col: usize) -> String {
+    let mut line = "| ".repeat(col + 1).trim_end().to_string();
+    line.push('\\');
+    for _ in col + 1..width {
+        line.push_str(" \\");
+    }
+    line
+}
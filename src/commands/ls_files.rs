@@ -0,0 +1,17 @@
+use crate::api;
+use crate::utils::repository::Repository;
+use crate::RustGitResult;
+
+/// 实现 git ls-files 核心逻辑（按路径排序列出暂存区中的文件）
+pub fn ls_files(repo: &Repository, stage: bool) -> RustGitResult<()> {
+    for entry in api::ls_files(repo)? {
+        if stage {
+            // -s：同时输出模式和 blob 哈希
+            println!("{:06o} {}\t{}", entry.mode, entry.hash, entry.path);
+        } else {
+            println!("{}", entry.path);
+        }
+    }
+
+    Ok(())
+}
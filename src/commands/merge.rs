@@ -0,0 +1,369 @@
+use anyhow::Context;
+use crate::commands::checkout;
+use crate::utils::{eol, fs as utils_fs, hash, merge, metadata};
+use crate::utils::hash::ObjectType;
+use crate::utils::metadata::{CommitDates, IndexEntry};
+use crate::utils::repository::Repository;
+use crate::RustGitResult;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+/// 冲突标记行前缀（提交前检查冲突是否已解决）
+const CONFLICT_MARKERS: [&str; 2] = ["<<<<<<< ", ">>>>>>> "];
+
+/// 实现 git merge 核心逻辑（可快进时快进；双方分叉时按最近公共祖先进行三方合并，
+/// 无冲突则创建合并提交，有冲突时在工作区写入冲突标记、记录 MERGE_HEAD 并报告；
+/// abort 时放弃未完成的合并）
+pub fn merge(branch_name: Option<&str>, abort: bool) -> RustGitResult<()> {
+    // 裸仓库没有工作区
+    utils_fs::ensure_work_tree()?;
+
+    // 检查仓库是否初始化
+    if !utils_fs::is_repo_initialized() {
+        return Err(anyhow::anyhow!("未初始化 rust-git 仓库，请先执行 `rust-git init`"));
+    }
+
+    let repo = Repository::open()?;
+    // 持有暂存区锁直到命令结束，防止并发进程交错读写暂存区
+    let _lock = utils_fs::IndexLock::acquire(&repo)?;
+    if abort {
+        return merge_abort(&repo);
+    }
+    let branch_name = branch_name.ok_or_else(|| anyhow::anyhow!("请指定要合并的分支"))?;
+    if merge_in_progress(&repo) {
+        return Err(anyhow::anyhow!("上一次合并尚未完成（存在 MERGE_HEAD），请解决冲突后提交，或使用 merge --abort 中止合并"));
+    }
+
+    let current_branch = utils_fs::get_current_branch()?;
+    if branch_name == current_branch {
+        return Err(anyhow::anyhow!("不能将分支 {} 合并到自身", branch_name));
+    }
+
+    // 读取双方的最新提交
+    let theirs = utils_fs::read_branch_commit(branch_name)?;
+    if theirs.is_empty() {
+        return Err(anyhow::anyhow!("分支 {} 暂无提交记录", branch_name));
+    }
+    let ours = utils_fs::read_branch_commit(&current_branch)?;
+
+    // 目标分支已包含在当前分支中
+    if !ours.is_empty() && hash::is_ancestor(&theirs, &ours)? {
+        println!("已经是最新的");
+        return Ok(());
+    }
+
+    // 快进和三方合并都会修改工作区和暂存区，要求当前没有未提交的修改
+    let changed = checkout::uncommitted_changes(&repo)?;
+    if !changed.is_empty() {
+        let list: Vec<String> = changed.iter().map(|path| format!("  {}", path)).collect();
+        return Err(anyhow::anyhow!(
+            "以下文件有未提交的修改，请先提交或储藏（commit or stash your changes）：\n{}",
+            list.join("\n")
+        ));
+    }
+
+    // 当前分支是目标分支的祖先：快进
+    if ours.is_empty() || hash::is_ancestor(&ours, &theirs)? {
+        // 不覆盖当前分支未跟踪、目标分支中存在的文件
+        let ours_tree = if ours.is_empty() { BTreeMap::new() } else { commit_entries(&ours)? };
+        let theirs_tree = commit_entries(&theirs)?;
+        ensure_untracked_not_overwritten(&repo, &ours_tree, theirs_tree.values())?;
+        checkout::restore_working_dir(&repo, &theirs)?;
+        utils_fs::update_branch(&current_branch, &theirs)?;
+        metadata::append_reflog(
+            Some(&ours),
+            &theirs,
+            &current_branch,
+            &format!("merge {}: Fast-forward", branch_name),
+        )?;
+        println!("快进合并：{} -> {}", &ours[..ours.len().min(7)], &theirs[..7]);
+        return Ok(());
+    }
+
+    // 双方分叉：以最近公共祖先为基准进行三方合并
+    let base = hash::merge_base(&ours, &theirs)?
+        .ok_or_else(|| anyhow::anyhow!("分支 {} 与当前分支没有公共祖先，无法合并", branch_name))?;
+    three_way_merge(&repo, &current_branch, branch_name, [&base, &ours, &theirs])
+}
+
+/// 文件在合并结果中的处理方式
+enum Resolution {
+    Keep,                  // 保留当前版本
+    Take(IndexEntry),      // 采用对方版本（或合并后已存储的版本）
+    Delete,                // 删除
+    Conflict(Vec<u8>),     // 双方内容冲突：工作区写入带冲突标记的内容，暂存区保留当前版本
+    Unmerged(IndexEntry),  // 一方删除、另一方修改：工作区保留修改后的版本，暂存区保留当前状态
+}
+
+/// 三方合并 [base, ours, theirs] 三个提交的目录树，无冲突时创建有两个父提交的合并提交
+fn three_way_merge(repo: &Repository, current_branch: &str, branch_name: &str, commits: [&str; 3]) -> RustGitResult<()> {
+    let [base, ours, theirs] = commits;
+
+    let base_tree = commit_entries(base)?;
+    let ours_tree = commit_entries(ours)?;
+    let theirs_tree = commit_entries(theirs)?;
+    let same = |a: Option<&IndexEntry>, b: Option<&IndexEntry>| {
+        a.map(|entry| (&entry.hash, entry.mode)) == b.map(|entry| (&entry.hash, entry.mode))
+    };
+
+    // 逐个文件确定合并结果
+    let paths: BTreeSet<&String> = base_tree.keys().chain(ours_tree.keys()).chain(theirs_tree.keys()).collect();
+    let mut resolutions: BTreeMap<&str, Resolution> = BTreeMap::new();
+    for path in paths {
+        let (b, o, t) = (base_tree.get(path), ours_tree.get(path), theirs_tree.get(path));
+        let resolution = if same(o, t) || same(b, t) {
+            Resolution::Keep
+        } else if same(b, o) {
+            // 只有对方修改：直接采用对方版本
+            match t {
+                Some(t) => Resolution::Take(t.clone()),
+                None => Resolution::Delete,
+            }
+        } else {
+            // 双方都修改
+            match (o, t) {
+                (Some(o), Some(t)) => {
+                    println!("自动合并 {}", path);
+                    let base_content = match b {
+                        Some(b) => hash::read_object(&b.hash)?,
+                        None => Vec::new(),
+                    };
+                    let outcome = merge::merge_contents(
+                        &base_content,
+                        &hash::read_object(&o.hash)?,
+                        &hash::read_object(&t.hash)?,
+                        "HEAD",
+                        branch_name,
+                    );
+                    if outcome.conflicted {
+                        Resolution::Conflict(outcome.content)
+                    } else {
+                        // 可执行位只有一方修改时采用修改后的模式
+                        let mode = if b.is_some_and(|b| b.mode == o.mode) { t.mode } else { o.mode };
+                        let hash = hash::store_object(ObjectType::Blob, &outcome.content)?;
+                        Resolution::Take(IndexEntry { path: path.clone(), hash, mode, size: 0, mtime: 0 })
+                    }
+                }
+                (Some(modified), None) | (None, Some(modified)) => Resolution::Unmerged(modified.clone()),
+                (None, None) => Resolution::Keep,
+            }
+        };
+        resolutions.insert(path, resolution);
+    }
+
+    // 写入前检查：不覆盖未跟踪的文件，且需要的对象都存在
+    let written = resolutions.values().filter_map(|resolution| match resolution {
+        Resolution::Take(entry) | Resolution::Unmerged(entry) => Some(entry),
+        _ => None,
+    });
+    ensure_untracked_not_overwritten(repo, &ours_tree, written)?;
+    hash::ensure_objects_exist(resolutions.values().filter_map(|resolution| match resolution {
+        Resolution::Take(entry) | Resolution::Unmerged(entry) => Some(entry.hash.as_str()),
+        _ => None,
+    }))?;
+
+    // 更新工作区和暂存区
+    let mut index: BTreeMap<String, IndexEntry> = ours_tree.clone();
+    let mut conflicts: Vec<(&str, &str)> = Vec::new();
+    for (path, resolution) in resolutions {
+        let abs_path = repo.root().join(path);
+        match resolution {
+            Resolution::Keep => {}
+            Resolution::Take(mut entry) => {
+                write_work_file(&abs_path, &hash::read_object(&entry.hash)?, entry.mode)?;
+                entry.refresh_stat(&abs_path)?;
+                index.insert(path.to_string(), entry);
+            }
+            Resolution::Delete => {
+                if abs_path.is_file() {
+                    fs::remove_file(&abs_path)
+                        .context(format!("删除文件 {} 失败", abs_path.display()))?;
+                }
+                index.remove(path);
+            }
+            Resolution::Conflict(content) => {
+                let mode = ours_tree.get(path).map_or(metadata::MODE_REGULAR, |entry| entry.mode);
+                write_work_file(&abs_path, &content, mode)?;
+                conflicts.push((path, "双方修改"));
+            }
+            Resolution::Unmerged(entry) => {
+                write_work_file(&abs_path, &hash::read_object(&entry.hash)?, entry.mode)?;
+                conflicts.push((path, "一方删除，另一方修改"));
+            }
+        }
+    }
+    let index: Vec<IndexEntry> = index.into_values().collect();
+    repo.write_index(&index)?;
+
+    if !conflicts.is_empty() {
+        // 记录未完成的合并：解决冲突并暂存后，commit 会创建合并提交
+        utils_fs::write_atomic(&repo.merge_head_path(), theirs)
+            .context("写入 MERGE_HEAD 失败")?;
+        let paths: String = conflicts.iter().map(|(path, _)| format!("{}\n", path)).collect();
+        utils_fs::write_atomic(&repo.merge_conflicts_path(), paths)
+            .context("记录冲突文件失败")?;
+
+        let list: Vec<String> = conflicts.iter().map(|(path, kind)| format!("  {}（{}）", path, kind)).collect();
+        return Err(anyhow::anyhow!(
+            "自动合并失败，以下文件存在冲突，请修正并暂存后提交结果（或使用 merge --abort 中止合并）：\n{}",
+            list.join("\n")
+        ));
+    }
+
+    // 无冲突：创建合并提交（第一个父提交为当前分支，第二个为被合并的分支）
+    let message = format!("Merge branch '{}'", branch_name);
+    let commit = metadata::create_commit_with_parents(&index, &message, None, CommitDates::default(), &[ours.to_string(), theirs.to_string()])?;
+    metadata::save_commit(&commit)?;
+    utils_fs::update_branch(current_branch, &commit.id)?;
+    metadata::append_reflog(
+        Some(ours),
+        &commit.id,
+        current_branch,
+        &format!("merge {}: Merge made by the 'three-way' strategy.", branch_name),
+    )?;
+
+    println!("[{} {}] {}", current_branch, &commit.id[..7], message);
+    Ok(())
+}
+
+/// 是否有未完成的合并（存在 MERGE_HEAD）
+pub fn merge_in_progress(repo: &Repository) -> bool {
+    repo.merge_head_path().is_file()
+}
+
+/// 读取未完成合并中被合并的提交
+pub fn read_merge_head(repo: &Repository) -> RustGitResult<String> {
+    Ok(fs::read_to_string(repo.merge_head_path())
+        .context("读取 MERGE_HEAD 失败")?
+        .trim()
+        .to_string())
+}
+
+/// 读取未完成合并中存在冲突的文件
+pub fn read_merge_conflicts(repo: &Repository) -> RustGitResult<Vec<String>> {
+    let path = repo.merge_conflicts_path();
+    if !path.is_file() {
+        return Ok(Vec::new());
+    }
+    Ok(fs::read_to_string(&path)
+        .context("读取冲突文件列表失败")?
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// 检查冲突是否都已解决：工作区文件不再含冲突标记，且已暂存（或已从工作区和暂存区删除）
+pub fn ensure_conflicts_resolved(repo: &Repository, index: &[IndexEntry]) -> RustGitResult<()> {
+    let mut unresolved = Vec::new();
+    for path in read_merge_conflicts(repo)? {
+        let abs_path = repo.root().join(&path);
+        let entry = index.iter().find(|entry| entry.path == path);
+        let resolved = match entry {
+            Some(entry) => abs_path.is_file() && !has_conflict_markers(&abs_path)? && entry.matches_file(&abs_path)?,
+            None => !abs_path.exists(),
+        };
+        if !resolved {
+            unresolved.push(format!("  {}", path));
+        }
+    }
+    if !unresolved.is_empty() {
+        return Err(anyhow::anyhow!(
+            "以下文件仍有未解决的合并冲突，请修正并暂存后再提交（或使用 merge --abort 中止合并）：\n{}",
+            unresolved.join("\n")
+        ));
+    }
+    Ok(())
+}
+
+/// 清除未完成合并的状态（MERGE_HEAD 和冲突文件列表）
+pub fn clear_merge_state(repo: &Repository) -> RustGitResult<()> {
+    for path in [repo.merge_head_path(), repo.merge_conflicts_path()] {
+        if path.exists() {
+            fs::remove_file(&path)
+                .context(format!("删除 {} 失败", path.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// 中止未完成的合并：删除合并新增的文件，将工作区和暂存区恢复为 HEAD，并清除合并状态
+fn merge_abort(repo: &Repository) -> RustGitResult<()> {
+    if !merge_in_progress(repo) {
+        return Err(anyhow::anyhow!("没有正在进行的合并（不存在 MERGE_HEAD）"));
+    }
+    let head = repo.head()?
+        .ok_or_else(|| anyhow::anyhow!("暂无提交记录，无法中止合并"))?;
+
+    // 合并开始时工作区是干净的：暂存区或冲突列表中不属于 HEAD 的文件都由合并写入
+    let head_tree = hash::read_commit_tree(&head)?;
+    let mut added: BTreeSet<String> = repo.index()?.into_iter().map(|entry| entry.path).collect();
+    added.extend(read_merge_conflicts(repo)?);
+    for path in added.iter().filter(|path| !head_tree.contains_key(*path)) {
+        let abs_path = repo.root().join(path);
+        if abs_path.is_file() {
+            fs::remove_file(&abs_path)
+                .context(format!("删除文件 {} 失败", abs_path.display()))?;
+        }
+    }
+    checkout::restore_working_dir(repo, &head)?;
+    clear_merge_state(repo)?;
+
+    println!("已中止合并，工作区和暂存区已恢复为 {}", &head[..7]);
+    Ok(())
+}
+
+/// 文件中是否仍有冲突标记行
+fn has_conflict_markers(abs_path: &Path) -> RustGitResult<bool> {
+    let content = fs::read(abs_path)
+        .context(format!("读取文件 {} 失败", abs_path.display()))?;
+    Ok(content
+        .split(|&byte| byte == b'\n')
+        .any(|line| CONFLICT_MARKERS.iter().any(|marker| line.starts_with(marker.as_bytes()))))
+}
+
+/// 合并将写入的文件中，当前分支未跟踪、工作区已存在且内容不同的文件会被覆盖，此时报错
+fn ensure_untracked_not_overwritten<'a>(
+    repo: &Repository,
+    ours_tree: &BTreeMap<String, IndexEntry>,
+    entries: impl IntoIterator<Item = &'a IndexEntry>,
+) -> RustGitResult<()> {
+    let mut untracked = Vec::new();
+    for entry in entries {
+        let abs_path = repo.root().join(&entry.path);
+        if ours_tree.contains_key(&entry.path) || !abs_path.exists() {
+            continue;
+        }
+        // 内容与将写入的版本相同的文件覆盖后不会丢失内容
+        if abs_path.is_file() && hash::hash_file(&abs_path)? == entry.hash {
+            continue;
+        }
+        untracked.push(format!("  {}", entry.path));
+    }
+    if !untracked.is_empty() {
+        return Err(anyhow::anyhow!("以下未跟踪的文件将被合并覆盖，请先移除或添加它们：\n{}", untracked.join("\n")));
+    }
+    Ok(())
+}
+
+/// 读取提交的目录树：路径 -> 文件条目
+fn commit_entries(commit_id: &str) -> RustGitResult<BTreeMap<String, IndexEntry>> {
+    Ok(hash::read_tree_entries(&hash::read_commit(commit_id)?.tree_hash)?
+        .into_iter()
+        .map(|entry| (entry.path.clone(), entry))
+        .collect())
+}
+
+/// 写入工作区文件（按需创建父目录、按 core.autocrlf 转换换行符并设置文件模式）
+fn write_work_file(abs_path: &Path, content: &[u8], mode: u32) -> RustGitResult<()> {
+    if let Some(parent) = abs_path.parent()
+        && !parent.exists()
+    {
+        fs::create_dir_all(parent)
+            .context(format!("创建目录 {} 失败", parent.display()))?;
+    }
+    fs::write(abs_path, eol::to_work_tree(content.to_vec())?)
+        .context(format!("写入文件 {} 失败", abs_path.display()))?;
+    metadata::apply_file_mode(abs_path, mode)
+}
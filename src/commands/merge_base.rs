@@ -0,0 +1,21 @@
+use crate::utils::{fs, hash};
+use crate::RustGitResult;
+
+/// 实现 git merge-base 核心逻辑（输出两个修订的最近公共祖先的完整提交哈希）
+pub fn merge_base(a: &str, b: &str) -> RustGitResult<()> {
+    // 检查仓库是否初始化
+    if !fs::is_repo_initialized() {
+        return Err(anyhow::anyhow!("未初始化 rust-git 仓库，请先执行 `rust-git init`"));
+    }
+
+    let a_id = fs::resolve_rev(a)?;
+    let b_id = fs::resolve_rev(b)?;
+    match hash::merge_base(&a_id, &b_id)? {
+        Some(base) => {
+            println!("{}", base);
+            Ok(())
+        }
+        // 没有公共祖先：标准输出为空，以非零状态退出
+        None => Err(anyhow::anyhow!("{} 与 {} 没有公共祖先", a, b)),
+    }
+}
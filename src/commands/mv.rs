@@ -0,0 +1,54 @@
+use anyhow::Context;
+use crate::utils::fs as utils_fs;
+use crate::utils::repository::Repository;
+use crate::RustGitResult;
+use std::fs;
+
+/// 实现 git mv 核心逻辑（移动工作区文件并更新暂存区路径）
+pub fn mv(from: &str, to: &str) -> RustGitResult<()> {
+    // 裸仓库没有工作区
+    utils_fs::ensure_work_tree()?;
+
+    // 检查仓库是否初始化
+    if !utils_fs::is_repo_initialized() {
+        return Err(anyhow::anyhow!("未初始化 rust-git 仓库，请先执行 `rust-git init`"));
+    }
+
+    // 持有暂存区锁直到命令结束，防止并发进程交错读写暂存区
+    let _lock = utils_fs::IndexLock::acquire(&Repository::open()?)?;
+
+    // 基于仓库根目录计算相对路径
+    let repo_root = utils_fs::get_repo_root()?;
+    let from_rel = utils_fs::get_repo_relative_path(from)?;
+    let to_rel = utils_fs::get_repo_relative_path(to)?;
+    let from_abs = repo_root.join(&from_rel);
+    let to_abs = repo_root.join(&to_rel);
+
+    // 源文件必须已被跟踪，目标不能已存在
+    let mut index = utils_fs::read_index_typed()?;
+    let entry_pos = index.iter()
+        .position(|entry| entry.path == from_rel)
+        .ok_or_else(|| anyhow::anyhow!("文件未在暂存区中：{}", from_rel))?;
+    if to_abs.exists() || index.iter().any(|entry| entry.path == to_rel) {
+        return Err(anyhow::anyhow!("目标已存在：{}", to_rel));
+    }
+
+    // 移动工作区文件
+    if from_abs.exists() {
+        if let Some(parent) = to_abs.parent()
+            && !parent.exists()
+        {
+            fs::create_dir_all(parent)
+                .context(format!("创建目录 {} 失败", parent.display()))?;
+        }
+        fs::rename(&from_abs, &to_abs)
+            .context(format!("移动文件 {} 到 {} 失败", from_abs.display(), to_abs.display()))?;
+    }
+
+    // 更新暂存区条目路径
+    index[entry_pos].path = to_rel.clone();
+    utils_fs::write_index_typed(&index)?;
+    println!("已将 {} 重命名为 {}", from_rel, to_rel);
+
+    Ok(())
+}
@@ -0,0 +1,201 @@
+use anyhow::Context;
+use crate::commands::{checkout, merge};
+use crate::utils::{fs as utils_fs, hash, merge as utils_merge, metadata};
+use crate::utils::hash::ObjectType;
+use crate::utils::metadata::{Commit, CommitDates, IndexEntry};
+use crate::utils::repository::Repository;
+use crate::RustGitResult;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+
+/// 实现 git rebase 核心逻辑（将当前分支自公共祖先以来独有的提交依次重放到 onto 之上，
+/// 全部重放成功后才更新工作区、暂存区和分支；遇到冲突时报告并中止，不修改任何内容）
+pub fn rebase(repo: &Repository, onto: &str) -> RustGitResult<()> {
+    // 裸仓库没有工作区
+    repo.ensure_work_tree()?;
+
+    // 持有暂存区锁直到命令结束，防止并发进程交错读写暂存区
+    let _lock = utils_fs::IndexLock::acquire(repo)?;
+
+    if merge::merge_in_progress(repo) {
+        return Err(anyhow::anyhow!("合并尚未完成，请先提交或使用 merge --abort 中止合并"));
+    }
+    let current_branch = repo.current_branch()?;
+    if current_branch == utils_fs::DETACHED_HEAD {
+        return Err(anyhow::anyhow!("HEAD 处于分离状态，请先切换到要变基的分支"));
+    }
+    let head = repo.head()?
+        .ok_or_else(|| anyhow::anyhow!("分支 {} 暂无提交记录，无法变基", current_branch))?;
+    let onto_id = utils_fs::resolve_rev(onto)?;
+
+    // 变基会改写工作区和暂存区，要求当前没有未提交的修改
+    let changed = checkout::uncommitted_changes(repo)?;
+    if !changed.is_empty() {
+        let list: Vec<String> = changed.iter().map(|path| format!("  {}", path)).collect();
+        return Err(anyhow::anyhow!(
+            "以下文件有未提交的修改，请先提交或储藏（commit or stash your changes）：\n{}",
+            list.join("\n")
+        ));
+    }
+
+    if hash::is_ancestor(&onto_id, &head)? {
+        println!("当前分支 {} 已基于 {}，无需变基", current_branch, onto);
+        return Ok(());
+    }
+
+    let commits = commits_to_replay(&head, &onto_id)?;
+
+    // 在内存中依次重放，全部成功后再写入工作区
+    let mut tree: BTreeMap<String, IndexEntry> = hash::read_tree_entries(&hash::read_commit(&onto_id)?.tree_hash)?
+        .into_iter()
+        .map(|entry| (entry.path.clone(), entry))
+        .collect();
+    let mut new_head = onto_id.clone();
+    let mut replayed_count = 0;
+    for commit in &commits {
+        let short_id = &commit.id[..7];
+        let subject = commit.message.lines().next().unwrap_or("");
+        let applied = apply_commit(&mut tree, commit)
+            .context(format!("无法重放提交 {}（{}），已中止变基，当前分支未改变", short_id, subject))?;
+        // 变更已包含在目标中的提交重放后为空，跳过
+        if !applied {
+            println!("跳过已应用的提交 {} {}", short_id, subject);
+            continue;
+        }
+        // 保留原作者和作者时间，提交时间为变基时的当前时间
+        let entries: Vec<IndexEntry> = tree.values().cloned().collect();
+        let replayed = metadata::create_commit_with_parents(
+            &entries,
+            &commit.message,
+            Some(&commit.author),
+            CommitDates { author: Some(commit.timestamp), committer: None },
+            &[new_head],
+        )?;
+        metadata::save_commit(&replayed)?;
+        new_head = replayed.id;
+        replayed_count += 1;
+    }
+
+    // 写入前检查：不覆盖原分支未跟踪、变基结果中新增的文件
+    let head_tree = hash::read_commit_tree(&head)?;
+    let untracked: Vec<String> = tree
+        .keys()
+        .filter(|path| !head_tree.contains_key(*path) && repo.root().join(path).exists())
+        .map(|path| format!("  {}", path))
+        .collect();
+    if !untracked.is_empty() {
+        return Err(anyhow::anyhow!("以下未跟踪的文件将被变基覆盖，请先移除或添加它们：\n{}", untracked.join("\n")));
+    }
+
+    // 删除原分支中已跟踪、变基结果中不存在的文件，再恢复为变基结果
+    for path in head_tree.keys().filter(|path| !tree.contains_key(*path)) {
+        let abs_path = repo.root().join(path);
+        if abs_path.is_file() {
+            fs::remove_file(&abs_path)
+                .context(format!("删除文件 {} 失败", abs_path.display()))?;
+        }
+    }
+    checkout::restore_working_dir(repo, &new_head)?;
+    metadata::update_branch_commit(&current_branch, &new_head)?;
+    metadata::append_reflog(
+        Some(&head),
+        &new_head,
+        &current_branch,
+        &format!("rebase (finish): refs/heads/{} onto {}", current_branch, onto_id),
+    )?;
+
+    println!(
+        "已将分支 {} 的 {} 个提交变基到 {}（{}）",
+        current_branch,
+        replayed_count,
+        onto,
+        &new_head[..7]
+    );
+    Ok(())
+}
+
+/// 沿第一父提交链从 head 回溯到 onto 已包含的提交为止，得到待重放的提交（最早的在前）
+fn commits_to_replay(head: &str, onto: &str) -> RustGitResult<Vec<Commit>> {
+    let upstream = hash::ancestors(onto)?;
+    let mut commits = Vec::new();
+    let mut next = Some(head.to_string());
+    while let Some(commit_id) = next {
+        if upstream.contains(&commit_id) {
+            break;
+        }
+        let commit = hash::read_commit(&commit_id)?;
+        if commit.parents.len() > 1 {
+            return Err(anyhow::anyhow!("提交 {} 是合并提交，暂不支持变基合并提交", &commit_id[..7]));
+        }
+        next = commit.first_parent().map(str::to_string);
+        commits.push(commit);
+    }
+    commits.reverse();
+    Ok(commits)
+}
+
+/// 将提交相对其父提交的变更应用到目录树上，返回目录树是否有变化；变更路径的当前版本与父提交不一致时
+/// 按行三方合并（父提交版本为基准），无法自动合并或一方删除另一方修改时视为冲突
+fn apply_commit(tree: &mut BTreeMap<String, IndexEntry>, commit: &Commit) -> RustGitResult<bool> {
+    let parent: BTreeMap<String, IndexEntry> = match commit.first_parent() {
+        Some(parent) => hash::read_tree_entries(&hash::read_commit(parent)?.tree_hash)?
+            .into_iter()
+            .map(|entry| (entry.path.clone(), entry))
+            .collect(),
+        None => BTreeMap::new(),
+    };
+    let target: BTreeMap<String, IndexEntry> = hash::read_tree_entries(&commit.tree_hash)?
+        .into_iter()
+        .map(|entry| (entry.path.clone(), entry))
+        .collect();
+    let version = |entry: Option<&IndexEntry>| entry.map(|entry| (entry.hash.clone(), entry.mode));
+
+    let mut conflicts = Vec::new();
+    let mut changed = false;
+    let paths: BTreeSet<&String> = parent.keys().chain(target.keys()).collect();
+    for path in paths {
+        let (old, new) = (version(parent.get(path)), version(target.get(path)));
+        let current = version(tree.get(path));
+        // 未修改的路径，或当前已与目标一致的路径无需处理
+        if old == new || current == new {
+            continue;
+        }
+        if current == old {
+            match target.get(path) {
+                Some(entry) => tree.insert(path.clone(), entry.clone()),
+                None => tree.remove(path),
+            };
+            changed = true;
+            continue;
+        }
+        let (Some(ours), Some(theirs)) = (tree.get(path), target.get(path)) else {
+            conflicts.push(format!("  {}", path));
+            continue;
+        };
+        let base = parent.get(path);
+        let base_content = match base {
+            Some(base) => hash::read_object(&base.hash)?,
+            None => Vec::new(),
+        };
+        let outcome = utils_merge::merge_contents(
+            &base_content,
+            &hash::read_object(&ours.hash)?,
+            &hash::read_object(&theirs.hash)?,
+            "HEAD",
+            &commit.id[..7],
+        );
+        if outcome.conflicted {
+            conflicts.push(format!("  {}", path));
+            continue;
+        }
+        // 可执行位只有提交一方修改时采用修改后的模式
+        let mode = if base.is_some_and(|base| base.mode == ours.mode) { theirs.mode } else { ours.mode };
+        let hash = hash::store_object(ObjectType::Blob, &outcome.content)?;
+        changed |= hash != ours.hash || mode != ours.mode;
+        tree.insert(path.clone(), IndexEntry { path: path.clone(), hash, mode, size: 0, mtime: 0 });
+    }
+    if !conflicts.is_empty() {
+        return Err(anyhow::anyhow!("以下文件存在冲突：\n{}", conflicts.join("\n")));
+    }
+    Ok(changed)
+}
@@ -0,0 +1,23 @@
+use crate::utils::{fs, metadata};
+use crate::RustGitResult;
+
+/// 实现 git reflog 核心逻辑（按时间倒序输出 HEAD 的移动记录）
+pub fn reflog() -> RustGitResult<()> {
+    // 检查仓库是否初始化
+    if !fs::is_repo_initialized() {
+        return Err(anyhow::anyhow!("未初始化 rust-git 仓库，请先执行 `rust-git init`"));
+    }
+
+    let entries = metadata::read_reflog()?;
+    if entries.is_empty() {
+        println!("暂无引用日志");
+        return Ok(());
+    }
+
+    // 最新的记录为 HEAD@{0}
+    for (i, entry) in entries.iter().rev().enumerate() {
+        println!("{} HEAD@{{{}}}: {}", &entry.new_id[..entry.new_id.len().min(7)], i, entry.action);
+    }
+
+    Ok(())
+}
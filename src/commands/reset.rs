@@ -0,0 +1,50 @@
+use crate::utils::fs as utils_fs;
+use crate::utils::repository::Repository;
+use crate::RustGitResult;
+use serde_json::Value;
+
+/// 实现 git reset 核心逻辑（取消暂存，不修改工作区文件）
+pub fn reset(path: Option<&str>) -> RustGitResult<()> {
+    // 裸仓库没有工作区
+    utils_fs::ensure_work_tree()?;
+
+    // 检查仓库是否初始化
+    if !utils_fs::is_repo_initialized() {
+        return Err(anyhow::anyhow!("未初始化 rust-git 仓库，请先执行 `rust-git init`"));
+    }
+
+    // 持有暂存区锁直到命令结束，防止并发进程交错读写暂存区
+    let _lock = utils_fs::IndexLock::acquire(&Repository::open()?)?;
+
+    // 未指定路径：清空整个暂存区
+    let path = match path {
+        Some(path) => path,
+        None => {
+            utils_fs::write_index(&Value::Array(Vec::new()))?;
+            println!("已清空暂存区");
+            return Ok(());
+        }
+    };
+
+    // 基于仓库根目录计算相对路径
+    let rel_path = utils_fs::get_repo_relative_path(path)?;
+
+    // 读取暂存区并移除对应条目
+    let mut index = utils_fs::read_index()?;
+    let entries = match index.as_array_mut() {
+        Some(entries) => entries,
+        None => return Err(anyhow::anyhow!("文件未在暂存区中：{}", rel_path)),
+    };
+
+    let original_len = entries.len();
+    entries.retain(|entry| entry["path"] != rel_path.as_str());
+    if entries.len() == original_len {
+        return Err(anyhow::anyhow!("文件未在暂存区中：{}", rel_path));
+    }
+
+    // 写入更新后的暂存区（保留工作区文件）
+    utils_fs::write_index(&index)?;
+    println!("已取消暂存：{}", rel_path);
+
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use anyhow::Context;
+use crate::utils::eol;
+use crate::utils::fs as utils_fs;
+use crate::utils::hash;
+use crate::utils::metadata::{self, IndexEntry};
+use crate::utils::repository::Repository;
+use crate::RustGitResult;
+use std::fs;
+
+/// 实现 git restore 核心逻辑（paths 可为多个文件、目录或 glob 模式，如 `src/*.rs`）
+/// - 默认：用暂存区中的版本覆盖工作区文件
+/// - `--staged`：将暂存区条目恢复为最新提交中的版本（提交中不存在则移除）
+pub fn restore(paths: &[String], staged: bool) -> RustGitResult<()> {
+    // 裸仓库没有工作区
+    utils_fs::ensure_work_tree()?;
+
+    // 检查仓库是否初始化
+    if !utils_fs::is_repo_initialized() {
+        return Err(anyhow::anyhow!("未初始化 rust-git 仓库，请先执行 `rust-git init`"));
+    }
+
+    // 持有暂存区锁直到命令结束，防止并发进程交错读写暂存区
+    let _lock = utils_fs::IndexLock::acquire(&Repository::open()?)?;
+
+    let repo_root = utils_fs::get_repo_root()?;
+    let mut index = utils_fs::read_index_typed()?;
+
+    if staged {
+        // 可取消暂存的路径：暂存区或最新提交中的文件
+        let head_tree = hash::read_head_tree()?;
+        let mut candidates: Vec<String> = index.iter().map(|entry| entry.path.clone()).collect();
+        candidates.extend(head_tree.keys().cloned());
+        let rel_paths = utils_fs::expand_pathspecs(paths, &candidates)?;
+
+        for rel_path in &rel_paths {
+            let position = index.iter().position(|entry| entry.path == *rel_path);
+            match (head_tree.get(rel_path), position) {
+                (Some(head_hash), Some(i)) => {
+                    index[i].hash = head_hash.clone();
+                    index[i].clear_stat();
+                }
+                (Some(head_hash), None) => {
+                    // 目录树映射不含模式，沿用工作区文件的模式
+                    let abs_path = repo_root.join(rel_path);
+                    let mode = if abs_path.is_file() {
+                        metadata::file_mode(&abs_path)?
+                    } else {
+                        metadata::MODE_REGULAR
+                    };
+                    index.push(IndexEntry {
+                        path: rel_path.clone(),
+                        hash: head_hash.clone(),
+                        mode,
+                        size: 0,
+                        mtime: 0,
+                    });
+                }
+                (None, Some(i)) => {
+                    index.remove(i);
+                }
+                (None, None) => return Err(anyhow::anyhow!("文件未在暂存区或最新提交中：{}", rel_path)),
+            }
+        }
+        utils_fs::write_index_typed(&index)?;
+        for rel_path in &rel_paths {
+            println!("已取消暂存：{}", rel_path);
+        }
+        return Ok(());
+    }
+
+    // 从暂存区读取文件对象并写回工作区
+    let candidates: Vec<String> = index.iter().map(|entry| entry.path.clone()).collect();
+    let rel_paths = utils_fs::expand_pathspecs(paths, &candidates)?;
+    let entries = rel_paths
+        .iter()
+        .map(|rel_path| {
+            index.iter()
+                .find(|entry| &entry.path == rel_path)
+                .ok_or_else(|| anyhow::anyhow!("文件未在暂存区中：{}", rel_path))
+        })
+        .collect::<RustGitResult<Vec<_>>>()?;
+    hash::ensure_objects_exist(entries.iter().map(|entry| entry.hash.as_str()))?;
+    for (rel_path, entry) in rel_paths.iter().zip(entries) {
+        let abs_path = repo_root.join(rel_path);
+        if let Some(parent) = abs_path.parent()
+            && !parent.exists()
+        {
+            fs::create_dir_all(parent)
+                .context(format!("创建目录 {} 失败", parent.display()))?;
+        }
+        let file_content = eol::read_blob_for_work_tree(&entry.hash)?;
+        fs::write(&abs_path, file_content)
+            .context(format!("恢复文件 {} 失败", abs_path.display()))?;
+        metadata::apply_file_mode(&abs_path, entry.mode)?;
+
+        println!("已恢复文件：{}", rel_path);
+    }
+    Ok(())
+}
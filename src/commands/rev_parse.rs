@@ -0,0 +1,13 @@
+use crate::utils::fs;
+use crate::RustGitResult;
+
+/// 实现 git rev-parse 核心逻辑（输出修订对应的完整提交哈希）
+pub fn rev_parse(rev: &str) -> RustGitResult<()> {
+    // 检查仓库是否初始化
+    if !fs::is_repo_initialized() {
+        return Err(anyhow::anyhow!("未初始化 rust-git 仓库，请先执行 `rust-git init`"));
+    }
+
+    println!("{}", fs::resolve_rev(rev)?);
+    Ok(())
+}
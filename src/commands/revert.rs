@@ -0,0 +1,150 @@
+use anyhow::Context;
+use crate::commands::checkout;
+use crate::utils::{eol, fs as utils_fs, hash, metadata};
+use crate::utils::metadata::IndexEntry;
+use crate::utils::repository::Repository;
+use crate::RustGitResult;
+use std::collections::BTreeMap;
+use std::fs;
+
+/// 实现 git revert 核心逻辑（创建一个撤销目标提交全部变更的新提交）
+///
+/// 根提交没有父提交，撤销即删除它引入的全部文件。
+pub fn revert(repo: &Repository, target: &str) -> RustGitResult<()> {
+    // 裸仓库没有工作区
+    repo.ensure_work_tree()?;
+
+    // 持有暂存区锁直到命令结束，防止并发进程交错读写暂存区
+    let _lock = utils_fs::IndexLock::acquire(repo)?;
+
+    let head = repo.head()?
+        .ok_or_else(|| anyhow::anyhow!("暂无提交记录，无法还原"))?;
+    let commit_id = utils_fs::resolve_rev(target)?;
+    let commit = hash::read_commit(&commit_id)?;
+    if commit.parents.len() > 1 {
+        return Err(anyhow::anyhow!("提交 {} 是合并提交，暂不支持还原合并提交", &commit_id[..7]));
+    }
+
+    // 还原会修改工作区和暂存区，要求当前没有未提交的修改
+    let changed = checkout::uncommitted_changes(repo)?;
+    if !changed.is_empty() {
+        let list: Vec<String> = changed.iter().map(|path| format!("  {}", path)).collect();
+        return Err(anyhow::anyhow!(
+            "以下文件有未提交的修改，请先提交或储藏（commit or stash your changes）：\n{}",
+            list.join("\n")
+        ));
+    }
+
+    // 反向应用：从目标提交的目录树变回其父提交的目录树
+    let parent_entries = match commit.first_parent() {
+        Some(parent) => hash::read_tree_entries(&hash::read_commit(parent)?.tree_hash)?,
+        None => Vec::new(),
+    };
+    let commit_entries = hash::read_tree_entries(&commit.tree_hash)?;
+    let short_id = &commit_id[..7];
+    apply_tree_diff(repo, &commit_entries, &parent_entries)
+        .context(format!("无法还原提交 {}", short_id))?;
+
+    let subject = commit.message.lines().next().unwrap_or("");
+    let message = format!("Revert \"{}\"\n\nThis reverts commit {}.", subject, commit_id);
+    let new_commit = metadata::create_commit(&message, None, None)?;
+    metadata::save_commit(&new_commit)?;
+
+    let current_branch = repo.current_branch()?;
+    metadata::update_branch_commit(&current_branch, &new_commit.id)?;
+    metadata::append_reflog(
+        Some(&head),
+        &new_commit.id,
+        &current_branch,
+        &format!("revert: Revert \"{}\"", subject),
+    )?;
+
+    println!("[{} {}] Revert \"{}\"", current_branch, &new_commit.id[..7], subject);
+    Ok(())
+}
+
+/// 将 from -> to 两个目录树之间的变更应用到工作区和暂存区（要求工作区与暂存区一致）
+///
+/// 变更路径在暂存区中的当前版本必须与 from 一致，否则视为冲突并中止，不修改任何文件。
+pub fn apply_tree_diff(repo: &Repository, from: &[IndexEntry], to: &[IndexEntry]) -> RustGitResult<()> {
+    let from_map: BTreeMap<&str, &IndexEntry> = from.iter().map(|entry| (entry.path.as_str(), entry)).collect();
+    let to_map: BTreeMap<&str, &IndexEntry> = to.iter().map(|entry| (entry.path.as_str(), entry)).collect();
+    let mut index: BTreeMap<String, IndexEntry> = repo.index()?
+        .into_iter()
+        .map(|entry| (entry.path.clone(), entry))
+        .collect();
+
+    // 收集变更路径：路径 -> 目标版本（None 表示删除）
+    let mut changes: BTreeMap<&str, Option<&IndexEntry>> = BTreeMap::new();
+    for (path, entry) in &to_map {
+        if from_map.get(path).is_none_or(|old| old.hash != entry.hash || old.mode != entry.mode) {
+            changes.insert(path, Some(entry));
+        }
+    }
+    for path in from_map.keys() {
+        if !to_map.contains_key(path) {
+            changes.insert(path, None);
+        }
+    }
+    if changes.is_empty() {
+        return Err(anyhow::anyhow!("没有需要应用的变更"));
+    }
+
+    // 当前版本与 from 不一致的路径无法直接应用
+    let conflicts: Vec<String> = changes
+        .keys()
+        .filter(|path| {
+            let current = index.get(**path).map(|entry| (&entry.hash, entry.mode));
+            let expected = from_map.get(*path).map(|entry| (&entry.hash, entry.mode));
+            current != expected
+        })
+        .map(|path| format!("  {}", path))
+        .collect();
+    if !conflicts.is_empty() {
+        return Err(anyhow::anyhow!("以下文件的当前版本与变更前的版本不一致，存在冲突：\n{}", conflicts.join("\n")));
+    }
+
+    // 不覆盖工作区中同名的未跟踪文件
+    let untracked: Vec<String> = changes
+        .keys()
+        .filter(|path| !index.contains_key(**path) && repo.root().join(path).exists())
+        .map(|path| format!("  {}", path))
+        .collect();
+    if !untracked.is_empty() {
+        return Err(anyhow::anyhow!("以下未跟踪的文件将被覆盖，请先移除或添加它们：\n{}", untracked.join("\n")));
+    }
+
+    // 写入前确认全部文件对象都存在，避免只应用一部分
+    hash::ensure_objects_exist(changes.values().flatten().map(|entry| entry.hash.as_str()))?;
+
+    for (path, entry) in changes {
+        let abs_path = repo.root().join(path);
+        match entry {
+            Some(entry) => {
+                if let Some(parent) = abs_path.parent()
+                    && !parent.exists()
+                {
+                    fs::create_dir_all(parent)
+                        .context(format!("创建目录 {} 失败", parent.display()))?;
+                }
+                fs::write(&abs_path, eol::read_blob_for_work_tree(&entry.hash)?)
+                    .context(format!("写入文件 {} 失败", abs_path.display()))?;
+                metadata::apply_file_mode(&abs_path, entry.mode)?;
+                let mut entry = entry.clone();
+                entry.refresh_stat(&abs_path)?;
+                index.insert(path.to_string(), entry);
+            }
+            None => {
+                if abs_path.is_file() {
+                    fs::remove_file(&abs_path)
+                        .context(format!("删除文件 {} 失败", abs_path.display()))?;
+                }
+                index.remove(path);
+            }
+        }
+    }
+
+    let index: Vec<IndexEntry> = index.into_values().collect();
+    repo.write_index(&index)?;
+    Ok(())
+}
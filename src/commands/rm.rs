@@ -1,49 +1,38 @@
 use anyhow::Context;
-use crate::utils::fs;
+use crate::utils::{config, fs};
+use crate::utils::repository::Repository;
 use crate::RustGitResult;
-use serde_json::Value;
 
 /// 实现 git rm 核心逻辑
-pub fn rm(path: &str) -> RustGitResult<()> {
+pub fn rm(path: &str, cached: bool) -> RustGitResult<()> {
+    // 裸仓库没有工作区
+    fs::ensure_work_tree()?;
+
     // 检查仓库是否初始化
     if !fs::is_repo_initialized() {
         return Err(anyhow::anyhow!("未初始化 rust-git 仓库，请先执行 `rust-git init`"));
     }
 
-    // 获取绝对路径
-    let abs_path = fs::get_absolute_path(path)?;
-    let repo_root = std::env::current_dir()?;
-    let rel_path = abs_path.strip_prefix(&repo_root)
-        .context(format!("获取相对路径失败：{}", abs_path.display()))?
-        .to_str()
-        .ok_or_else(|| anyhow::anyhow!("路径转换为字符串失败：{}", abs_path.display()))?;
+    // 持有暂存区锁直到命令结束，防止并发进程交错读写暂存区
+    let _lock = fs::IndexLock::acquire(&Repository::open()?)?;
 
-    // 读取暂存区
-    let mut index = fs::read_index()?;
-    let mut index_array = if index.is_array() {
-        index.take()
-    } else {
-        Value::Array(Vec::new())
-    };
+    // 基于仓库根目录计算相对路径（与 add 存储的格式一致；仓库之外的路径如 ../other 会被拒绝）
+    let repo_root = fs::get_repo_root()?;
+    let rel_path = fs::get_repo_relative_path(path)?;
 
-    // 从暂存区移除条目
-    let mut removed = false;
-    if let Value::Array(ref mut entries) = index_array {
-        entries.retain(|entry| {
-            let keep = entry["path"] != rel_path;
-            if !keep {
-                removed = true;
-            }
-            keep
-        });
-    }
+    // 读取暂存区
+    let mut index = fs::read_index_typed()?;
 
-    if !removed {
-        return Err(anyhow::anyhow!("文件未在暂存区中：{}", abs_path.display()));
-    }
+    // 从暂存区移除条目（core.ignorecase 时忽略大小写匹配，并以暂存区中的路径写法为准）
+    let ignore_case = config::get_bool("core.ignorecase", false)?;
+    let position = index.iter().position(|entry| fs::paths_equal(&entry.path, &rel_path, ignore_case));
+    let Some(position) = position else {
+        return Err(anyhow::anyhow!("文件未在暂存区中：{}", repo_root.join(&rel_path).display()));
+    };
+    let abs_path = repo_root.join(index.remove(position).path);
 
-    // 删除物理文件/目录（可选，模仿 Git 的 rm 行为）
-    if abs_path.exists() {
+    // 删除物理文件/目录（--cached 时保留，模仿 Git 的 rm 行为）
+    if !cached && abs_path.exists() {
         if abs_path.is_file() {
             std::fs::remove_file(&abs_path)
                 .context(format!("删除文件失败：{}", abs_path.display()))?;
@@ -54,8 +43,12 @@ pub fn rm(path: &str) -> RustGitResult<()> {
     }
 
     // 写入更新后的暂存区
-    fs::write_index(&index_array)?;
-    println!("已从暂存区和文件系统移除：{}", abs_path.display());
+    fs::write_index_typed(&index)?;
+    if cached {
+        println!("已从暂存区移除：{}", abs_path.display());
+    } else {
+        println!("已从暂存区和文件系统移除：{}", abs_path.display());
+    }
 
     Ok(())
 }
\ No newline at end of file
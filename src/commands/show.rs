@@ -0,0 +1,49 @@
+use crate::api;
+use crate::commands::diff;
+use crate::utils::{fs as utils_fs, hash, metadata};
+use crate::utils::hash::ObjectType;
+use crate::RustGitResult;
+use std::collections::BTreeMap;
+
+/// 实现 git show 核心逻辑（显示提交信息及其变更；目标为附注标签时先显示标签信息）
+pub fn show(target: &str) -> RustGitResult<()> {
+    // 检查仓库是否初始化
+    if !utils_fs::is_repo_initialized() {
+        return Err(anyhow::anyhow!("未初始化 rust-git 仓库，请先执行 `rust-git init`"));
+    }
+
+    if let Some(tag) = annotated_tag(target)? {
+        println!("{}", metadata::format_tag(&tag));
+    }
+
+    // 解析目标（支持 HEAD~n 等祖先语法）并输出提交元信息
+    let commit = api::read_commit(target)?;
+    let commit_id = commit.id.clone();
+    println!("{}", metadata::format_commit(&commit));
+
+    // 与父提交的目录树比较（根提交视为所有文件新增）
+    let parent_tree = match commit.first_parent() {
+        Some(parent_id) => hash::read_commit_tree(parent_id)?,
+        None => BTreeMap::new(),
+    };
+    let commit_tree = hash::read_commit_tree(&commit_id)?;
+    print!("{}", diff::colorize_diff(&diff::render_tree_diff(&parent_tree, &commit_tree)?));
+
+    Ok(())
+}
+
+/// 目标为附注标签（标签名或标签对象哈希）时读取标签对象，否则返回 None
+fn annotated_tag(target: &str) -> RustGitResult<Option<metadata::Tag>> {
+    let object_id = if utils_fs::list_tags()?.iter().any(|tag| tag == target) {
+        utils_fs::read_tag_ref(target)?
+    } else {
+        match hash::resolve_hash(target) {
+            Ok(object_id) => object_id,
+            Err(_) => return Ok(None),
+        }
+    };
+    match hash::read_object_with_type(&object_id)? {
+        (Some(ObjectType::Tag), content) => Ok(Some(hash::parse_tag(&content)?)),
+        _ => Ok(None),
+    }
+}
@@ -0,0 +1,196 @@
+use anyhow::Context;
+use crate::commands::checkout;
+use crate::utils::eol;
+use crate::utils::fs as utils_fs;
+use crate::utils::hash::{self, ObjectType};
+use crate::utils::metadata::{self, CommitDates, IndexEntry};
+use crate::utils::repository::Repository;
+use crate::RustGitResult;
+use std::fs;
+
+/// 实现 git stash 核心逻辑（储藏工作区和暂存区的修改，或用 pop 恢复最近一次储藏）
+///
+/// 每次储藏写入两个提交对象：暂存区快照（父提交为 HEAD）和工作区快照（父提交为暂存区快照）。
+/// refs/stash 指向最近一次储藏，logs/stash 按顺序记录全部储藏（栈，最新的在末尾）。
+pub fn stash(repo: &Repository, pop: bool) -> RustGitResult<()> {
+    // 裸仓库没有工作区
+    repo.ensure_work_tree()?;
+
+    // 持有暂存区锁直到命令结束，防止并发进程交错读写暂存区
+    let _lock = utils_fs::IndexLock::acquire(repo)?;
+
+    if pop {
+        stash_pop(repo)
+    } else {
+        stash_push(repo)
+    }
+}
+
+/// 储藏当前修改，并将工作区和暂存区重置为 HEAD
+fn stash_push(repo: &Repository) -> RustGitResult<()> {
+    let head = repo.head()?
+        .ok_or_else(|| anyhow::anyhow!("暂无提交记录，无法储藏"))?;
+    if checkout::uncommitted_changes(repo)?.is_empty() {
+        println!("没有要储藏的本地修改");
+        return Ok(());
+    }
+
+    // 工作区快照：已跟踪文件的当前内容（工作区中已删除的文件不包含在内）
+    let index = repo.index()?;
+    let mut working = Vec::new();
+    for entry in &index {
+        let abs_path = repo.root().join(&entry.path);
+        if !abs_path.is_file() {
+            continue;
+        }
+        let content = fs::read(&abs_path)
+            .context(format!("读取文件失败：{}", abs_path.display()))?;
+        let content = eol::to_repo(content)?;
+        working.push(IndexEntry {
+            path: entry.path.clone(),
+            hash: hash::store_object(ObjectType::Blob, &content)?,
+            mode: metadata::file_mode(&abs_path)?,
+            size: 0,
+            mtime: 0,
+        });
+    }
+
+    // 写入暂存区快照和工作区快照两个提交对象
+    let head_commit = hash::read_commit(&head)?;
+    let subject = format!(
+        "{}: {} {}",
+        repo.current_branch()?,
+        &head[..7],
+        head_commit.message.lines().next().unwrap_or("")
+    );
+    let author = metadata::read_author()?;
+    let timestamp = chrono::Local::now().timestamp();
+    let index_commit = metadata::write_commit(
+        &metadata::write_tree_from_entries(&index)?,
+        &[&head],
+        &author,
+        &author,
+        CommitDates::at(Some(timestamp)),
+        &format!("index on {}", subject),
+    )?;
+    let stash_id = metadata::write_commit(
+        &metadata::write_tree_from_entries(&working)?,
+        &[&index_commit],
+        &author,
+        &author,
+        CommitDates::at(Some(timestamp)),
+        &format!("WIP on {}", subject),
+    )?;
+
+    let mut stack = read_stash_stack(repo)?;
+    stack.push(stash_id);
+    write_stash_stack(repo, &stack)?;
+
+    // 重置为 HEAD：删除 HEAD 中不存在的已跟踪文件，其余文件恢复为 HEAD 中的版本
+    let head_tree = hash::read_commit_tree(&head)?;
+    for entry in &index {
+        let abs_path = repo.root().join(&entry.path);
+        if !head_tree.contains_key(&entry.path) && abs_path.is_file() {
+            fs::remove_file(&abs_path)
+                .context(format!("删除文件 {} 失败", abs_path.display()))?;
+        }
+    }
+    checkout::restore_working_dir(repo, &head)?;
+
+    println!("已保存工作区和暂存区状态：WIP on {}", subject);
+    Ok(())
+}
+
+/// 恢复最近一次储藏（覆盖工作区和暂存区）并将其从栈中移除
+fn stash_pop(repo: &Repository) -> RustGitResult<()> {
+    let mut stack = read_stash_stack(repo)?;
+    let stash_id = stack.pop()
+        .ok_or_else(|| anyhow::anyhow!("没有可恢复的储藏"))?;
+
+    // 恢复会覆盖工作区，要求当前没有未提交的修改
+    let changed = checkout::uncommitted_changes(repo)?;
+    if !changed.is_empty() {
+        let list: Vec<String> = changed.iter().map(|path| format!("  {}", path)).collect();
+        return Err(anyhow::anyhow!(
+            "以下文件有未提交的修改，恢复储藏将覆盖它们，请先提交（commit your changes）：\n{}",
+            list.join("\n")
+        ));
+    }
+
+    let stash_commit = hash::read_commit(&stash_id)?;
+    let index_commit_id = stash_commit.first_parent()
+        .ok_or_else(|| anyhow::anyhow!("储藏 {} 格式错误（缺少暂存区快照）", &stash_id[..7]))?;
+    let index_commit = hash::read_commit(index_commit_id)?;
+    let working = hash::read_tree_entries(&stash_commit.tree_hash)?;
+    let index = hash::read_tree_entries(&index_commit.tree_hash)?;
+
+    // 修改工作区前确认快照引用的文件对象都存在
+    hash::ensure_objects_exist(working.iter().chain(&index).map(|entry| entry.hash.as_str()))?;
+
+    // 储藏时工作区中不存在的已跟踪文件需要删除
+    for entry in repo.index()? {
+        let abs_path = repo.root().join(&entry.path);
+        if !working.iter().any(|stashed| stashed.path == entry.path) && abs_path.is_file() {
+            fs::remove_file(&abs_path)
+                .context(format!("删除文件 {} 失败", abs_path.display()))?;
+        }
+    }
+
+    // 写回工作区快照，再恢复暂存区快照
+    for entry in &working {
+        let abs_path = repo.root().join(&entry.path);
+        if let Some(parent) = abs_path.parent()
+            && !parent.exists()
+        {
+            fs::create_dir_all(parent)
+                .context(format!("创建目录 {} 失败", parent.display()))?;
+        }
+        fs::write(&abs_path, eol::read_blob_for_work_tree(&entry.hash)?)
+            .context(format!("恢复文件 {} 失败", abs_path.display()))?;
+        metadata::apply_file_mode(&abs_path, entry.mode)?;
+    }
+    repo.write_index(&index)?;
+
+    write_stash_stack(repo, &stack)?;
+    println!("已恢复储藏 stash@{{0}}：{}", stash_commit.message);
+    Ok(())
+}
+
+/// 读取储藏栈（最早的在前）
+pub fn read_stash_stack(repo: &Repository) -> RustGitResult<Vec<String>> {
+    let log_path = repo.logs_dir().join("stash");
+    if !log_path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&log_path)
+        .context("读取储藏记录失败")?;
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// 写入储藏栈，并使 refs/stash 指向栈顶（栈为空时删除）
+fn write_stash_stack(repo: &Repository, stack: &[String]) -> RustGitResult<()> {
+    let log_path = repo.logs_dir().join("stash");
+    let ref_path = repo.git_dir().join("refs/stash");
+    match stack.last() {
+        Some(top) => {
+            fs::create_dir_all(repo.logs_dir()).context("创建日志目录失败")?;
+            let content: String = stack.iter().map(|id| format!("{}\n", id)).collect();
+            fs::write(&log_path, content).context("写入储藏记录失败")?;
+            utils_fs::write_atomic(&ref_path, top).context("更新 refs/stash 失败")?;
+        }
+        None => {
+            for path in [&log_path, &ref_path] {
+                if path.exists() {
+                    fs::remove_file(path)
+                        .context(format!("删除 {} 失败", path.display()))?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
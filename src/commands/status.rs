@@ -0,0 +1,124 @@
+use crate::api::{self, FileState, StatusEntry};
+use crate::commands::merge;
+use crate::utils::color;
+use crate::utils::fs as utils_fs;
+use crate::utils::repository::Repository;
+use crate::RustGitResult;
+
+/// 实现 git status 核心逻辑（porcelain 时输出 Git porcelain v1 格式的机器可读结果；
+/// short 时在同样的状态行之前输出分支头部行）
+pub fn status(porcelain: bool, short: bool) -> RustGitResult<()> {
+    // 裸仓库没有工作区
+    utils_fs::ensure_work_tree()?;
+
+    // 检查仓库是否初始化
+    if !utils_fs::is_repo_initialized() {
+        return Err(anyhow::anyhow!("未初始化 rust-git 仓库，请先执行 `rust-git init`"));
+    }
+
+    let repo = Repository::open()?;
+    let entries = api::status(&repo)?;
+    let staged: Vec<(FileState, &str)> = entries
+        .iter()
+        .filter_map(|entry| entry.staged.map(|state| (state, entry.path.as_str())))
+        .collect();
+    let unstaged: Vec<(FileState, &str)> = entries
+        .iter()
+        .filter(|entry| entry.unstaged != Some(FileState::Untracked))
+        .filter_map(|entry| entry.unstaged.map(|state| (state, entry.path.as_str())))
+        .collect();
+    let untracked: Vec<&str> = entries
+        .iter()
+        .filter(|entry| entry.unstaged == Some(FileState::Untracked))
+        .map(|entry| entry.path.as_str())
+        .collect();
+
+    if porcelain || short {
+        if short {
+            println!("{}", short_branch_header()?);
+        }
+        print_porcelain(&entries);
+        return Ok(());
+    }
+
+    // 按 Git 风格分组输出
+    let current_branch = utils_fs::get_current_branch()?;
+    let head = utils_fs::resolve_head()?;
+    match &head {
+        Some(commit_id) if current_branch == utils_fs::DETACHED_HEAD => {
+            println!("HEAD 分离于 {}", &commit_id[..commit_id.len().min(7)]);
+        }
+        _ => println!("位于分支 {}", current_branch),
+    }
+    if head.is_none() {
+        println!("\n尚无提交");
+    }
+    if merge::merge_in_progress(&repo) {
+        println!("\n合并尚未完成（修正冲突并暂存后执行 commit，或使用 merge --abort 中止合并）");
+        for path in merge::read_merge_conflicts(&repo)? {
+            println!("  {}", color::red(&format!("冲突：{}", path)));
+        }
+    }
+    if staged.is_empty() && unstaged.is_empty() && untracked.is_empty() {
+        println!("无文件要提交，工作区干净");
+        return Ok(());
+    }
+
+    if !staged.is_empty() {
+        println!("\n要提交的变更：");
+        for (state, path) in &staged {
+            println!("  {}", color::green(&format!("{}：{}", describe(*state), path)));
+        }
+    }
+
+    if !unstaged.is_empty() {
+        println!("\n尚未暂存以备提交的变更：");
+        for (state, path) in &unstaged {
+            println!("  {}", color::red(&format!("{}：{}", describe(*state), path)));
+        }
+    }
+
+    if !untracked.is_empty() {
+        println!("\n未跟踪的文件：");
+        for path in &untracked {
+            println!("  {}", color::red(path));
+        }
+    }
+
+    Ok(())
+}
+
+/// 简短格式的分支头部行（与 `git status -sb` 一致；暂无远程仓库，不输出领先/落后的提交数）
+fn short_branch_header() -> RustGitResult<String> {
+    let current_branch = utils_fs::get_current_branch()?;
+    Ok(match utils_fs::resolve_head()? {
+        None => format!("## No commits yet on {}", current_branch),
+        Some(_) if current_branch == utils_fs::DETACHED_HEAD => "## HEAD (no branch)".to_string(),
+        Some(_) => format!("## {}", current_branch),
+    })
+}
+
+/// 输出 porcelain v1 格式：两列状态码（暂存区、工作区）+ 空格 + 路径，未跟踪文件为 `??`
+fn print_porcelain(entries: &[StatusEntry]) {
+    let code = |state: Option<FileState>| state.map_or(' ', FileState::code);
+    // 未跟踪文件排在已跟踪文件之后
+    let (untracked, tracked): (Vec<_>, Vec<_>) = entries
+        .iter()
+        .partition(|entry| entry.unstaged == Some(FileState::Untracked));
+    for entry in tracked {
+        println!("{}{} {}", code(entry.staged), code(entry.unstaged), entry.path);
+    }
+    for entry in untracked {
+        println!("?? {}", entry.path);
+    }
+}
+
+/// 文件状态对应的变更说明
+fn describe(state: FileState) -> &'static str {
+    match state {
+        FileState::Added => "新文件",
+        FileState::Deleted => "删除",
+        FileState::Modified => "修改",
+        FileState::Untracked => "未跟踪",
+    }
+}
@@ -0,0 +1,26 @@
+use crate::commands::checkout;
+use crate::utils::{fs as utils_fs, metadata};
+use crate::utils::repository::Repository;
+use crate::RustGitResult;
+
+/// 实现 git switch 核心逻辑（切换分支；工作区有未提交修改时默认拒绝）
+pub fn switch(repo: &Repository, name: &str, create: bool, force: bool) -> RustGitResult<()> {
+    // 裸仓库没有工作区
+    repo.ensure_work_tree()?;
+
+    // 持有暂存区锁直到命令结束，防止并发进程交错读写暂存区
+    let _lock = utils_fs::IndexLock::acquire(repo)?;
+
+    // -c：先基于当前 HEAD 创建分支（与 HEAD 指向同一提交，切换时保留工作区修改）
+    if create {
+        utils_fs::create_branch(name)?;
+        let commit_id = utils_fs::read_branch_commit(name)?;
+        metadata::append_reflog(None, &commit_id, name, "branch: Created from HEAD")?;
+        println!("已创建分支：{}", name);
+    } else if !utils_fs::list_branches()?.iter().any(|branch| branch == name) {
+        return Err(anyhow::anyhow!("分支 {} 不存在（如需创建请使用 -c）", name));
+    }
+
+    // 目标分支指向其他提交且有未提交的修改时，除非 --force 否则拒绝切换
+    checkout::checkout_branch(repo, name, force)
+}
@@ -0,0 +1,43 @@
+use crate::utils::fs;
+use crate::RustGitResult;
+
+/// 实现 git tag 核心逻辑（annotate 或指定 message 时创建附注标签）
+pub fn tag(
+    name: Option<String>,
+    delete: Option<String>,
+    annotate: bool,
+    message: Option<String>,
+) -> RustGitResult<()> {
+    // 检查仓库是否初始化
+    if !fs::is_repo_initialized() {
+        return Err(anyhow::anyhow!("未初始化 rust-git 仓库，请先执行 `rust-git init`"));
+    }
+
+    // 处理删除标签
+    if let Some(tag_to_delete) = delete {
+        fs::delete_tag(&tag_to_delete)?;
+        println!("已删除标签：{}", tag_to_delete);
+        return Ok(());
+    }
+
+    // 处理创建标签（-m 隐含 -a；没有编辑器，附注标签必须通过 -m 提供标签信息）
+    if let Some(tag_name) = name {
+        if annotate && message.is_none() {
+            return Err(anyhow::anyhow!("创建附注标签需要使用 -m 指定标签信息"));
+        }
+        fs::create_tag(&tag_name, message.as_deref())?;
+        if message.is_some() {
+            println!("已创建附注标签：{}", tag_name);
+        } else {
+            println!("已创建标签：{}", tag_name);
+        }
+        return Ok(());
+    }
+
+    // 列出所有标签
+    for tag_name in fs::list_tags()? {
+        println!("{}", tag_name);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// 底层（utils / api）可供调用方匹配的错误类型
+///
+/// 各函数仍返回 `anyhow::Result`，调用方可通过 `err.downcast_ref::<GitError>()` 判断具体错误。
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum GitError {
+    #[error("未初始化 rust-git 仓库，请先执行 `rust-git init`")]
+    NotARepository,
+
+    #[error("当前为裸仓库（无工作区），无法执行该命令")]
+    BareRepository,
+
+    #[error("引用名不合法：{0}")]
+    InvalidRefName(String),
+
+    #[error("分支 {0} 已存在")]
+    BranchExists(String),
+
+    #[error("分支 {0} 不存在")]
+    BranchNotFound(String),
+
+    #[error("分支 {0} 包含未合并到其他分支的提交，删除后这些提交将丢失（如确定要删除，请使用 branch -D {0}）")]
+    BranchNotMerged(String),
+
+    #[error("标签 {0} 已存在")]
+    TagExists(String),
+
+    #[error("标签 {0} 不存在")]
+    TagNotFound(String),
+
+    #[error("path is outside the repository：{}（仓库根目录：{}）", path.display(), root.display())]
+    PathOutsideRepo { path: PathBuf, root: PathBuf },
+
+    #[error("对象不存在：{0}")]
+    ObjectNotFound(String),
+
+    #[error("哈希前缀有歧义：{prefix}（匹配 {count} 个对象）")]
+    AmbiguousPrefix { prefix: String, count: usize },
+
+    #[error("index is locked：{}（如果没有其他 rust-git 进程正在运行，请手动删除该文件）", .0.display())]
+    IndexLocked(PathBuf),
+
+    #[error("repository is missing objects: {}", .0.join(", "))]
+    MissingObjects(Vec<String>),
+}
@@ -4,28 +4,231 @@ use clap::Parser;
 #[derive(Parser, Debug)]
 #[command(author, version, about = "Rust 实现的简易 Git", long_about = None)]
 pub struct Cli {
+    #[arg(long, global = true)]
+    pub no_color: bool, // 禁用彩色输出（非终端输出时自动禁用）
     #[command(subcommand)]
     pub command: Commands,
 }
 
 #[derive(Parser, Debug)]
 pub enum Commands {
-    Init,                  // 无参数
-    Add { path: String },  // 接收文件/目录路径
-    Rm { path: String },   // 接收文件/目录路径
-    Commit { message: String }, // 接收提交信息
-    Log,                      // git log：无参数（简化版）
+    Init {
+        #[arg(required = false)]
+        path: Option<String>, // 初始化的目标目录（默认当前目录）
+        #[arg(long)]
+        bare: bool,           // 创建无工作区的裸仓库
+        #[arg(short = 'b', long = "initial-branch")]
+        initial_branch: Option<String>, // 初始分支名（默认 master）
+    },
+    Add {
+        #[arg(required_unless_present = "all", num_args = 1..)]
+        paths: Vec<String>, // 接收一个或多个文件/目录路径
+        #[arg(short = 'A', long = "all")]
+        all: bool,          // 暂存整个工作区的全部变更（包括删除），忽略 paths
+        #[arg(short = 'n', long = "dry-run")]
+        dry_run: bool,      // 只列出将被暂存的文件，不修改暂存区
+        #[arg(short = 'v', long)]
+        verbose: bool,      // 逐个输出被暂存的文件
+        #[arg(short = 'p', long, requires = "hunks", conflicts_with_all = ["all", "dry_run"])]
+        patch: bool,        // 只暂存单个文件中 --hunks 选中的块
+        #[arg(long, value_delimiter = ',', requires = "patch")]
+        hunks: Vec<usize>,  // 要暂存的块编号（从 1 开始，与 diff 输出的块顺序一致），如 1,3
+    },
+    Rm {
+        path: String,  // 接收文件/目录路径
+        #[arg(long)]
+        cached: bool,  // 只从暂存区移除，保留工作区文件
+    },
+    Commit {
+        #[arg(required_unless_present = "file", conflicts_with = "file")]
+        message: Option<String>, // 接收提交信息
+        #[arg(short = 'F', long = "file")]
+        file: Option<String>,    // 从文件读取提交信息（`-` 表示标准输入）
+        #[arg(long)]
+        allow_empty_message: bool, // 允许空的提交信息
+        #[arg(long)]
+        allow_empty: bool, // 允许提交空目录树或与父提交相同的目录树（无变更的空提交）
+        #[arg(long)]
+        author: Option<String>, // 覆盖作者信息（格式：Name <email>）
+        #[arg(long)]
+        date: Option<String>,   // 覆盖提交时间（RFC3339 或 Unix 时间戳）
+        #[arg(last = true)]
+        paths: Vec<String>,     // 只提交这些路径的暂存变更（`--` 之后指定）
+        #[arg(short = 'q', long)]
+        quiet: bool,            // 不输出提交成功后的摘要信息
+    },
+    Log {
+        #[arg(long)]
+        oneline: bool, // 每个提交只输出一行（短哈希 + 提交信息）
+        #[arg(short = 'n', long = "max-count")]
+        max_count: Option<usize>, // 最多输出的提交数
+        #[arg(long)]
+        graph: bool,   // 绘制 ASCII 提交图
+        #[arg(required = false, conflicts_with = "graph")]
+        path: Option<String>, // 只显示修改了该文件/目录的提交
+        #[arg(long = "name-only", conflicts_with = "graph")]
+        name_only: bool,      // 在每个提交后列出其相对父提交修改的文件
+        #[arg(long, conflicts_with = "graph")]
+        since: Option<String>, // 只显示该时间及之后的提交（RFC3339 / Unix 时间戳 / YYYY-MM-DD）
+        #[arg(long, conflicts_with = "graph")]
+        until: Option<String>, // 只显示该时间及之前的提交（YYYY-MM-DD 包含当天）
+    },
     Branch {
         #[arg(required = false)]
-        name: Option<String>, // 分支名（创建分支时必填）
+        name: Option<String>, // 分支名（创建分支时必填；列出分支时为 glob 过滤模式）
         #[arg(short, long)]
-        delete: Option<String>, // 删除分支（-d/--delete）
+        delete: Option<String>, // 删除分支（-d/--delete；分支有未合并到其他分支的提交时拒绝删除）
+        #[arg(short = 'D', conflicts_with = "delete")]
+        force_delete: Option<String>, // 强制删除分支（等同于 -d -f）
+        #[arg(short, long, requires = "delete")]
+        force: bool,            // 删除时不检查分支是否已合并
+        #[arg(short = 'm', long = "move")]
+        rename: Option<String>, // 重命名分支（-m/--move，name 为旧分支名，默认当前分支）
+        #[arg(short = 'l', long)]
+        list: bool,             // 列出分支（name 作为过滤模式）
+        #[arg(short = 'v', long)]
+        verbose: bool,          // 列出分支及其最新提交的短哈希和标题（隐含 --list）
+    },
+    Checkout {
+        #[arg(required_unless_present = "paths", conflicts_with = "paths")]
+        target: Option<String>, // 分支名 / 标签名 / 修订 / 文件路径
+        #[arg(last = true)]
+        paths: Vec<String>,     // 要从暂存区恢复的文件（`--` 之后指定，不会被当作分支名）
+    },
+    Status {
+        #[arg(long)]
+        porcelain: bool, // 输出机器可读的 porcelain v1 格式
+        #[arg(short = 's', long, conflicts_with = "porcelain")]
+        short: bool,     // 简短格式：首行为 `## <分支>`，其后每个文件一行状态码
+    },
+    Diff {
+        #[arg(requires = "b")]
+        a: Option<String>, // 旧修订（与 b 同时指定时比较两个提交，否则比较暂存区与工作区）
+        b: Option<String>, // 新修订
+        #[arg(long)]
+        stat: bool,        // 只输出每个文件的增删行数统计及汇总
+    },
+    Reset {
+        #[arg(required = false)]
+        path: Option<String>, // 取消暂存的路径（不指定则清空暂存区）
+    },
+    Config {
+        #[arg(required_unless_present = "list")]
+        key: Option<String>,   // 配置项（如 user.name）
+        #[arg(required = false)]
+        value: Option<String>, // 配置值（不指定则打印当前值）
+        #[arg(long)]
+        list: bool,            // 列出全部配置项
+    },
+    Show { target: String },  // 提交（HEAD / 分支名 / 哈希 / HEAD~n）
+    CatFile {
+        hash: String,     // 对象哈希
+        #[arg(short = 'p')]
+        pretty: bool,     // 格式化输出对象内容
+        #[arg(short = 't')]
+        type_only: bool,  // 只输出对象类型
+    },
+    Reflog,                   // git reflog：无参数
+    Merge {
+        #[arg(required_unless_present = "abort", conflicts_with = "abort")]
+        branch: Option<String>, // 要合并到当前分支的分支名
+        #[arg(long)]
+        abort: bool,            // 中止未完成的合并，恢复到合并前的状态
+    },
+    Restore {
+        #[arg(required = true, num_args = 1..)]
+        paths: Vec<String>, // 要恢复的文件路径（可为多个文件、目录或 glob 模式）
+        #[arg(long)]
+        staged: bool,  // 恢复暂存区（取消暂存）而非工作区
+    },
+    Tag {
+        #[arg(required = false)]
+        name: Option<String>,   // 标签名（创建标签时必填）
+        #[arg(short = 'd')]
+        delete: Option<String>, // 删除标签（-d）
+        #[arg(short = 'a', requires = "name")]
+        annotate: bool,         // 创建附注标签（需配合 -m 指定标签信息）
+        #[arg(short = 'm', requires = "name")]
+        message: Option<String>, // 附注标签的标签信息（隐含 -a）
+    },
+    Mv { from: String, to: String }, // 重命名已跟踪的文件
+    Fsck {                    // git fsck：校验对象完整性
+        #[arg(short = 'v', long)]
+        verbose: bool, // 输出检查的对象数、损坏对象数和悬空引用数
+    },
+    Gc {
+        #[arg(long)]
+        dry_run: bool, // 只列出将被删除的不可达对象
+        #[arg(short = 'v', long)]
+        verbose: bool, // 输出扫描、可达、删除的对象数及回收的字节数
+    },
+    RevParse { rev: String }, // 修订（HEAD / 分支名 / 标签名 / 缩写哈希）
+    Blame { path: String },   // 要逐行追溯的文件路径
+    LsFiles {
+        #[arg(short = 's')]
+        stage: bool, // 同时输出模式和 blob 哈希
+    },
+    Switch {
+        name: String, // 目标分支名
+        #[arg(short = 'c')]
+        create: bool, // 先创建分支再切换
+        #[arg(short = 'f', long)]
+        force: bool,  // 忽略未提交的修改强制切换（会覆盖工作区）
+    },
+    Stash {
+        #[arg(long)]
+        pop: bool, // 恢复最近一次储藏并将其移除
+    },
+    Revert {
+        commit: String, // 要撤销的提交（HEAD / 分支名 / 哈希 / HEAD~n）
+    },
+    CherryPick {
+        commit: String, // 要重放到当前分支的提交（HEAD / 分支名 / 哈希 / HEAD~n）
+    },
+    Describe {
+        #[arg(required = false)]
+        rev: Option<String>, // 要描述的修订（默认 HEAD）
+    },
+    MergeBase {
+        a: String, // 第一个修订
+        b: String, // 第二个修订
+    },
+    Rebase {
+        onto: String, // 将当前分支的提交重放到其上的目标（分支名 / 标签 / 哈希）
+    },
+    CountObjects {
+        #[arg(short = 'v', long)]
+        verbose: bool, // 按对象类型（blob / tree / commit）分别统计
     },
-    Checkout {target: String},
 }
 
 pub type RustGitResult<T> = Result<T>;
 
+/// 命令输出的详细程度（由 --quiet / --verbose 决定）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Verbosity {
+    Quiet,   // 只输出警告和错误
+    #[default]
+    Normal,  // 输出摘要信息
+    Verbose, // 额外输出逐个文件的处理过程
+}
+
+impl Verbosity {
+    /// 由 quiet / verbose 开关确定详细程度（quiet 优先）
+    pub fn from_flags(quiet: bool, verbose: bool) -> Verbosity {
+        match (quiet, verbose) {
+            (true, _) => Verbosity::Quiet,
+            (false, true) => Verbosity::Verbose,
+            (false, false) => Verbosity::Normal,
+        }
+    }
+}
+
+pub mod error;
+
+/// 供其他工具嵌入调用的接口（返回结构化结果，不打印）
+pub mod api;
+
 pub mod commands {
     pub mod init; 
     pub mod add;
@@ -34,10 +237,41 @@ pub mod commands {
     pub mod log;
     pub mod branch;
     pub mod checkout;
+    pub mod status;
+    pub mod diff;
+    pub mod reset;
+    pub mod config;
+    pub mod show;
+    pub mod cat_file;
+    pub mod reflog;
+    pub mod merge;
+    pub mod restore;
+    pub mod tag;
+    pub mod mv;
+    pub mod fsck;
+    pub mod gc;
+    pub mod rev_parse;
+    pub mod blame;
+    pub mod ls_files;
+    pub mod switch;
+    pub mod stash;
+    pub mod revert;
+    pub mod cherry_pick;
+    pub mod describe;
+    pub mod merge_base;
+    pub mod rebase;
+    pub mod count_objects;
 }
 
 pub mod utils {
     pub mod fs; 
     pub mod hash;
     pub mod metadata;
+    pub mod ignore;
+    pub mod config;
+    pub mod repository;
+    pub mod color;
+    pub mod hooks;
+    pub mod merge;
+    pub mod eol;
 }
\ No newline at end of file
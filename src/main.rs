@@ -1,31 +1,118 @@
 use clap::Parser;
 use anyhow::Context;
-use rust_git::{Cli, Commands, commands};
+use rust_git::{Cli, Commands, Verbosity, commands};
+use rust_git::utils::color;
+use rust_git::utils::repository::Repository;
 
 fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
+    color::init(cli.no_color);
 
     match cli.command {
-        Commands::Init => {
-            commands::init::init().context("执行 init 命令失败")?;
+        Commands::Init { path, bare, initial_branch } => {
+            commands::init::init(path.as_deref(), bare, initial_branch.as_deref()).context("执行 init 命令失败")?;
         }
-        Commands::Add { path } => {
-            commands::add::add(&path).context(format!("执行 add 命令失败（路径：{}）", path))?;
+        Commands::Add { paths, all, dry_run, verbose, patch, hunks } => {
+            if patch {
+                commands::add::add_hunks(&Repository::open()?, &paths, &hunks).context(format!("执行 add --patch 命令失败（路径：{}）", paths.join(", ")))?;
+            } else {
+                commands::add::add(&Repository::open()?, &paths, all, dry_run, Verbosity::from_flags(false, verbose)).context(format!("执行 add 命令失败（路径：{}）", paths.join(", ")))?;
+            }
         }
-        Commands::Rm { path } => {
-            commands::rm::rm(&path).context(format!("执行 rm 命令失败（路径：{}）", path))?;
+        Commands::Rm { path, cached } => {
+            commands::rm::rm(&path, cached).context(format!("执行 rm 命令失败（路径：{}）", path))?;
         }
-        Commands::Commit { message } => {
-            commands::commit::commit(&message).context(format!("执行 commit 命令失败（信息：{}）", message))?;
+        Commands::Commit { message, file, allow_empty_message, allow_empty, author, date, paths, quiet } => {
+            let message = commands::commit::read_message(message.as_deref(), file.as_deref(), allow_empty_message).context("执行 commit 命令失败")?;
+            commands::commit::commit(&Repository::open()?, &message, allow_empty, author.as_deref(), date.as_deref(), &paths, Verbosity::from_flags(quiet, false)).context(format!("执行 commit 命令失败（信息：{}）", message))?;
         }
-        Commands::Log => {
-            commands::log::log().context("执行 log 命令失败")?;
+        Commands::Log { oneline, max_count, graph, path, name_only, since, until } => {
+            commands::log::log(oneline, max_count, graph, path.as_deref(), name_only, since.as_deref(), until.as_deref()).context("执行 log 命令失败")?;
         }
-        Commands::Branch { name, delete } => {
-            commands::branch::branch(name, delete).context("执行 branch 命令失败")?;
+        Commands::Branch { name, delete, force_delete, force, rename, list, verbose } => {
+            // -D 等同于 -d -f
+            let force = force || force_delete.is_some();
+            let delete = delete.or(force_delete);
+            commands::branch::branch(name, delete, force, rename, list, verbose).context("执行 branch 命令失败")?;
         }
-        Commands::Checkout { target } => {
-            commands::checkout::checkout(&target).context("执行 checkout 命令失败")?;
+        Commands::Checkout { target, paths } => {
+            commands::checkout::checkout(&Repository::open()?, target.as_deref(), &paths).context("执行 checkout 命令失败")?;
+        }
+        Commands::Status { porcelain, short } => {
+            commands::status::status(porcelain, short).context("执行 status 命令失败")?;
+        }
+        Commands::Diff { a, b, stat } => {
+            commands::diff::diff(a.as_deref(), b.as_deref(), stat).context("执行 diff 命令失败")?;
+        }
+        Commands::Reset { path } => {
+            commands::reset::reset(path.as_deref()).context("执行 reset 命令失败")?;
+        }
+        Commands::Config { key, value, list } => {
+            commands::config::config(key.as_deref(), value.as_deref(), list).context("执行 config 命令失败")?;
+        }
+        Commands::Show { target } => {
+            commands::show::show(&target).context(format!("执行 show 命令失败（目标：{}）", target))?;
+        }
+        Commands::CatFile { hash, pretty, type_only } => {
+            commands::cat_file::cat_file(&hash, pretty, type_only).context(format!("执行 cat-file 命令失败（对象：{}）", hash))?;
+        }
+        Commands::Reflog => {
+            commands::reflog::reflog().context("执行 reflog 命令失败")?;
+        }
+        Commands::Merge { branch, abort } => {
+            let context = match &branch {
+                Some(branch) => format!("执行 merge 命令失败（分支：{}）", branch),
+                None => "执行 merge --abort 失败".to_string(),
+            };
+            commands::merge::merge(branch.as_deref(), abort).context(context)?;
+        }
+        Commands::Restore { paths, staged } => {
+            commands::restore::restore(&paths, staged).context(format!("执行 restore 命令失败（路径：{}）", paths.join(", ")))?;
+        }
+        Commands::Tag { name, delete, annotate, message } => {
+            commands::tag::tag(name, delete, annotate, message).context("执行 tag 命令失败")?;
+        }
+        Commands::Mv { from, to } => {
+            commands::mv::mv(&from, &to).context(format!("执行 mv 命令失败（{} -> {}）", from, to))?;
+        }
+        Commands::Fsck { verbose } => {
+            commands::fsck::fsck(verbose).context("执行 fsck 命令失败")?;
+        }
+        Commands::Gc { dry_run, verbose } => {
+            commands::gc::gc(dry_run, verbose).context("执行 gc 命令失败")?;
+        }
+        Commands::RevParse { rev } => {
+            commands::rev_parse::rev_parse(&rev).context(format!("执行 rev-parse 命令失败（修订：{}）", rev))?;
+        }
+        Commands::Blame { path } => {
+            commands::blame::blame(&path).context(format!("执行 blame 命令失败（路径：{}）", path))?;
+        }
+        Commands::LsFiles { stage } => {
+            commands::ls_files::ls_files(&Repository::open()?, stage).context("执行 ls-files 命令失败")?;
+        }
+        Commands::Switch { name, create, force } => {
+            commands::switch::switch(&Repository::open()?, &name, create, force).context(format!("执行 switch 命令失败（分支：{}）", name))?;
+        }
+        Commands::Stash { pop } => {
+            commands::stash::stash(&Repository::open()?, pop).context("执行 stash 命令失败")?;
+        }
+        Commands::Revert { commit } => {
+            commands::revert::revert(&Repository::open()?, &commit).context(format!("执行 revert 命令失败（提交：{}）", commit))?;
+        }
+        Commands::CherryPick { commit } => {
+            commands::cherry_pick::cherry_pick(&Repository::open()?, &commit).context(format!("执行 cherry-pick 命令失败（提交：{}）", commit))?;
+        }
+        Commands::Describe { rev } => {
+            commands::describe::describe(rev.as_deref()).context("执行 describe 命令失败")?;
+        }
+        Commands::MergeBase { a, b } => {
+            commands::merge_base::merge_base(&a, &b).context(format!("执行 merge-base 命令失败（修订：{} {}）", a, b))?;
+        }
+        Commands::Rebase { onto } => {
+            commands::rebase::rebase(&Repository::open()?, &onto).context(format!("执行 rebase 命令失败（目标：{}）", onto))?;
+        }
+        Commands::CountObjects { verbose } => {
+            commands::count_objects::count_objects(verbose).context("执行 count-objects 命令失败")?;
         }
     }
 
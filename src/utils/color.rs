@@ -0,0 +1,57 @@
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// 是否输出 ANSI 颜色（由 `init` 在启动时设置，默认关闭）
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// 决定是否启用颜色：未指定 --no-color、未设置 NO_COLOR 环境变量且标准输出为终端时启用
+pub fn init(no_color: bool) {
+    let enabled = !no_color
+        && std::env::var_os("NO_COLOR").is_none()
+        && std::io::stdout().is_terminal();
+    set_enabled(enabled);
+}
+
+/// 直接设置是否启用颜色
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// 当前是否启用颜色
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// 绿色（新增内容、已暂存的变更）
+pub fn green(text: &str) -> String {
+    paint("32", text)
+}
+
+/// 红色（删除内容、未暂存的变更）
+pub fn red(text: &str) -> String {
+    paint("31", text)
+}
+
+/// 黄色（提交哈希）
+pub fn yellow(text: &str) -> String {
+    paint("33", text)
+}
+
+/// 青色（差异块头部）
+pub fn cyan(text: &str) -> String {
+    paint("36", text)
+}
+
+/// 粗体（差异文件头部）
+pub fn bold(text: &str) -> String {
+    paint("1", text)
+}
+
+/// 启用颜色时用 ANSI 转义序列包裹文本，否则原样返回
+fn paint(code: &str, text: &str) -> String {
+    if enabled() {
+        format!("\x1b[{}m{}\x1b[0m", code, text)
+    } else {
+        text.to_string()
+    }
+}
@@ -0,0 +1,107 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use crate::utils::eol;
+use crate::utils::repository::Repository;
+
+/// 读取全部配置项（仓库配置文件 .rust-git/config）
+pub fn read_config() -> Result<BTreeMap<String, String>> {
+    read_config_file(&Repository::open()?.config_path())
+}
+
+/// 写入全部配置项
+pub fn write_config(config: &BTreeMap<String, String>) -> Result<()> {
+    write_config_file(&Repository::open()?.config_path(), config)?;
+    // 配置已改变，下次查询时重新读取
+    eol::clear_autocrlf_cache();
+    Ok(())
+}
+
+/// 读取指定配置文件（INI 风格：`[section]` 下每行 `name = value`，# 或 ; 开头为注释）
+///
+/// 返回的键为 `section.name`；兼容旧版不带小节、每行 `section.name = value` 的格式。
+pub fn read_config_file(path: &Path) -> Result<BTreeMap<String, String>> {
+    let mut config = BTreeMap::new();
+    if !path.exists() {
+        return Ok(config);
+    }
+
+    let content = fs::read_to_string(path)
+        .context("读取配置文件失败")?;
+    let mut section: Option<String> = None;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            section = Some(name.trim().to_string());
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            let key = match &section {
+                Some(section) => format!("{}.{}", section, key.trim()),
+                None => key.trim().to_string(),
+            };
+            config.insert(key, value.trim().to_string());
+        }
+    }
+
+    Ok(config)
+}
+
+/// 写入指定配置文件（按 `section.name` 的第一段分组为 INI 小节）
+pub fn write_config_file(path: &Path, config: &BTreeMap<String, String>) -> Result<()> {
+    let mut sections: BTreeMap<&str, Vec<(&str, &str)>> = BTreeMap::new();
+    for (key, value) in config {
+        let (section, name) = key.split_once('.').unwrap_or(("", key.as_str()));
+        sections.entry(section).or_default().push((name, value));
+    }
+
+    let mut content = String::new();
+    for (section, entries) in sections {
+        content.push_str(&format!("[{}]\n", section));
+        for (name, value) in entries {
+            content.push_str(&format!("\t{} = {}\n", name, value));
+        }
+    }
+    fs::write(path, content)
+        .context("写入配置文件失败")?;
+    Ok(())
+}
+
+/// 解析布尔配置值（true/yes/on/1 与 false/no/off/0，不区分大小写）
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Some(true),
+        "false" | "no" | "off" | "0" | "" => Some(false),
+        _ => None,
+    }
+}
+
+/// 读取单个配置项
+pub fn get(key: &str) -> Result<Option<String>> {
+    Ok(read_config()?.remove(key))
+}
+
+/// 设置单个配置项
+pub fn set(key: &str, value: &str) -> Result<()> {
+    let mut config = read_config()?;
+    config.insert(key.to_string(), value.to_string());
+    write_config(&config)
+}
+
+/// 读取字符串配置项，未设置时返回默认值
+pub fn get_str(key: &str, default: &str) -> Result<String> {
+    Ok(get(key)?.unwrap_or_else(|| default.to_string()))
+}
+
+/// 读取布尔配置项，未设置时返回默认值
+pub fn get_bool(key: &str, default: bool) -> Result<bool> {
+    match get(key)? {
+        Some(value) => parse_bool(&value)
+            .ok_or_else(|| anyhow::anyhow!("配置项 {} 的值不是合法的布尔值：{}", key, value)),
+        None => Ok(default),
+    }
+}
@@ -0,0 +1,90 @@
+use anyhow::{Context, Result};
+use crate::utils::{config, hash};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 已读取的 core.autocrlf 及读取时的当前目录（每个文件都会查询，避免反复读取解析配置文件）
+static AUTOCRLF: Mutex<Option<(PathBuf, bool)>> = Mutex::new(None);
+
+/// 是否启用 core.autocrlf：检出时将 LF 转为 CRLF，添加时将 CRLF 转回 LF（二进制文件不转换）
+///
+/// 结果按当前目录缓存，切换到其他仓库后重新读取；修改配置文件后需调用 `clear_autocrlf_cache`。
+pub fn autocrlf() -> Result<bool> {
+    let current_dir = std::env::current_dir().context("获取当前目录失败")?;
+    let mut cached = AUTOCRLF.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some((dir, enabled)) = cached.as_ref()
+        && *dir == current_dir
+    {
+        return Ok(*enabled);
+    }
+    let enabled = config::get_bool("core.autocrlf", false)?;
+    *cached = Some((current_dir, enabled));
+    Ok(enabled)
+}
+
+/// 清除 core.autocrlf 的缓存（写入配置文件后调用）
+pub fn clear_autocrlf_cache() {
+    *AUTOCRLF.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+}
+
+/// 含 NUL 字节的内容视为二进制
+pub fn is_binary(content: &[u8]) -> bool {
+    content.contains(&0)
+}
+
+/// 将 CRLF 换行转换为 LF（单独的 CR 保持不变）
+pub fn crlf_to_lf(content: &[u8]) -> Vec<u8> {
+    let mut converted = Vec::with_capacity(content.len());
+    for (i, &byte) in content.iter().enumerate() {
+        if byte == b'\r' && content.get(i + 1) == Some(&b'\n') {
+            continue;
+        }
+        converted.push(byte);
+    }
+    converted
+}
+
+/// 将 LF 换行转换为 CRLF（已是 CRLF 的换行保持不变）
+pub fn lf_to_crlf(content: &[u8]) -> Vec<u8> {
+    let mut converted = Vec::with_capacity(content.len() + content.len() / 16);
+    for (i, &byte) in content.iter().enumerate() {
+        if byte == b'\n' && (i == 0 || content[i - 1] != b'\r') {
+            converted.push(b'\r');
+        }
+        converted.push(byte);
+    }
+    converted
+}
+
+/// 写入工作区前的内容转换（启用 core.autocrlf 时文本文件转为 CRLF）
+pub fn to_work_tree(content: Vec<u8>) -> Result<Vec<u8>> {
+    if autocrlf()? && !is_binary(&content) {
+        return Ok(lf_to_crlf(&content));
+    }
+    Ok(content)
+}
+
+/// 存入对象库前的内容转换（启用 core.autocrlf 时文本文件转回 LF）
+pub fn to_repo(content: Vec<u8>) -> Result<Vec<u8>> {
+    if autocrlf()? && !is_binary(&content) {
+        return Ok(crlf_to_lf(&content));
+    }
+    Ok(content)
+}
+
+/// 读取 blob 对象并转换为写入工作区的内容
+pub fn read_blob_for_work_tree(hash: &str) -> Result<Vec<u8>> {
+    to_work_tree(hash::read_object(hash)?)
+}
+
+/// 启用 core.autocrlf 时读取工作区文件并转换为存入对象库的内容；
+/// 未启用时返回 None，调用方可直接流式读取文件
+pub fn read_clean_file(path: &Path) -> Result<Option<Vec<u8>>> {
+    if !autocrlf()? {
+        return Ok(None);
+    }
+    let content = fs::read(path)
+        .context(format!("读取文件失败：{}", path.display()))?;
+    Ok(Some(to_repo(content)?))
+}
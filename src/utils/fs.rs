@@ -1,65 +1,97 @@
 use anyhow::{Context, Result};
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
-use winapi::um::fileapi::CreateDirectoryW;
-use winapi::um::errhandlingapi::GetLastError;
-use std::os::windows::ffi::OsStrExt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use globset::Glob;
 use serde_json::Value;
+use crate::error::GitError;
+use crate::utils::config;
+use crate::utils::hash;
+use crate::utils::metadata::{self, IndexEntry};
+use crate::utils::repository::{Repository, GIT_DIR_NAME};
 
-/// 检查当前目录是否已初始化 rust-git 仓库
+/// 检查当前目录（或其上级目录）是否已初始化 rust-git 仓库
 pub fn is_repo_initialized() -> bool {
-    Path::new(".rust-git").exists()
+    Repository::open().is_ok()
 }
 
-/// 创建 .rust-git 目录结构
-pub fn create_repo_dirs() -> Result<()> {
-    let dirs = [
-        ".rust-git",
-        ".rust-git/objects",    // 存储对象（文件/提交/目录树）
-        ".rust-git/refs",       // 引用（分支/标签）
-        ".rust-git/refs/heads", // 分支存储目录
-        ".rust-git/logs",       // 日志
-    ];
+/// 未指定初始分支时使用的默认分支名
+pub const DEFAULT_BRANCH: &str = "master";
 
-    for dir in dirs {
-        let path = Path::new(dir);
-        if !path.exists() {
-            let wide_path: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
-            let success = unsafe { CreateDirectoryW(wide_path.as_ptr(), std::ptr::null_mut()) != 0 };
-
-            if !success {
-                let err = unsafe { GetLastError() };
-                if err != 183 { // 183 = 目录已存在（忽略该错误）
-                    return Err(anyhow::anyhow!("创建目录失败（错误码：{}）：{}", err, dir));
-                }
-            }
-        }
-    }
+/// 在 base 目录下创建 .rust-git 目录结构（HEAD 指向 initial_branch）
+pub fn create_repo_dirs(base: &Path, initial_branch: &str) -> Result<()> {
+    let git_dir = base.join(GIT_DIR_NAME);
+    create_repo_layout(&git_dir, initial_branch)?;
 
     // 初始化暂存区（index）文件
-    let index_path = Path::new(".rust-git/index");
+    let index_path = git_dir.join("index");
     if !index_path.exists() {
         fs::write(index_path, "[]")
             .context("初始化暂存区 index 文件失败")?;
     }
 
-    // 初始化 HEAD 文件，指向默认分支 master
-    let head_path = Path::new(".rust-git/HEAD");
+    Ok(())
+}
+
+/// 在 base 目录下直接创建裸仓库结构（无工作区、无暂存区），并标记 core.bare = true
+pub fn create_bare_repo_dirs(base: &Path, initial_branch: &str) -> Result<()> {
+    create_repo_layout(base, initial_branch)?;
+
+    let config_path = base.join("config");
+    let mut repo_config = config::read_config_file(&config_path)?;
+    repo_config.insert("core.bare".to_string(), "true".to_string());
+    config::write_config_file(&config_path, &repo_config)
+}
+
+/// 在 git_dir 下创建对象/引用/日志目录，以及 HEAD 和初始分支
+fn create_repo_layout(git_dir: &Path, initial_branch: &str) -> Result<()> {
+    let dirs = [
+        "",
+        "objects",    // 存储对象（文件/提交/目录树）
+        "refs",       // 引用（分支/标签）
+        "refs/heads", // 分支存储目录
+        "refs/tags",  // 标签存储目录
+        "logs",       // 日志
+    ];
+
+    for dir in dirs {
+        // create_dir_all 对已存在的目录直接返回成功
+        let path = git_dir.join(dir);
+        fs::create_dir_all(&path)
+            .context(format!("创建目录失败：{}", path.display()))?;
+    }
+
+    // 初始化 HEAD 文件，指向初始分支
+    let head_path = git_dir.join("HEAD");
     if !head_path.exists() {
-        fs::write(head_path, "ref: refs/heads/master")
+        fs::write(head_path, format!("ref: refs/heads/{}", initial_branch))
             .context("初始化 HEAD 文件失败")?;
     }
 
-    // 创建默认分支 master 文件
-    let master_branch = Path::new(".rust-git/refs/heads/master");
-    if !master_branch.exists() {
-        fs::write(master_branch, "")
-            .context("初始化 master 分支文件失败")?;
+    // 创建初始分支文件（尚无提交，内容为空）
+    let branch_path = git_dir.join("refs/heads").join(initial_branch);
+    if !branch_path.exists() {
+        fs::write(branch_path, "")
+            .context(format!("初始化 {} 分支文件失败", initial_branch))?;
     }
 
     Ok(())
 }
 
+/// 检查当前目录是否为裸仓库（由 `init --bare` 创建）
+pub fn is_bare_repo() -> bool {
+    Repository::open().is_ok_and(|repo| repo.is_bare())
+}
+
+/// 需要工作区的命令在裸仓库中拒绝执行
+pub fn ensure_work_tree() -> Result<()> {
+    if is_bare_repo() {
+        return Err(GitError::BareRepository.into());
+    }
+    Ok(())
+}
+
 /// 获取文件/目录的绝对路径
 pub fn get_absolute_path(path: &str) -> Result<PathBuf> {
     let path = Path::new(path);
@@ -80,10 +112,10 @@ pub fn get_absolute_path(path: &str) -> Result<PathBuf> {
     let s = canonical.to_string_lossy();
     let verbatim_unc = "\\\\?\\UNC\\";
     let verbatim = "\\\\?\\";
-    let cleaned = if s.starts_with(verbatim_unc) {
-        format!("\\{}", &s[verbatim_unc.len()..])
-    } else if s.starts_with(verbatim) {
-        s[verbatim.len()..].to_string()
+    let cleaned = if let Some(rest) = s.strip_prefix(verbatim_unc) {
+        format!("\\{}", rest)
+    } else if let Some(rest) = s.strip_prefix(verbatim) {
+        rest.to_string()
     } else {
         s.to_string()
     };
@@ -93,7 +125,7 @@ pub fn get_absolute_path(path: &str) -> Result<PathBuf> {
 
 /// 读取暂存区（index）文件
 pub fn read_index() -> Result<Value> {
-    let index_content = fs::read_to_string(".rust-git/index")
+    let index_content = fs::read_to_string(Repository::open()?.index_path())
         .context("读取暂存区 index 文件失败")?;
     let index = serde_json::from_str(&index_content)
         .context("解析 index 文件失败（JSON 格式错误）")?;
@@ -104,62 +136,188 @@ pub fn read_index() -> Result<Value> {
 pub fn write_index(index: &Value) -> Result<()> {
     let index_content = serde_json::to_string_pretty(index)
         .context("序列化 index 失败")?;
-    fs::write(".rust-git/index", index_content)
+    write_atomic(&Repository::open()?.index_path(), index_content)
         .context("写入 index 文件失败")?;
     Ok(())
 }
 
-/// 标准化路径分隔符（将 \ 转为 /）
-pub fn normalize_path(path: &str) -> String {
-    path.replace('\\', "/")
+/// 读取暂存区为强类型条目列表（兼容旧格式：非数组的暂存区视为空；无法解析的条目报错并指明该条目）
+pub fn read_index_typed() -> Result<Vec<IndexEntry>> {
+    Repository::open()?.index()
 }
 
-/// 获取 rust-git 仓库的根目录（包含 .rust-git 的目录）
-pub fn get_repo_root() -> Result<PathBuf> {
-    let mut current_dir = std::env::current_dir().context("获取当前目录失败")?;
-    
-    // 向上遍历直到找到 .rust-git 目录
-    loop {
-        if current_dir.join(".rust-git").exists() {
-            return Ok(current_dir);
-        }
-        
-        // 到达根目录仍未找到
-        if !current_dir.pop() {
-            return Err(anyhow::anyhow!("未找到 rust-git 仓库根目录（未初始化或不在仓库内）"));
+/// 将强类型条目列表写入暂存区
+pub fn write_index_typed(entries: &[IndexEntry]) -> Result<()> {
+    Repository::open()?.write_index(entries)
+}
+
+/// 临时文件后缀（引用名不允许以该后缀结尾，临时文件不会与分支/标签重名）
+pub const TEMP_FILE_SUFFIX: &str = ".lock";
+
+/// 同一进程内临时文件的序号（与进程号一起保证并发写入者使用不同的临时文件）
+static TEMP_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// path 同目录下本次写入专用的临时文件路径：`.<文件名>.<进程号>.<序号>.lock`
+pub fn temp_file_path(path: &Path) -> Result<PathBuf> {
+    let file_name = path.file_name()
+        .ok_or_else(|| anyhow::anyhow!("无效的文件路径：{}", path.display()))?;
+    Ok(path.with_file_name(format!(
+        ".{}.{}.{}{}",
+        file_name.to_string_lossy(),
+        std::process::id(),
+        TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed),
+        TEMP_FILE_SUFFIX
+    )))
+}
+
+/// 原子地写入文件：先写入同目录下的 `.<文件名>.<进程号>.<序号>.lock`，落盘后再重命名覆盖目标文件
+///
+/// 同一文件系统内的重命名是原子的，写入中途失败或崩溃时原文件保持完整；失败时删除临时文件。
+pub fn write_atomic(path: &Path, content: impl AsRef<[u8]>) -> Result<()> {
+    write_atomic_with(path, |file| file.write_all(content.as_ref()))
+}
+
+/// 同 `write_atomic`，由 write 向临时文件写入内容（write 返回错误时目标文件保持不变）
+pub fn write_atomic_with(path: &Path, write: impl FnOnce(&mut fs::File) -> std::io::Result<()>) -> Result<()> {
+    let tmp_path = temp_file_path(path)?;
+
+    let result = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&tmp_path)
+        .and_then(|mut file| {
+            write(&mut file)?;
+            file.sync_all()
+        })
+        .context(format!("写入临时文件失败：{}", tmp_path.display()))
+        .and_then(|_| {
+            fs::rename(&tmp_path, path)
+                .context(format!("重命名 {} 为 {} 失败", tmp_path.display(), path.display()))
+        });
+    if result.is_err() && tmp_path.is_file() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+/// 暂存区锁文件名（位于 .rust-git 目录下）
+pub const INDEX_LOCK_NAME: &str = "index.lock";
+
+/// 暂存区锁：持有期间其他进程无法修改暂存区；离开作用域时（包括出错提前返回）自动删除锁文件
+#[derive(Debug)]
+pub struct IndexLock {
+    path: PathBuf,
+}
+
+impl IndexLock {
+    /// 以原子的“不存在才创建”方式创建 index.lock；锁文件已存在时返回 `GitError::IndexLocked`
+    pub fn acquire(repo: &Repository) -> Result<IndexLock> {
+        let path = repo.git_dir().join(INDEX_LOCK_NAME);
+        match fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                // 记录持有锁的进程号，便于排查残留的锁文件
+                let _ = writeln!(file, "{}", std::process::id());
+                Ok(IndexLock { path })
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+                Err(GitError::IndexLocked(path).into())
+            }
+            Err(err) => Err(err).context(format!("创建暂存区锁文件失败：{}", path.display())),
         }
     }
 }
 
-/// 获取当前分支名（默认 master）
-pub fn get_current_branch() -> Result<String> {
-    let head_path = Path::new(".rust-git/HEAD");
-    if !head_path.exists() {
-        return Ok("master".to_string());
+impl Drop for IndexLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
     }
+}
 
-    let head_content = fs::read_to_string(head_path)
-        .context("读取 HEAD 失败")?;
-    // HEAD 格式：ref: refs/heads/[分支名]（直接存储分支名则简化处理）
-    let branch = if head_content.starts_with("ref: ") {
-        head_content.trim_start_matches("ref: refs/heads/").trim().to_string()
+/// 标准化路径写法（纯字符串处理，不访问文件系统）：将 \ 转为 /，去掉 `./`（含中间的 `/./`），
+/// 合并重复的 /，去掉末尾的 /；绝对路径保留开头的 /
+pub fn normalize_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let segments: Vec<&str> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+        .collect();
+    let joined = segments.join("/");
+    if path.starts_with('/') {
+        format!("/{}", joined)
     } else {
-        // 若 HEAD 直接存储提交ID，默认 master
-        "master".to_string()
-    };
+        joined
+    }
+}
+
+/// 比较两个相对仓库根目录的路径（ignore_case 时忽略大小写，对应配置 core.ignorecase）
+pub fn paths_equal(a: &str, b: &str, ignore_case: bool) -> bool {
+    if ignore_case {
+        a.to_lowercase() == b.to_lowercase()
+    } else {
+        a == b
+    }
+}
+
+/// 将路径规格（文件、目录或 glob 模式，相对当前目录）展开为 candidates 中匹配的路径（去重、有序）；
+/// 任一路径规格没有匹配时报错
+pub fn expand_pathspecs(pathspecs: &[String], candidates: &[String]) -> Result<Vec<String>> {
+    let repo = Repository::open()?;
+    let mut matched = std::collections::BTreeSet::new();
+    for pathspec in pathspecs {
+        let rel_spec = repo.relative_path(pathspec)?;
+        let hits: Vec<&String> = if rel_spec.contains(['*', '?', '[']) {
+            let matcher = Glob::new(&rel_spec)
+                .context(format!("路径模式不合法：{}", pathspec))?
+                .compile_matcher();
+            candidates.iter().filter(|path| matcher.is_match(path.as_str())).collect()
+        } else {
+            // 普通路径：匹配文件本身或目录下的全部文件
+            let dir_prefix = format!("{}/", rel_spec);
+            candidates
+                .iter()
+                .filter(|path| **path == rel_spec || rel_spec.is_empty() || path.starts_with(&dir_prefix))
+                .collect()
+        };
+        if hits.is_empty() {
+            return Err(anyhow::anyhow!("路径规格 {} 没有匹配任何已跟踪的文件", pathspec));
+        }
+        matched.extend(hits.into_iter().cloned());
+    }
+    Ok(matched.into_iter().collect())
+}
 
-    Ok(branch)
+/// 获取 rust-git 仓库的根目录（包含 .rust-git 的目录）
+pub fn get_repo_root() -> Result<PathBuf> {
+    Ok(Repository::open()?.root().to_path_buf())
+}
+
+/// 将用户输入的路径转换为相对仓库根目录的标准化路径（与暂存区中的路径格式一致）
+pub fn get_repo_relative_path(path: &str) -> Result<String> {
+    Repository::open()?.relative_path(path)
+}
+
+/// 分离 HEAD 状态下 `get_current_branch` 返回的名称
+pub const DETACHED_HEAD: &str = "HEAD";
+
+/// 获取当前分支名（默认 master；分离 HEAD 状态返回 `DETACHED_HEAD`）
+pub fn get_current_branch() -> Result<String> {
+    Repository::open()?.current_branch()
 }
 
-/// 列出所有分支
+/// 解析 HEAD 指向的提交ID（跟随分支引用或读取分离 HEAD 中的提交ID；尚无提交时返回 None）
+pub fn resolve_head() -> Result<Option<String>> {
+    Repository::open()?.head()
+}
+
+/// 列出所有分支（按名称排序）
 pub fn list_branches() -> Result<Vec<String>> {
-    let branches_dir = Path::new(".rust-git/refs/heads");
+    let branches_dir = Repository::open()?.heads_dir();
     if !branches_dir.exists() {
-        return Ok(vec!["master".to_string()]);
+        return Ok(vec![DEFAULT_BRANCH.to_string()]);
     }
 
     let mut branches = Vec::new();
-    for entry in fs::read_dir(branches_dir)
+    for entry in fs::read_dir(&branches_dir)
         .context("读取分支目录失败")?
     {
         let entry = entry.context("读取分支条目失败")?;
@@ -168,78 +326,91 @@ pub fn list_branches() -> Result<Vec<String>> {
                 .to_str()
                 .ok_or_else(|| anyhow::anyhow!("分支名转换失败"))?
                 .to_string();
-            branches.push(branch_name);
+            // 跳过写入中的临时文件等不是合法引用名的文件
+            if is_valid_ref_name(&branch_name) {
+                branches.push(branch_name);
+            }
         }
     }
+    branches.sort();
 
     if branches.is_empty() {
-        branches.push("master".to_string());
+        branches.push(DEFAULT_BRANCH.to_string());
     }
 
     Ok(branches)
 }
 
+/// 按 Git 引用名规则（git check-ref-format）检查名称是否合法
+pub fn is_valid_ref_name(name: &str) -> bool {
+    if name.is_empty() || name == "@" || name.starts_with('/') || name.ends_with('/') || name.ends_with('.') {
+        return false;
+    }
+    if name.contains("..") || name.contains("@{") || name.contains("//") {
+        return false;
+    }
+    // 控制字符、空格及 ~ ^ : ? * [ \ 均不允许
+    if name.chars().any(|c| c.is_ascii_control() || " ~^:?*[\\".contains(c)) {
+        return false;
+    }
+    // 每一级名称不能以 . 开头，也不能以 .lock 结尾（与原子写入的临时文件区分）
+    name.split('/').all(|component| !component.starts_with('.') && !component.ends_with(TEMP_FILE_SUFFIX))
+}
+
+/// 检查分支名/标签名合法性（引用以单个文件存储在 refs/heads、refs/tags 下，因此也不允许 /）
+pub fn validate_ref_name(name: &str) -> Result<()> {
+    if !is_valid_ref_name(name) || name.contains('/') || name == DETACHED_HEAD {
+        return Err(GitError::InvalidRefName(name.to_string()).into());
+    }
+    Ok(())
+}
+
 /// 创建分支（关联当前 HEAD 指向的提交）
 pub fn create_branch(branch_name: &str) -> Result<()> {
     // 检查分支名合法性
-    if branch_name.contains('/') || branch_name.contains('\\') || branch_name.is_empty() {
-        return Err(anyhow::anyhow!("分支名不合法：{}", branch_name));
-    }
+    validate_ref_name(branch_name)?;
 
     // 检查分支是否已存在
-    let branch_path = Path::new(".rust-git/refs/heads").join(branch_name);
+    let repo = Repository::open()?;
+    let branch_path = repo.branch_ref(branch_name);
     if branch_path.exists() {
-        return Err(anyhow::anyhow!("分支 {} 已存在", branch_name));
+        return Err(GitError::BranchExists(branch_name.to_string()).into());
     }
 
-    // 获取当前 HEAD 指向的提交ID
-    let head_path = Path::new(".rust-git/HEAD");
-    let head_content = if head_path.exists() {
-        fs::read_to_string(head_path)
-            .context("读取 HEAD 失败")?
-            .trim()
-            .to_string()
-    } else {
-        return Err(anyhow::anyhow!("暂无提交记录，无法创建分支"));
-    };
-
-    // 分支文件存储对应提交ID
-    let commit_id = if head_content.starts_with("ref: ") {
-        // 若 HEAD 指向分支，读取分支对应的提交ID
-        let target_branch = head_content.trim_start_matches("ref: refs/heads/").trim();
-        let target_branch_path = Path::new(".rust-git/refs/heads").join(target_branch);
-        fs::read_to_string(target_branch_path)
-            .context(format!("读取分支 {} 失败", target_branch))?
-            .trim()
-            .to_string()
-    } else {
-        head_content
-    };
+    // 获取当前 HEAD 指向的提交ID（尚无提交时拒绝创建，避免写入空引用）
+    let commit_id = repo.head()?
+        .ok_or_else(|| anyhow::anyhow!("cannot create branch: no commits yet（暂无提交记录，无法创建分支 {}）", branch_name))?;
 
     // 创建分支文件
-    fs::write(&branch_path, commit_id)
+    write_atomic(&branch_path, commit_id)
         .context(format!("创建分支 {} 失败", branch_name))?;
 
     Ok(())
 }
 
 /// 删除分支
-pub fn delete_branch(branch_name: &str) -> Result<()> {
+pub fn delete_branch(branch_name: &str, force: bool) -> Result<()> {
+    // 先校验分支名，防止 `../` 等名称指向 refs/heads 之外的文件
+    validate_ref_name(branch_name)?;
+
     // 禁止删除当前分支
     let current_branch = get_current_branch()?;
     if branch_name == current_branch {
         return Err(anyhow::anyhow!("无法删除当前分支：{}", branch_name));
     }
 
-    // 禁止删除 master 分支（可选）
-    if branch_name == "master" {
-        return Err(anyhow::anyhow!("禁止删除 master 分支"));
-    }
-
     // 删除分支文件
-    let branch_path = Path::new(".rust-git/refs/heads").join(branch_name);
+    let branch_path = Repository::open()?.branch_ref(branch_name);
     if !branch_path.exists() {
-        return Err(anyhow::anyhow!("分支 {} 不存在", branch_name));
+        return Err(GitError::BranchNotFound(branch_name.to_string()).into());
+    }
+
+    // 非强制删除时，分支的最新提交必须能从其他分支到达，否则会丢失历史
+    if !force {
+        let commit_id = read_branch_commit(branch_name)?;
+        if !commit_id.is_empty() && !reachable_from_other_branch(branch_name, &commit_id)? {
+            return Err(GitError::BranchNotMerged(branch_name.to_string()).into());
+        }
     }
 
     fs::remove_file(&branch_path)
@@ -248,19 +419,71 @@ pub fn delete_branch(branch_name: &str) -> Result<()> {
     Ok(())
 }
 
-/// 更新分支指向的提交ID
+/// 提交是否可从除 branch_name 之外的某个分支沿父提交到达
+fn reachable_from_other_branch(branch_name: &str, commit_id: &str) -> Result<bool> {
+    for other in list_branches()?.iter().filter(|other| *other != branch_name) {
+        let tip = read_branch_commit(other)?;
+        if !tip.is_empty() && hash::is_ancestor(commit_id, &tip)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// 重命名分支（若为当前分支则同步更新 HEAD）
+pub fn rename_branch(old_name: &str, new_name: &str) -> Result<()> {
+    validate_ref_name(old_name)?;
+    validate_ref_name(new_name)?;
+
+    let repo = Repository::open()?;
+    let old_path = repo.branch_ref(old_name);
+    if !old_path.exists() {
+        return Err(GitError::BranchNotFound(old_name.to_string()).into());
+    }
+    let new_path = repo.branch_ref(new_name);
+    if new_path.exists() {
+        return Err(GitError::BranchExists(new_name.to_string()).into());
+    }
+
+    fs::rename(&old_path, &new_path)
+        .context(format!("重命名分支 {} 为 {} 失败", old_name, new_name))?;
+
+    // 重命名当前分支时，HEAD 需指向新分支
+    if repo.current_branch()? == old_name {
+        repo.set_head_branch(new_name)?;
+    }
+
+    Ok(())
+}
+
+/// 更新分支指向的提交ID（分离 HEAD 状态下直接更新 HEAD）
 pub fn update_branch(branch_name: &str, commit_id: &str) -> Result<()> {
-    let branch_path = Path::new(".rust-git/refs/heads").join(branch_name);
-    fs::write(&branch_path, commit_id)
+    let repo = Repository::open()?;
+    if branch_name == DETACHED_HEAD {
+        return repo.set_head_detached(commit_id);
+    }
+
+    let branch_path = repo.branch_ref(branch_name);
+    write_atomic(&branch_path, commit_id)
         .context(format!("更新分支 {} 失败", branch_name))?;
     Ok(())
 }
 
-/// 读取分支指向的提交ID
+/// 读取分支指向的提交ID（分离 HEAD 状态下读取 HEAD 中的提交ID）
 pub fn read_branch_commit(branch_name: &str) -> Result<String> {
-    let branch_path = Path::new(".rust-git/refs/heads").join(branch_name);
+    let repo = Repository::open()?;
+    if branch_name == DETACHED_HEAD {
+        let commit_id = fs::read_to_string(repo.head_path())
+            .context("读取 HEAD 失败")?
+            .trim()
+            .to_string();
+        return Ok(commit_id);
+    }
+    validate_ref_name(branch_name)?;
+
+    let branch_path = repo.branch_ref(branch_name);
     if !branch_path.exists() {
-        return Err(anyhow::anyhow!("分支 {} 不存在", branch_name));
+        return Err(GitError::BranchNotFound(branch_name.to_string()).into());
     }
 
     let commit_id = fs::read_to_string(branch_path)
@@ -269,4 +492,161 @@ pub fn read_branch_commit(branch_name: &str) -> Result<String> {
         .to_string();
 
     Ok(commit_id)
-}
\ No newline at end of file
+}
+
+/// 列出所有标签
+pub fn list_tags() -> Result<Vec<String>> {
+    let tags_dir = Repository::open()?.tags_dir();
+    if !tags_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut tags = Vec::new();
+    for entry in fs::read_dir(&tags_dir)
+        .context("读取标签目录失败")?
+    {
+        let entry = entry.context("读取标签条目失败")?;
+        if entry.file_type()?.is_file() {
+            let tag_name = entry.file_name()
+                .to_str()
+                .ok_or_else(|| anyhow::anyhow!("标签名转换失败"))?
+                .to_string();
+            if is_valid_ref_name(&tag_name) {
+                tags.push(tag_name);
+            }
+        }
+    }
+    tags.sort();
+
+    Ok(tags)
+}
+
+/// 创建标签（指向当前 HEAD 的提交；指定 message 时创建附注标签，引用指向标签对象）
+pub fn create_tag(tag_name: &str, message: Option<&str>) -> Result<()> {
+    validate_ref_name(tag_name)?;
+
+    let tags_dir = Repository::open()?.tags_dir();
+    let tag_path = tags_dir.join(tag_name);
+    if tag_path.exists() {
+        return Err(GitError::TagExists(tag_name.to_string()).into());
+    }
+
+    let commit_id = read_branch_commit(&get_current_branch()?)?;
+    if commit_id.is_empty() {
+        return Err(anyhow::anyhow!("暂无提交记录，无法创建标签"));
+    }
+
+    let object_id = match message {
+        Some(message) => metadata::write_tag(&commit_id, tag_name, message)?,
+        None => commit_id,
+    };
+
+    fs::create_dir_all(&tags_dir)
+        .context("创建标签目录失败")?;
+    write_atomic(&tag_path, object_id)
+        .context(format!("创建标签 {} 失败", tag_name))?;
+
+    Ok(())
+}
+
+/// 删除标签
+pub fn delete_tag(tag_name: &str) -> Result<()> {
+    // 先校验标签名，防止 `../` 等名称指向 refs/tags 之外的文件
+    validate_ref_name(tag_name)?;
+
+    let tag_path = Repository::open()?.tag_ref(tag_name);
+    if !tag_path.exists() {
+        return Err(GitError::TagNotFound(tag_name.to_string()).into());
+    }
+
+    fs::remove_file(&tag_path)
+        .context(format!("删除标签 {} 失败", tag_name))?;
+
+    Ok(())
+}
+
+/// 读取标签引用中记录的对象ID（轻量标签为提交，附注标签为标签对象）
+pub fn read_tag_ref(tag_name: &str) -> Result<String> {
+    validate_ref_name(tag_name)?;
+
+    let tag_path = Repository::open()?.tag_ref(tag_name);
+    if !tag_path.exists() {
+        return Err(GitError::TagNotFound(tag_name.to_string()).into());
+    }
+
+    let object_id = fs::read_to_string(tag_path)
+        .context(format!("读取标签 {} 失败", tag_name))?
+        .trim()
+        .to_string();
+
+    Ok(object_id)
+}
+
+/// 读取标签指向的提交ID（附注标签解引用到其目标提交）
+pub fn read_tag_commit(tag_name: &str) -> Result<String> {
+    hash::peel_to_commit(&read_tag_ref(tag_name)?)
+}
+
+/// 将修订解析为完整提交哈希，支持在引用后追加 `~n` 沿父提交链回溯 n 代（如 HEAD~2）
+pub fn resolve_rev(rev: &str) -> Result<String> {
+    let mut parts = rev.split('~');
+    let base = parts.next().unwrap_or("");
+    // 附注标签（名称、完整引用名或标签对象哈希）解引用到其目标提交
+    let mut commit_id = hash::peel_to_commit(&resolve_ref(base)?)?;
+
+    // 依次处理每个 ~n（省略 n 时视为 1，~0 保持不变）
+    for step in parts {
+        let count: usize = if step.is_empty() {
+            1
+        } else {
+            step.parse()
+                .map_err(|_| anyhow::anyhow!("unknown revision：{}（无效的祖先数 {}）", rev, step))?
+        };
+        for _ in 0..count {
+            let commit_content = hash::read_object(&commit_id)?;
+            commit_id = hash::parse_parent(&commit_content)?
+                .ok_or_else(|| anyhow::anyhow!("unknown revision：{}（提交 {} 没有父提交）", rev, &commit_id[..7]))?;
+        }
+    }
+
+    Ok(commit_id)
+}
+
+/// 将单个引用（HEAD 或 @ / 完整引用名 / 分支名 / 标签名 / 缩写哈希）解析为完整提交哈希
+fn resolve_ref(rev: &str) -> Result<String> {
+    // HEAD（`@` 为其简写）
+    if rev == DETACHED_HEAD || rev == "@" {
+        return resolve_head()?
+            .ok_or_else(|| anyhow::anyhow!("unknown revision：{}（暂无提交记录）", rev));
+    }
+
+    // 完整引用名（如 refs/heads/master、refs/tags/v1）
+    if rev.starts_with("refs/") && !rev.split('/').any(|part| part == "..") {
+        let ref_path = Repository::open()?.git_dir().join(rev);
+        if ref_path.is_file() {
+            let commit_id = fs::read_to_string(&ref_path)
+                .context(format!("读取引用 {} 失败", rev))?
+                .trim()
+                .to_string();
+            if !commit_id.is_empty() {
+                return Ok(commit_id);
+            }
+        }
+    }
+
+    // 分支名
+    if list_branches()?.iter().any(|branch| branch == rev) {
+        let commit_id = read_branch_commit(rev)?;
+        if !commit_id.is_empty() {
+            return Ok(commit_id);
+        }
+    }
+
+    // 标签名
+    if list_tags()?.iter().any(|tag| tag == rev) {
+        return read_tag_commit(rev);
+    }
+
+    // 完整或缩写哈希
+    hash::resolve_hash(rev).map_err(|_| anyhow::anyhow!("unknown revision：{}", rev))
+}
@@ -1,50 +1,370 @@
 use anyhow::{Context, Result};
+use flate2::Compression;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
 use sha1::{Digest, Sha1};
+use crate::error::GitError;
+use crate::utils::eol;
+use crate::utils::fs as utils_fs;
+use crate::utils::metadata::{Commit, IndexEntry, Tag};
+use crate::utils::repository::Repository;
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
 
-/// 计算文件内容的 SHA-1 哈希（Git 风格）
+/// Git 对象类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectType {
+    Blob,   // 文件内容
+    Tree,   // 目录树
+    Commit, // 提交
+    Tag,    // 附注标签
+}
+
+impl ObjectType {
+    /// 对象头中使用的类型名
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ObjectType::Blob => "blob",
+            ObjectType::Tree => "tree",
+            ObjectType::Commit => "commit",
+            ObjectType::Tag => "tag",
+        }
+    }
+
+    /// 从对象头中的类型名解析
+    pub fn parse(name: &str) -> Option<ObjectType> {
+        match name {
+            "blob" => Some(ObjectType::Blob),
+            "tree" => Some(ObjectType::Tree),
+            "commit" => Some(ObjectType::Commit),
+            "tag" => Some(ObjectType::Tag),
+            _ => None,
+        }
+    }
+}
+
+/// 为内容加上 Git 标准对象头："<类型> <长度>\0"
+fn with_header(obj_type: ObjectType, content: &[u8]) -> Vec<u8> {
+    let mut data = format!("{} {}\0", obj_type.as_str(), content.len()).into_bytes();
+    data.extend_from_slice(content);
+    data
+}
+
+/// 计算对象的 SHA-1 哈希（含对象头，与 `git hash-object` 一致）
+pub fn hash_object(obj_type: ObjectType, content: &[u8]) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(with_header(obj_type, content));
+    format!("{:x}", hasher.finalize())
+}
+
+/// 流式读取文件时每次读取的字节数
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// 计算文件内容的 SHA-1 哈希（Git 风格，按 blob 对象计算；分块读取，不将整个文件载入内存；
+/// 启用 core.autocrlf 时按转换为 LF 后的内容计算）
 pub fn hash_file(path: &Path) -> Result<String> {
-    // 读取文件内容
-    let content = fs::read(path)
-        .context(format!("读取文件失败：{}", path.display()))?;
-    // 计算 SHA-1 哈希
+    if let Some(content) = eol::read_clean_file(path)? {
+        return Ok(hash_object(ObjectType::Blob, &content));
+    }
+    let mut hasher = Sha1::new();
+    stream_blob(path, |chunk| {
+        hasher.update(chunk);
+        Ok(())
+    })?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// 将文件存储为 blob 对象并返回对象哈希：分块读取，一次遍历同时计算哈希和压缩写入
+/// （启用 core.autocrlf 时需要先转换换行符，改为整体读取）
+pub fn store_file(path: &Path) -> Result<String> {
+    if let Some(content) = eol::read_clean_file(path)? {
+        return store_object(ObjectType::Blob, &content);
+    }
+    let objects_dir = Repository::open()?.objects_dir();
+    fs::create_dir_all(&objects_dir)
+        .context(format!("创建对象目录失败：{}", objects_dir.display()))?;
+
+    // 哈希在读完之前未知，先写入本次专用的临时文件，完成后再移动到最终位置
+    let tmp_path = utils_fs::temp_file_path(&objects_dir.join("object"))?;
+    let tmp_file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&tmp_path)
+        .context(format!("创建临时对象文件失败：{}", tmp_path.display()))?;
+    let mut encoder = ZlibEncoder::new(std::io::BufWriter::new(tmp_file), Compression::default());
     let mut hasher = Sha1::new();
-    hasher.update(&content);
-    // 转换为十六进制字符串
+    let streamed = stream_blob(path, |chunk| {
+        hasher.update(chunk);
+        encoder.write_all(chunk)
+            .context(format!("压缩对象失败：{}", path.display()))
+    })
+    .and_then(|_| {
+        encoder.finish()?
+            .into_inner()
+            .map_err(|err| err.into_error())?
+            .sync_all()?;
+        Ok(())
+    });
+    if let Err(err) = streamed {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(err);
+    }
+
     let hash = format!("{:x}", hasher.finalize());
+    let (dir_part, file_part) = hash.split_at(2);
+    let obj_dir = objects_dir.join(dir_part);
+    if !obj_dir.exists() {
+        fs::create_dir_all(&obj_dir)
+            .context(format!("创建对象目录失败：{}", obj_dir.display()))?;
+    }
+    let obj_path = obj_dir.join(file_part);
+    if obj_path.is_file() {
+        // 对象已存在（内容相同），丢弃临时文件
+        fs::remove_file(&tmp_path)
+            .context(format!("删除临时对象文件失败：{}", tmp_path.display()))?;
+        return Ok(hash);
+    }
+    fs::rename(&tmp_path, &obj_path)
+        .context(format!("写入对象失败：{}", obj_path.display()))?;
+
     Ok(hash)
 }
 
-/// 将内容存储为 Git 风格的对象（2 位目录 + 剩余哈希作为文件名）
-pub fn store_object(hash: &str, content: &[u8]) -> Result<()> {
+/// 分块读取文件，依次将 blob 对象头和文件内容交给 sink（读取期间文件大小变化时报错）
+fn stream_blob(path: &Path, mut sink: impl FnMut(&[u8]) -> Result<()>) -> Result<()> {
+    let file = fs::File::open(path)
+        .context(format!("读取文件失败：{}", path.display()))?;
+    let expected_len = file.metadata()
+        .context(format!("读取文件元数据失败：{}", path.display()))?
+        .len();
+    sink(format!("{} {}\0", ObjectType::Blob.as_str(), expected_len).as_bytes())?;
+
+    let mut reader = std::io::BufReader::with_capacity(CHUNK_SIZE, file);
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut total = 0u64;
+    loop {
+        let read = reader.read(&mut buffer)
+            .context(format!("读取文件失败：{}", path.display()))?;
+        if read == 0 {
+            break;
+        }
+        total += read as u64;
+        sink(&buffer[..read])?;
+    }
+    if total != expected_len {
+        return Err(anyhow::anyhow!("文件在读取期间被修改：{}", path.display()));
+    }
+    Ok(())
+}
+
+/// 将内容存储为 Git 风格的对象（2 位目录 + 剩余哈希作为文件名），返回对象哈希
+///
+/// 迁移说明：对象内容现在带有 "<类型> <长度>\0" 头，哈希也随之改变；
+/// 旧版本写入的无头对象仍可通过 `read_object` 读取，但其哈希与新版不一致。
+pub fn store_object(obj_type: ObjectType, content: &[u8]) -> Result<String> {
+    let data = with_header(obj_type, content);
+    let mut hasher = Sha1::new();
+    hasher.update(&data);
+    let hash = format!("{:x}", hasher.finalize());
+
     // 拆分哈希：前 2 位为目录名，剩余为文件名（Git 标准）
     let (dir_part, file_part) = hash.split_at(2);
-    let obj_dir = Path::new(".rust-git/objects").join(dir_part);
+    let obj_dir = Repository::open()?.objects_dir().join(dir_part);
     let obj_path = obj_dir.join(file_part);
 
+    // 对象按内容寻址，已存在则无需重复写入
+    if obj_path.is_file() {
+        return Ok(hash);
+    }
+
     // 创建对象目录
     if !obj_dir.exists() {
         fs::create_dir_all(&obj_dir)
             .context(format!("创建对象目录失败：{}", obj_dir.display()))?;
     }
 
-    // 写入对象内容
-    fs::write(&obj_path, content)
+    // 使用 zlib 压缩后写入对象内容（与 Git 一致）
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&data)
+        .context(format!("压缩对象失败：{}", obj_path.display()))?;
+    let compressed = encoder.finish()
+        .context(format!("压缩对象失败：{}", obj_path.display()))?;
+    utils_fs::write_atomic(&obj_path, compressed)
         .context(format!("写入对象失败：{}", obj_path.display()))?;
 
-    Ok(())
+    Ok(hash)
 }
 
-/// 读取 Git 对象内容
-pub fn read_object(hash: &str) -> Result<Vec<u8>> {
-    let (dir_part, file_part) = hash.split_at(2);
-    let obj_path = Path::new(".rust-git/objects")
-        .join(dir_part)
-        .join(file_part);
-    
-    let content = fs::read(&obj_path)
+/// 获取对象文件路径
+pub fn object_path(hash: &str) -> Result<PathBuf> {
+    let (dir_part, file_part) = hash.split_at(hash.len().min(2));
+    Ok(Repository::open()?.objects_dir().join(dir_part).join(file_part))
+}
+
+/// 检查对象是否存在
+pub fn object_exists(hash: &str) -> bool {
+    hash.len() > 2 && object_path(hash).is_ok_and(|obj_path| obj_path.is_file())
+}
+
+/// 检查一组对象是否全部存在，缺失时一次性列出全部缺失的对象（用于写入工作区前的预检，避免只恢复一部分）
+pub fn ensure_objects_exist<'a>(hashes: impl IntoIterator<Item = &'a str>) -> Result<()> {
+    let mut missing: Vec<String> = hashes
+        .into_iter()
+        .filter(|hash| !object_exists(hash))
+        .map(str::to_string)
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    missing.sort();
+    missing.dedup();
+    Err(GitError::MissingObjects(missing).into())
+}
+
+/// 将（缩写）哈希解析为完整对象哈希（前缀至少 4 位，且必须唯一匹配）
+pub fn resolve_hash(prefix: &str) -> Result<String> {
+    if prefix.len() < 4 {
+        return Err(anyhow::anyhow!("哈希前缀过短（至少 4 位）：{}", prefix));
+    }
+    if !prefix.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(GitError::ObjectNotFound(prefix.to_string()).into());
+    }
+
+    let prefix = prefix.to_ascii_lowercase();
+    let (dir_part, file_part) = prefix.split_at(2);
+    let obj_dir = Repository::open()?.objects_dir().join(dir_part);
+
+    let mut matches = Vec::new();
+    if obj_dir.is_dir() {
+        for entry in fs::read_dir(&obj_dir)
+            .context(format!("读取对象目录失败：{}", obj_dir.display()))?
+        {
+            let name = entry?.file_name().to_string_lossy().to_string();
+            if name.starts_with(file_part) {
+                matches.push(format!("{}{}", dir_part, name));
+            }
+        }
+    }
+
+    match matches.len() {
+        0 => Err(GitError::ObjectNotFound(prefix).into()),
+        1 => Ok(matches.remove(0)),
+        _ => Err(GitError::AmbiguousPrefix { prefix, count: matches.len() }.into()),
+    }
+}
+
+/// 拆分对象头，返回对象类型和内容（无合法对象头时返回 None）
+fn split_header(data: &[u8]) -> Option<(ObjectType, &[u8])> {
+    let nul = data.iter().position(|&b| b == 0)?;
+    let header = std::str::from_utf8(&data[..nul]).ok()?;
+    let (type_name, len) = header.split_once(' ')?;
+    let obj_type = ObjectType::parse(type_name)?;
+    let body = &data[nul + 1..];
+    if len.parse::<usize>().ok()? != body.len() {
+        return None;
+    }
+    Some((obj_type, body))
+}
+
+/// 读取对象文件并解压，返回含对象头的完整数据（其 SHA-1 即为对象哈希）
+pub fn read_object_raw(hash: &str) -> Result<Vec<u8>> {
+    let obj_path = object_path(hash)?;
+    if !obj_path.is_file() {
+        return Err(GitError::ObjectNotFound(hash.to_string()).into());
+    }
+    let raw = fs::read(&obj_path)
         .context(format!("读取对象失败：{}", obj_path.display()))?;
+    // 尝试 zlib 解压；旧版未压缩的对象解压失败时按原始字节处理
+    let mut data = Vec::new();
+    if ZlibDecoder::new(raw.as_slice()).read_to_end(&mut data).is_err() {
+        data = raw;
+    }
+    Ok(data)
+}
+
+/// 列出对象目录中的所有对象哈希
+pub fn list_objects() -> Result<Vec<String>> {
+    let objects_dir = Repository::open()?.objects_dir();
+    let mut hashes = Vec::new();
+    if !objects_dir.is_dir() {
+        return Ok(hashes);
+    }
+
+    for dir_entry in fs::read_dir(&objects_dir).context("读取对象目录失败")? {
+        let dir_entry = dir_entry?;
+        let dir_name = dir_entry.file_name().to_string_lossy().to_string();
+        // 只处理两位十六进制的对象子目录
+        if !dir_entry.file_type()?.is_dir()
+            || dir_name.len() != 2
+            || !dir_name.chars().all(|c| c.is_ascii_hexdigit())
+        {
+            continue;
+        }
+        for file_entry in fs::read_dir(dir_entry.path())? {
+            let file_entry = file_entry?;
+            // 跳过写入中断残留的临时文件等非对象文件
+            let file_name = file_entry.file_name().to_string_lossy().to_string();
+            if file_entry.file_type()?.is_file()
+                && file_name.len() == 38
+                && file_name.chars().all(|c| c.is_ascii_hexdigit())
+            {
+                hashes.push(format!("{}{}", dir_name, file_name));
+            }
+        }
+    }
+    hashes.sort();
+
+    Ok(hashes)
+}
+
+/// 读取 Git 对象的类型和内容（已去除对象头）
+pub fn read_object_with_type(hash: &str) -> Result<(Option<ObjectType>, Vec<u8>)> {
+    let data = read_object_raw(hash)?;
+    // 兼容旧版无对象头的对象：类型未知，原样返回
+    match split_header(&data) {
+        Some((obj_type, body)) => Ok((Some(obj_type), body.to_vec())),
+        None => Ok((None, data)),
+    }
+}
+
+/// 对象头（类型名、空格、十进制长度、NUL）的最大长度
+const MAX_HEADER_LEN: u64 = 32;
+
+/// 只解压对象头读取对象类型，不解压对象内容（无对象头或对象头无法识别时返回 None；
+/// 不校验内容长度，完整校验请使用 read_object_with_type）
+pub fn read_object_type(hash: &str) -> Result<Option<ObjectType>> {
+    let obj_path = object_path(hash)?;
+    if !obj_path.is_file() {
+        return Err(GitError::ObjectNotFound(hash.to_string()).into());
+    }
+    let open = || fs::File::open(&obj_path).context(format!("读取对象失败：{}", obj_path.display()));
+    let mut header = Vec::new();
+    let decoded = BufReader::new(ZlibDecoder::new(open()?).take(MAX_HEADER_LEN)).read_until(0, &mut header);
+    // 旧版未压缩的对象解压失败时按原始字节读取
+    if decoded.is_err() {
+        header.clear();
+        BufReader::new(open()?.take(MAX_HEADER_LEN))
+            .read_until(0, &mut header)
+            .context(format!("读取对象失败：{}", obj_path.display()))?;
+    }
+
+    let Some(header) = header.strip_suffix(&[0]) else {
+        return Ok(None);
+    };
+    let Some((type_name, len)) = std::str::from_utf8(header).ok().and_then(|header| header.split_once(' ')) else {
+        return Ok(None);
+    };
+    if len.parse::<usize>().is_err() {
+        return Ok(None);
+    }
+    Ok(ObjectType::parse(type_name))
+}
+
+/// 读取 Git 对象内容（已去除对象头）
+pub fn read_object(hash: &str) -> Result<Vec<u8>> {
+    let (_, content) = read_object_with_type(hash)?;
     Ok(content)
 }
 
@@ -59,10 +379,279 @@ pub fn parse_commit(commit_content: &[u8]) -> Result<String> {
     Ok(tree_hash.to_string())
 }
 
-/// 解析目录树对象，提取文件路径和哈希（简化版：暂存区内容）
+/// 解析提交对象，提取父提交哈希（首次提交返回 None）
+pub fn parse_parent(commit_content: &[u8]) -> Result<Option<String>> {
+    let commit_str = String::from_utf8_lossy(commit_content);
+    // 只在头部（空行之前）查找 parent 行，避免误匹配提交信息
+    let parent = commit_str.lines()
+        .take_while(|line| !line.is_empty())
+        .find(|line| line.starts_with("parent "))
+        .map(|line| line.trim_start_matches("parent ").trim().to_string());
+    Ok(parent)
+}
+
+/// 解析提交对象中的全部父提交哈希（按出现顺序；合并提交有多个）
+pub fn parse_parents(commit_content: &[u8]) -> Vec<String> {
+    let commit_str = String::from_utf8_lossy(commit_content);
+    commit_str.lines()
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.strip_prefix("parent "))
+        .map(|parent| parent.trim().to_string())
+        .collect()
+}
+
+/// 解析完整的提交对象（tree / parent / author / committer / 时间戳 / 提交信息），提交哈希由内容计算
+///
+/// 提交信息为头部之后的全部内容（可包含多行）；parent 行按出现顺序全部记录（合并提交有多个）；
+/// 缺少 author 行时使用 committer 行的身份和时间，缺少 committer 行时视为与作者相同。
+pub fn parse_commit_full(commit_content: &[u8]) -> Result<Commit> {
+    let commit_str = String::from_utf8_lossy(commit_content);
+    let (header, message) = commit_str.split_once("\n\n").unwrap_or((&commit_str, ""));
+
+    let mut tree_hash = None;
+    let mut parents = Vec::new();
+    let mut author = None;
+    let mut committer = None;
+    for line in header.lines() {
+        if let Some(rest) = line.strip_prefix("tree ") {
+            tree_hash = Some(rest.trim().to_string());
+        } else if let Some(rest) = line.strip_prefix("parent ") {
+            parents.push(rest.trim().to_string());
+        } else if let Some(rest) = line.strip_prefix("author ") {
+            author = Some(parse_identity(rest));
+        } else if let Some(rest) = line.strip_prefix("committer ") {
+            committer = Some(parse_identity(rest));
+        }
+    }
+
+    let (author, timestamp) = author
+        .or_else(|| committer.clone())
+        .ok_or_else(|| anyhow::anyhow!("提交对象无作者信息"))?;
+    // 缺少 committer 行时视为与作者相同
+    let (committer, committer_timestamp) = committer.unwrap_or_else(|| (author.clone(), timestamp));
+    Ok(Commit {
+        id: hash_object(ObjectType::Commit, commit_content),
+        message: message.to_string(),
+        author,
+        committer,
+        timestamp,
+        committer_timestamp,
+        tree_hash: tree_hash.ok_or_else(|| anyhow::anyhow!("提交对象无目录树信息"))?,
+        parents,
+    })
+}
+
+/// 解析 author / committer 行的内容：`<name> <email> <timestamp> <timezone>`，返回（身份, 时间戳）
+fn parse_identity(rest: &str) -> (String, i64) {
+    let mut parts = rest.rsplitn(3, ' ');
+    let _timezone = parts.next();
+    let timestamp = parts.next().and_then(|ts| ts.parse().ok()).unwrap_or(0);
+    let identity = parts.next().unwrap_or(rest).to_string();
+    (identity, timestamp)
+}
+
+/// 读取并解析提交对象
+pub fn read_commit(commit_id: &str) -> Result<Commit> {
+    parse_commit_full(&read_object(commit_id)?)
+}
+
+/// 解析附注标签对象（object / type / tag / tagger / 标签信息），标签对象哈希由内容计算
+pub fn parse_tag(tag_content: &[u8]) -> Result<Tag> {
+    let tag_str = String::from_utf8_lossy(tag_content);
+    let (header, message) = tag_str.split_once("\n\n").unwrap_or((&tag_str, ""));
+
+    let mut object = None;
+    let mut name = None;
+    let mut tagger = None;
+    for line in header.lines() {
+        if let Some(rest) = line.strip_prefix("object ") {
+            object = Some(rest.trim().to_string());
+        } else if let Some(rest) = line.strip_prefix("tag ") {
+            name = Some(rest.trim().to_string());
+        } else if let Some(rest) = line.strip_prefix("tagger ") {
+            tagger = Some(parse_identity(rest));
+        }
+    }
+
+    let (tagger, timestamp) = tagger.unwrap_or_default();
+    Ok(Tag {
+        id: hash_object(ObjectType::Tag, tag_content),
+        object: object.ok_or_else(|| anyhow::anyhow!("标签对象无目标对象信息"))?,
+        name: name.ok_or_else(|| anyhow::anyhow!("标签对象无标签名"))?,
+        tagger,
+        timestamp,
+        message: message.to_string(),
+    })
+}
+
+/// 读取并解析附注标签对象
+pub fn read_tag(tag_id: &str) -> Result<Tag> {
+    let (obj_type, content) = read_object_with_type(tag_id)?;
+    if obj_type != Some(ObjectType::Tag) {
+        return Err(anyhow::anyhow!("对象 {} 不是标签对象", tag_id));
+    }
+    parse_tag(&content)
+}
+
+/// 将对象解引用为提交：附注标签沿其目标对象逐层解析，其他对象（或不存在的对象）原样返回
+pub fn peel_to_commit(object_id: &str) -> Result<String> {
+    let mut object_id = object_id.to_string();
+    while object_exists(&object_id) {
+        let (obj_type, content) = read_object_with_type(&object_id)?;
+        if obj_type != Some(ObjectType::Tag) {
+            break;
+        }
+        object_id = parse_tag(&content)?.object;
+    }
+    Ok(object_id)
+}
+
+/// 提取对象中引用的其他对象：(引用类型, 对象哈希)
+pub fn referenced_objects(obj_type: Option<ObjectType>, content: &[u8]) -> Vec<(&'static str, String)> {
+    let mut referenced = Vec::new();
+    match obj_type {
+        Some(ObjectType::Commit) => {
+            let text = String::from_utf8_lossy(content);
+            for line in text.lines().take_while(|line| !line.is_empty()) {
+                if let Some(tree_hash) = line.strip_prefix("tree ") {
+                    referenced.push(("tree", tree_hash.trim().to_string()));
+                } else if let Some(parent) = line.strip_prefix("parent ") {
+                    referenced.push(("parent", parent.trim().to_string()));
+                }
+            }
+        }
+        Some(ObjectType::Tree) => {
+            if let Ok(serde_json::Value::Array(entries)) = serde_json::from_slice(content) {
+                for entry in entries {
+                    if let Some(child) = entry["hash"].as_str() {
+                        let kind = if entry["type"] == "tree" { "tree" } else { "blob" };
+                        referenced.push((kind, child.to_string()));
+                    }
+                }
+            }
+        }
+        Some(ObjectType::Tag) => {
+            if let Ok(tag) = parse_tag(content) {
+                referenced.push(("object", tag.object));
+            }
+        }
+        _ => {}
+    }
+    referenced
+}
+
+/// 判断提交 ancestor 是否为提交 descendant 的祖先（沿全部父提交查找；相同提交也视为祖先）
+pub fn is_ancestor(ancestor: &str, descendant: &str) -> Result<bool> {
+    Ok(ancestors(descendant)?.contains(ancestor))
+}
+
+/// 从 start 出发沿全部父提交可达的提交集合（包括 start 自身）
+pub fn ancestors(start: &str) -> Result<HashSet<String>> {
+    let mut reachable = HashSet::new();
+    let mut pending = vec![start.to_string()];
+    while let Some(commit_id) = pending.pop() {
+        if reachable.contains(&commit_id) {
+            continue;
+        }
+        pending.extend(parse_parents(&read_object(&commit_id)?));
+        reachable.insert(commit_id);
+    }
+    Ok(reachable)
+}
+
+/// 求两个提交的最近公共祖先（合并基准）；没有公共祖先时返回 None
+///
+/// 公共祖先中不是其他公共祖先的父提交的即为最近公共祖先；存在多个时取提交时间最新的。
+pub fn merge_base(a: &str, b: &str) -> Result<Option<String>> {
+    let ancestors_a = ancestors(a)?;
+    let common: HashSet<String> = ancestors(b)?
+        .into_iter()
+        .filter(|commit_id| ancestors_a.contains(commit_id))
+        .collect();
+
+    // 公共祖先集合对“祖先”关系封闭，排除其中任意提交的父提交即可得到最近的公共祖先
+    let mut redundant = HashSet::new();
+    for commit_id in &common {
+        redundant.extend(parse_parents(&read_object(commit_id)?));
+    }
+    let mut best: Option<(i64, String)> = None;
+    for commit_id in common.into_iter().filter(|commit_id| !redundant.contains(commit_id)) {
+        let timestamp = read_commit(&commit_id)?.timestamp;
+        if best.as_ref().is_none_or(|(best_time, best_id)| (timestamp, &commit_id) > (*best_time, best_id)) {
+            best = Some((timestamp, commit_id));
+        }
+    }
+    Ok(best.map(|(_, commit_id)| commit_id))
+}
+
+/// 解析目录树对象，递归展开子目录树，返回与暂存区格式相同的文件列表（path / hash / mode）
 pub fn parse_tree(tree_hash: &str) -> Result<serde_json::Value> {
+    let mut files = Vec::new();
+    flatten_tree(tree_hash, "", &mut files)?;
+    Ok(serde_json::Value::Array(files))
+}
+
+/// 展开目录树对象中的文件条目（prefix 为该目录相对仓库根目录的前缀）
+fn flatten_tree(tree_hash: &str, prefix: &str, files: &mut Vec<serde_json::Value>) -> Result<()> {
     let tree_content = read_object(tree_hash)?;
-    let tree_json = serde_json::from_slice(&tree_content)
+    let tree_json: serde_json::Value = serde_json::from_slice(&tree_content)
         .context("解析目录树对象失败")?;
-    Ok(tree_json)
-}
\ No newline at end of file
+    let entries = tree_json.as_array()
+        .ok_or_else(|| anyhow::anyhow!("目录树格式错误"))?;
+
+    for entry in entries {
+        // 旧版目录树：直接存储暂存区内容（含完整路径）
+        if entry.get("path").is_some() {
+            files.push(entry.clone());
+            continue;
+        }
+
+        let name = entry["name"].as_str()
+            .ok_or_else(|| anyhow::anyhow!("目录树条目名称格式错误"))?;
+        let hash = entry["hash"].as_str()
+            .ok_or_else(|| anyhow::anyhow!("目录树条目哈希格式错误"))?;
+        let path = format!("{}{}", prefix, name);
+        if entry["type"] == ObjectType::Tree.as_str() {
+            flatten_tree(hash, &format!("{}/", path), files)?;
+        } else {
+            files.push(serde_json::json!({
+                "path": path,
+                "hash": hash,
+                "mode": entry["mode"],
+            }));
+        }
+    }
+
+    Ok(())
+}
+
+/// 读取目录树中的全部文件条目（路径、哈希、模式）
+pub fn read_tree_entries(tree_hash: &str) -> Result<Vec<IndexEntry>> {
+    serde_json::from_value(parse_tree(tree_hash)?)
+        .context("解析目录树条目失败")
+}
+
+/// 读取提交对应的目录树，返回 路径 -> 文件哈希 的有序映射
+pub fn read_commit_tree(commit_id: &str) -> Result<BTreeMap<String, String>> {
+    let commit_content = read_object(commit_id)?;
+    let tree_hash = parse_commit(&commit_content)?;
+    let tree = parse_tree(&tree_hash)?;
+
+    let mut files = BTreeMap::new();
+    if let Some(entries) = tree.as_array() {
+        for entry in entries {
+            if let (Some(path), Some(file_hash)) = (entry["path"].as_str(), entry["hash"].as_str()) {
+                files.insert(path.to_string(), file_hash.to_string());
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// 读取当前分支最新提交的目录树（尚无提交时为空）
+pub fn read_head_tree() -> Result<BTreeMap<String, String>> {
+    match crate::utils::fs::resolve_head()? {
+        Some(commit_id) => read_commit_tree(&commit_id),
+        None => Ok(BTreeMap::new()),
+    }
+}
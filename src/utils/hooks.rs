@@ -0,0 +1,31 @@
+use anyhow::{Context, Result};
+use crate::utils::metadata::{self, MODE_EXECUTABLE};
+use crate::utils::repository::Repository;
+use std::process::Command;
+
+/// 运行 hooks 目录下的钩子脚本（工作目录为仓库根目录），返回是否实际执行了钩子
+///
+/// 钩子文件不存在时直接跳过；存在但不可执行时给出提示后跳过；退出码非零时返回错误。
+pub fn run_hook(repo: &Repository, name: &str, args: &[&str]) -> Result<bool> {
+    let hook_path = repo.hooks_dir().join(name);
+    if !hook_path.is_file() {
+        return Ok(false);
+    }
+    if cfg!(unix) && metadata::file_mode(&hook_path)? != MODE_EXECUTABLE {
+        eprintln!("提示：钩子 {} 不可执行，已忽略（可使用 chmod +x 启用）", hook_path.display());
+        return Ok(false);
+    }
+
+    let status = Command::new(&hook_path)
+        .args(args)
+        .current_dir(repo.root())
+        .status()
+        .context(format!("执行钩子 {} 失败", hook_path.display()))?;
+    if !status.success() {
+        return Err(match status.code() {
+            Some(code) => anyhow::anyhow!("{} 钩子执行失败（退出码 {}）", name, code),
+            None => anyhow::anyhow!("{} 钩子被信号终止", name),
+        });
+    }
+    Ok(true)
+}
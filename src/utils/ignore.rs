@@ -0,0 +1,86 @@
+use anyhow::{Context, Result};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use std::fs;
+use std::path::Path;
+
+/// 忽略规则文件名（位于仓库根目录）
+pub const IGNORE_FILE: &str = ".rust-gitignore";
+
+/// 单条忽略规则的附加属性（与 GlobSet 中的模式一一对应）
+#[derive(Debug)]
+struct RuleMeta {
+    negated: bool,  // 以 ! 开头：重新包含
+    dir_only: bool, // 以 / 结尾：只匹配目录
+}
+
+/// 忽略规则集合（模式只编译一次）
+#[derive(Debug)]
+pub struct IgnoreRules {
+    set: GlobSet,
+    metas: Vec<RuleMeta>,
+}
+
+impl IgnoreRules {
+    /// 读取仓库根目录下的 .rust-gitignore（不存在时返回空规则）
+    pub fn load(repo_root: &Path) -> Result<IgnoreRules> {
+        let ignore_path = repo_root.join(IGNORE_FILE);
+        let content = if ignore_path.is_file() {
+            fs::read_to_string(&ignore_path)
+                .context(format!("读取忽略规则文件失败：{}", ignore_path.display()))?
+        } else {
+            String::new()
+        };
+        IgnoreRules::parse(&content)
+    }
+
+    /// 解析忽略规则（每行一个 glob 模式，# 开头为注释）
+    pub fn parse(content: &str) -> Result<IgnoreRules> {
+        let mut builder = GlobSetBuilder::new();
+        let mut metas = Vec::new();
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (negated, pattern) = match line.strip_prefix('!') {
+                Some(rest) => (true, rest),
+                None => (false, line),
+            };
+            let (dir_only, pattern) = match pattern.strip_suffix('/') {
+                Some(rest) => (true, rest),
+                None => (false, pattern),
+            };
+
+            // 以 / 开头的模式相对仓库根目录；不含 / 的模式匹配任意层级
+            let pattern = if let Some(rest) = pattern.strip_prefix('/') {
+                rest.to_string()
+            } else if pattern.contains('/') {
+                pattern.to_string()
+            } else {
+                format!("**/{}", pattern)
+            };
+
+            let glob = GlobBuilder::new(&pattern)
+                .literal_separator(true)
+                .build()
+                .context(format!("忽略规则格式错误：{}", line))?;
+            builder.add(glob);
+            metas.push(RuleMeta { negated, dir_only });
+        }
+
+        let set = builder.build().context("编译忽略规则失败")?;
+        Ok(IgnoreRules { set, metas })
+    }
+
+    /// 判断相对仓库根目录的路径是否被忽略（后出现的规则优先）
+    pub fn is_ignored(&self, rel_path: &str, is_dir: bool) -> bool {
+        self.set
+            .matches(rel_path)
+            .into_iter()
+            .filter(|&i| is_dir || !self.metas[i].dir_only)
+            .max()
+            .is_some_and(|i| !self.metas[i].negated)
+    }
+}
@@ -0,0 +1,140 @@
+use similar::{Algorithm, DiffOp, capture_diff_slices};
+use std::ops::Range;
+
+/// 三方合并的结果：合并后的内容，以及是否存在冲突（冲突处已写入冲突标记）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeOutcome {
+    pub content: Vec<u8>,
+    pub conflicted: bool,
+}
+
+/// 一侧相对基准的一处修改：将基准中 base 范围内的行替换为 lines
+struct Hunk<'a> {
+    base: Range<usize>,
+    lines: Vec<&'a [u8]>,
+}
+
+/// 按行进行三方合并（base 为共同祖先版本，ours / theirs 为双方版本）
+///
+/// 只有一方修改的区域直接采用该方的修改；双方修改了重叠（或相邻）的区域且结果不同时视为冲突，
+/// 写入 `<<<<<<< ours_label`、`=======`、`>>>>>>> theirs_label` 冲突标记。二进制内容不做行合并，
+/// 双方都修改时保留 ours 并视为冲突。
+pub fn merge_contents(base: &[u8], ours: &[u8], theirs: &[u8], ours_label: &str, theirs_label: &str) -> MergeOutcome {
+    if ours == theirs || base == theirs {
+        return MergeOutcome { content: ours.to_vec(), conflicted: false };
+    }
+    if base == ours {
+        return MergeOutcome { content: theirs.to_vec(), conflicted: false };
+    }
+    if [base, ours, theirs].iter().any(|content| content.contains(&0)) {
+        return MergeOutcome { content: ours.to_vec(), conflicted: true };
+    }
+
+    let base_lines = split_lines(base);
+    let ours_lines = split_lines(ours);
+    let theirs_lines = split_lines(theirs);
+    let ours_hunks = hunks(&base_lines, &ours_lines);
+    let theirs_hunks = hunks(&base_lines, &theirs_lines);
+
+    let mut content = Vec::new();
+    let mut conflicted = false;
+    let mut base_pos = 0;
+    let (mut i, mut j) = (0, 0);
+    while i < ours_hunks.len() || j < theirs_hunks.len() {
+        // 取起点最靠前的修改，并不断并入与当前区域重叠或相邻的双方修改
+        let start = match (ours_hunks.get(i), theirs_hunks.get(j)) {
+            (Some(a), Some(b)) => a.base.start.min(b.base.start),
+            (Some(a), None) => a.base.start,
+            (None, Some(b)) => b.base.start,
+            (None, None) => break,
+        };
+        let mut end = start;
+        let (first_ours, first_theirs) = (i, j);
+        loop {
+            if let Some(hunk) = ours_hunks.get(i).filter(|hunk| touches(&hunk.base, start, end)) {
+                end = end.max(hunk.base.end);
+                i += 1;
+            } else if let Some(hunk) = theirs_hunks.get(j).filter(|hunk| touches(&hunk.base, start, end)) {
+                end = end.max(hunk.base.end);
+                j += 1;
+            } else {
+                break;
+            }
+        }
+
+        // 区域之前未修改的基准行
+        for line in &base_lines[base_pos..start] {
+            content.extend_from_slice(line);
+        }
+        base_pos = end;
+
+        let ours_block = apply_hunks(&base_lines, start..end, &ours_hunks[first_ours..i]);
+        let theirs_block = apply_hunks(&base_lines, start..end, &theirs_hunks[first_theirs..j]);
+        if first_theirs == j || ours_block == theirs_block {
+            extend_lines(&mut content, &ours_block);
+        } else if first_ours == i {
+            extend_lines(&mut content, &theirs_block);
+        } else {
+            conflicted = true;
+            content.extend_from_slice(format!("<<<<<<< {}\n", ours_label).as_bytes());
+            extend_block(&mut content, &ours_block);
+            content.extend_from_slice(b"=======\n");
+            extend_block(&mut content, &theirs_block);
+            content.extend_from_slice(format!(">>>>>>> {}\n", theirs_label).as_bytes());
+        }
+    }
+    for line in &base_lines[base_pos..] {
+        content.extend_from_slice(line);
+    }
+
+    MergeOutcome { content, conflicted }
+}
+
+/// 按行切分（保留行尾换行符，最后一行可能没有换行符）
+fn split_lines(content: &[u8]) -> Vec<&[u8]> {
+    content.split_inclusive(|&byte| byte == b'\n').collect()
+}
+
+/// 计算一侧相对基准的全部修改（按基准中的位置排序）
+fn hunks<'a>(base: &[&'a [u8]], side: &[&'a [u8]]) -> Vec<Hunk<'a>> {
+    capture_diff_slices(Algorithm::Myers, base, side)
+        .into_iter()
+        .filter(|op| !matches!(op, DiffOp::Equal { .. }))
+        .map(|op| Hunk {
+            base: op.old_range(),
+            lines: side[op.new_range()].to_vec(),
+        })
+        .collect()
+}
+
+/// 修改是否与区域 [start, end) 重叠或相邻（相邻的修改同样需要合并判断，与 Git 一致）
+fn touches(range: &Range<usize>, start: usize, end: usize) -> bool {
+    range.start <= end && range.end >= start
+}
+
+/// 将区域内一侧的修改应用到基准行上，得到该侧在此区域的内容
+fn apply_hunks<'a>(base: &[&'a [u8]], region: Range<usize>, side_hunks: &[Hunk<'a>]) -> Vec<&'a [u8]> {
+    let mut block = Vec::new();
+    let mut pos = region.start;
+    for hunk in side_hunks {
+        block.extend_from_slice(&base[pos..hunk.base.start]);
+        block.extend_from_slice(&hunk.lines);
+        pos = hunk.base.end;
+    }
+    block.extend_from_slice(&base[pos..region.end]);
+    block
+}
+
+fn extend_lines(content: &mut Vec<u8>, lines: &[&[u8]]) {
+    for line in lines {
+        content.extend_from_slice(line);
+    }
+}
+
+/// 写入冲突块中一侧的内容（保证以换行结束，使冲突标记独占一行）
+fn extend_block(content: &mut Vec<u8>, lines: &[&[u8]]) {
+    extend_lines(content, lines);
+    if content.last().is_some_and(|&byte| byte != b'\n') {
+        content.push(b'\n');
+    }
+}
@@ -2,132 +2,502 @@ use anyhow::{Context, Result};
 use chrono::Local;
 use chrono::TimeZone;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
+use std::io::Write;
 use std::path::Path;
-use crate::utils::hash;
+use crate::utils::hash::{self, ObjectType};
 use crate::utils::fs as utils_fs;
-use sha1::{Digest, Sha1};
+use crate::utils::{color, config};
+use crate::utils::repository::Repository;
 
 /// 暂存区条目结构
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IndexEntry {
     pub path: String,
     pub hash: String,
+    #[serde(default = "default_file_mode")]
+    pub mode: u32, // 文件模式（0o100644 普通文件 / 0o100755 可执行文件）
+    #[serde(default)]
+    pub size: u64, // 暂存时的文件大小（0 且 mtime 为 0 表示未记录）
+    #[serde(default)]
+    pub mtime: i64, // 暂存时的修改时间（自 Unix 纪元起的纳秒数）
+}
+
+impl IndexEntry {
+    /// 记录工作区文件当前的大小和修改时间，供后续快速判断文件是否变化
+    pub fn refresh_stat(&mut self, path: &Path) -> Result<()> {
+        (self.size, self.mtime) = file_stat(path)?;
+        Ok(())
+    }
+
+    /// 清除记录的大小和修改时间（条目内容不再对应工作区文件时调用）
+    pub fn clear_stat(&mut self) {
+        self.size = 0;
+        self.mtime = 0;
+    }
+
+    /// 判断工作区文件内容是否与条目一致：大小和修改时间均未变化时直接视为一致，否则回退到内容哈希比较
+    pub fn matches_file(&self, path: &Path) -> Result<bool> {
+        if (self.size != 0 || self.mtime != 0) && file_stat(path)? == (self.size, self.mtime) {
+            return Ok(true);
+        }
+        Ok(hash::hash_file(path)? == self.hash)
+    }
+}
+
+/// 普通文件模式
+pub const MODE_REGULAR: u32 = 0o100644;
+/// 可执行文件模式
+pub const MODE_EXECUTABLE: u32 = 0o100755;
+
+/// 旧版暂存区条目没有 mode 字段时的默认值
+fn default_file_mode() -> u32 {
+    MODE_REGULAR
+}
+
+/// 读取文件模式（Unix 上根据可执行位判断，其他平台统一视为普通文件）
+pub fn file_mode(path: &Path) -> Result<u32> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let permissions = fs::metadata(path)
+            .context(format!("读取文件元数据失败：{}", path.display()))?
+            .permissions();
+        if permissions.mode() & 0o111 != 0 {
+            return Ok(MODE_EXECUTABLE);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(MODE_REGULAR)
+}
+
+/// 读取文件大小和修改时间（纳秒；平台不支持修改时间时为 0）
+pub fn file_stat(path: &Path) -> Result<(u64, i64)> {
+    let file_metadata = fs::metadata(path)
+        .context(format!("读取文件元数据失败：{}", path.display()))?;
+    let mtime = file_metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|elapsed| elapsed.as_nanos() as i64)
+        .unwrap_or(0);
+    Ok((file_metadata.len(), mtime))
+}
+
+/// 按文件模式设置文件权限（仅 Unix 生效）
+pub fn apply_file_mode(path: &Path, mode: u32) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let permissions = fs::Permissions::from_mode(if mode == MODE_EXECUTABLE { 0o755 } else { 0o644 });
+        fs::set_permissions(path, permissions)
+            .context(format!("设置文件权限失败：{}", path.display()))?;
+    }
+    #[cfg(not(unix))]
+    let _ = (path, mode);
+    Ok(())
 }
 
 /// 提交对象结构
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Commit {
     pub id: String,          // 提交哈希
     pub message: String,     // 提交信息
-    pub author: String,      // 作者（简化为固定值）
-    pub timestamp: i64,      // 时间戳（秒）
+    pub author: String,      // 作者（编写变更的人：--author 或配置）
+    #[serde(default)]
+    pub committer: String,   // 提交者（创建提交的人：始终取自配置）
+    pub timestamp: i64,      // 作者时间戳（秒）
+    #[serde(default)]
+    pub committer_timestamp: i64, // 提交时间戳（秒；变基等重写的提交与作者时间不同，旧版记录为 0 时取作者时间）
     pub tree_hash: String,   // 目录树哈希（简化为暂存区哈希）
+    #[serde(default, alias = "parent", deserialize_with = "deserialize_parents")]
+    pub parents: Vec<String>, // 父提交哈希（首次提交为空，合并提交有多个，第一个为合并时所在的分支）
+}
+
+/// 提交的作者时间和提交时间（秒；提交时间未指定时取当前时间，作者时间未指定时与提交时间相同）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CommitDates {
+    pub author: Option<i64>,    // 作者时间（变基等重写提交时保留原作者时间）
+    pub committer: Option<i64>, // 提交时间
+}
+
+impl CommitDates {
+    /// 作者时间与提交时间相同（commit --date 覆盖的时间，None 时取当前时间）
+    pub fn at(date: Option<i64>) -> CommitDates {
+        CommitDates { author: date, committer: date }
+    }
+
+    /// 填充未指定的时间，返回（作者时间, 提交时间）
+    pub fn resolve(self) -> (i64, i64) {
+        let committer = self.committer.unwrap_or_else(|| Local::now().timestamp());
+        (self.author.unwrap_or(committer), committer)
+    }
+}
+
+/// 附注标签对象结构
+#[derive(Debug, Clone)]
+pub struct Tag {
+    pub id: String,       // 标签对象哈希
+    pub object: String,   // 标签指向的对象（提交）哈希
+    pub name: String,     // 标签名
+    pub tagger: String,   // 创建标签的人
+    pub timestamp: i64,   // 时间戳（秒）
+    pub message: String,  // 标签信息
+}
+
+impl Commit {
+    /// 第一个父提交（沿历史主线回溯时使用）
+    pub fn first_parent(&self) -> Option<&str> {
+        self.parents.first().map(String::as_str)
+    }
+}
+
+/// 兼容旧版提交日志中的单个 `parent` 字段（字符串或 null）与新版的 `parents` 数组
+fn deserialize_parents<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Parents {
+        Many(Vec<String>),
+        One(Option<String>),
+    }
+    Ok(match Parents::deserialize(deserializer)? {
+        Parents::Many(parents) => parents,
+        Parents::One(parent) => parent.into_iter().collect(),
+    })
 }
 
-/// 生成目录树哈希（简化版：直接哈希暂存区内容）
+/// 目录树对象条目结构（每个目录一个目录树对象）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TreeEntry {
+    pub name: String, // 文件名或子目录名
+    #[serde(rename = "type")]
+    pub kind: String, // blob 或 tree
+    pub hash: String, // 对象哈希
+    pub mode: u32,    // 文件模式（子目录为 0o040000）
+}
+
+/// 子目录模式
+pub const MODE_TREE: u32 = 0o040000;
+
+/// 生成目录树哈希：按目录分组暂存区条目，为每个目录创建目录树对象，返回根目录树哈希
 pub fn generate_tree_hash() -> Result<String> {
     // 读取暂存区
-    let index = utils_fs::read_index()?;
-    let index_str = serde_json::to_string(&index)
-        .context("序列化暂存区失败")?;
-    
-    // 计算暂存区的 SHA-1 哈希作为目录树哈希
-    let mut hasher = Sha1::new();
-    hasher.update(index_str.as_bytes());
-    let tree_hash = format!("{:x}", hasher.finalize());
+    let index = utils_fs::read_index_typed()?;
+    write_tree_from_entries(&index)
+}
+
+/// 将一组文件条目写入为目录树对象，返回根目录树哈希
+pub fn write_tree_from_entries(index: &[IndexEntry]) -> Result<String> {
+    let entries: Vec<(&str, &IndexEntry)> = index
+        .iter()
+        .map(|entry| (entry.path.as_str(), entry))
+        .collect();
+    write_tree(&entries)
+}
+
+/// 递归写入目录树对象（entries 中的路径相对当前目录）
+fn write_tree(entries: &[(&str, &IndexEntry)]) -> Result<String> {
+    let mut tree_entries = Vec::new();
+    let mut sub_dirs: BTreeMap<&str, Vec<(&str, &IndexEntry)>> = BTreeMap::new();
+
+    for &(rel_path, entry) in entries {
+        match rel_path.split_once('/') {
+            // 子目录中的文件：按第一级目录名分组
+            Some((dir_name, rest)) => sub_dirs.entry(dir_name).or_default().push((rest, entry)),
+            None => tree_entries.push(TreeEntry {
+                name: rel_path.to_string(),
+                kind: ObjectType::Blob.as_str().to_string(),
+                hash: entry.hash.clone(),
+                mode: entry.mode,
+            }),
+        }
+    }
+
+    // 先写入子目录树，再引用其哈希
+    for (dir_name, children) in sub_dirs {
+        let sub_tree_hash = write_tree(&children)?;
+        tree_entries.push(TreeEntry {
+            name: dir_name.to_string(),
+            kind: ObjectType::Tree.as_str().to_string(),
+            hash: sub_tree_hash,
+            mode: MODE_TREE,
+        });
+    }
+    tree_entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let tree_str = serde_json::to_string(&tree_entries)
+        .context("序列化目录树失败")?;
+    // 存储目录树对象，其哈希即为目录树哈希
+    hash::store_object(ObjectType::Tree, tree_str.as_bytes())
+}
+
+/// 读取作者信息（user.name / user.email 配置，未设置时使用默认值）
+pub fn read_author() -> Result<String> {
+    let name = config::get_str("user.name", "RustGit")?;
+    let email = config::get_str("user.email", "rustgit@example.com")?;
+    Ok(format!("{} <{}>", name, email))
+}
+
+/// 校验作者信息格式：`Name <email>`
+pub fn validate_author(author: &str) -> Result<()> {
+    let valid = author
+        .strip_suffix('>')
+        .and_then(|rest| rest.split_once(" <"))
+        .is_some_and(|(name, email)| {
+            !name.trim().is_empty()
+                && !email.is_empty()
+                && !email.contains(['<', '>'])
+                && !name.contains(['<', '>'])
+        });
+    if !valid {
+        return Err(anyhow::anyhow!("作者格式不合法（应为 `Name <email>`）：{}", author));
+    }
+    Ok(())
+}
 
-    // 存储目录树对象
-    hash::store_object(&tree_hash, index_str.as_bytes())?;
+/// 解析提交时间：支持 RFC3339（如 2024-01-02T03:04:05+08:00）或 Unix 时间戳（秒）
+pub fn parse_date(date: &str) -> Result<i64> {
+    let date = date.trim();
+    if let Ok(timestamp) = date.parse::<i64>() {
+        return Ok(timestamp);
+    }
+    chrono::DateTime::parse_from_rfc3339(date)
+        .map(|dt| dt.timestamp())
+        .map_err(|_| anyhow::anyhow!("日期格式不合法（应为 RFC3339 或 Unix 时间戳）：{}", date))
+}
+
+/// 创建提交对象（author 为 None 时从配置读取作者信息，date 为 None 时使用当前时间）
+pub fn create_commit(message: &str, author: Option<&str>, date: Option<i64>) -> Result<Commit> {
+    let index = utils_fs::read_index_typed()?;
+    create_commit_from_entries(&index, message, author, date)
+}
 
-    Ok(tree_hash)
+/// 以指定的文件条目（而非整个暂存区）作为目录树创建提交对象
+pub fn create_commit_from_entries(
+    entries: &[IndexEntry],
+    message: &str,
+    author: Option<&str>,
+    date: Option<i64>,
+) -> Result<Commit> {
+    // 读取 HEAD 指向的提交作为父提交（尚无提交时没有父提交）
+    let parents: Vec<String> = utils_fs::resolve_head()?.into_iter().collect();
+    create_commit_with_parents(entries, message, author, CommitDates::at(date), &parents)
 }
 
-/// 创建提交对象
-pub fn create_commit(message: &str) -> Result<Commit> {
+/// 以指定的文件条目和父提交创建提交（合并提交的 parents 依次为当前 HEAD 和被合并的提交）
+pub fn create_commit_with_parents(
+    entries: &[IndexEntry],
+    message: &str,
+    author: Option<&str>,
+    dates: CommitDates,
+    parents: &[String],
+) -> Result<Commit> {
     // 生成目录树哈希
-    let tree_hash = generate_tree_hash()?;
-    let timestamp = Local::now().timestamp();
-    
-    // 构造 Git 风格的提交内容
-    let commit_content = format!(
-        "tree {}\nauthor RustGit <rustgit@example.com> {} +0800\ncommitter RustGit <rustgit@example.com> {} +0800\n\n{}",
-        tree_hash, timestamp, timestamp, message
-    );
-    
-    // 计算提交哈希
-    let mut hasher = Sha1::new();
-    hasher.update(commit_content.as_bytes());
-    let commit_id = format!("{:x}", hasher.finalize());
-    
-    // 存储提交对象
-    hash::store_object(&commit_id, commit_content.as_bytes())?;
+    let tree_hash = write_tree_from_entries(entries)?;
+    let (timestamp, committer_timestamp) = dates.resolve();
+
+    // 提交者始终从配置读取；作者优先使用指定的作者，否则与提交者相同
+    let committer = read_author()?;
+    let author = match author {
+        Some(author) => {
+            validate_author(author)?;
+            author.to_string()
+        }
+        None => committer.clone(),
+    };
+
+    let parent_refs: Vec<&str> = parents.iter().map(String::as_str).collect();
+    let dates = CommitDates { author: Some(timestamp), committer: Some(committer_timestamp) };
+    let commit_id = write_commit(&tree_hash, &parent_refs, &author, &committer, dates, message)?;
 
     Ok(Commit {
         id: commit_id,
         message: message.to_string(),
-        author: "RustGit <rustgit@example.com>".to_string(),
+        author,
+        committer,
         timestamp,
+        committer_timestamp,
         tree_hash,
+        parents: parents.to_vec(),
     })
 }
 
+/// 构造 Git 风格的提交内容并存储为提交对象（每个父提交一行 parent），返回提交哈希
+pub fn write_commit(
+    tree_hash: &str,
+    parents: &[&str],
+    author: &str,
+    committer: &str,
+    dates: CommitDates,
+    message: &str,
+) -> Result<String> {
+    let (author_timestamp, committer_timestamp) = dates.resolve();
+    let parent_line: String = parents
+        .iter()
+        .map(|parent_id| format!("parent {}\n", parent_id))
+        .collect();
+    let commit_content = format!(
+        "tree {}\n{}author {} {} +0800\ncommitter {} {} +0800\n\n{}",
+        tree_hash, parent_line, author, author_timestamp, committer, committer_timestamp, message
+    );
+
+    // 存储提交对象，其哈希即为提交哈希
+    hash::store_object(ObjectType::Commit, commit_content.as_bytes())
+}
+
+/// 构造 Git 风格的附注标签内容（指向提交）并存储为标签对象，返回标签对象哈希
+pub fn write_tag(commit_id: &str, tag_name: &str, message: &str) -> Result<String> {
+    let tagger = read_author()?;
+    let timestamp = Local::now().timestamp();
+    let tag_content = format!(
+        "object {}\ntype {}\ntag {}\ntagger {} {} +0800\n\n{}",
+        commit_id, ObjectType::Commit.as_str(), tag_name, tagger, timestamp, message
+    );
+    hash::store_object(ObjectType::Tag, tag_content.as_bytes())
+}
+
 /// 保存提交记录（写入日志）
 pub fn save_commit(commit: &Commit) -> Result<()> {
     // 写入提交日志
-    let log_path = ".rust-git/logs/commits";
+    let logs_dir = Repository::open()?.logs_dir();
+    let log_path = logs_dir.join("commits");
     // 创建日志目录
-    if !Path::new(log_path).parent().unwrap().exists() {
-        fs::create_dir_all(Path::new(log_path).parent().unwrap())?;
+    if !logs_dir.exists() {
+        fs::create_dir_all(&logs_dir)?;
     }
     // 序列化提交信息
     let commit_json = serde_json::to_string_pretty(commit)
         .context("序列化提交信息失败")?;
     // 追加到日志文件
-    let mut log_content = if Path::new(log_path).exists() {
-        fs::read_to_string(log_path)?
+    let mut log_content = if log_path.exists() {
+        fs::read_to_string(&log_path)?
     } else {
         String::new()
     };
-    log_content.push_str(&format!("[{}] {}\n{}\n\n", commit.id, commit.message, commit_json));
-    fs::write(log_path, log_content)
+    // 标题行只保留提交信息首行，避免多行信息中的空行打断条目分隔
+    let subject = commit.message.lines().next().unwrap_or("");
+    log_content.push_str(&format!("[{}] {}\n{}\n\n", commit.id, subject, commit_json));
+    fs::write(&log_path, log_content)
         .context("写入提交日志失败")?;
 
     Ok(())
 }
 
+/// 引用日志（reflog）条目结构
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReflogEntry {
+    pub old_id: String,  // 变更前的提交哈希（无则为全 0）
+    pub new_id: String,  // 变更后的提交哈希
+    pub branch: String,  // 变更的分支（分离 HEAD 时为 HEAD）
+    pub timestamp: i64,  // 时间戳（秒）
+    pub action: String,  // 操作描述
+}
+
+/// 引用日志中表示"无提交"的哈希
+pub const NULL_COMMIT_ID: &str = "0000000000000000000000000000000000000000";
+
+/// 追加一条引用日志：`<old> <new> <branch> <timestamp> <action>`
+pub fn append_reflog(old_id: Option<&str>, new_id: &str, branch: &str, action: &str) -> Result<()> {
+    let log_path = Repository::open()?.logs_dir().join("HEAD");
+    if let Some(parent) = log_path.parent()
+        && !parent.exists()
+    {
+        fs::create_dir_all(parent)?;
+    }
+
+    let old_id = old_id.filter(|id| !id.is_empty()).unwrap_or(NULL_COMMIT_ID);
+    let new_id = if new_id.is_empty() { NULL_COMMIT_ID } else { new_id };
+    // 操作描述保持单行
+    let action = action.replace(['\r', '\n'], " ");
+    let line = format!("{} {} {} {} {}\n", old_id, new_id, branch, Local::now().timestamp(), action);
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .context("打开引用日志失败")?;
+    file.write_all(line.as_bytes())
+        .context("写入引用日志失败")?;
+
+    Ok(())
+}
+
+/// 读取全部引用日志（按写入顺序，最早的在前）
+pub fn read_reflog() -> Result<Vec<ReflogEntry>> {
+    let log_path = Repository::open()?.logs_dir().join("HEAD");
+    if !log_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&log_path)
+        .context("读取引用日志失败")?;
+    let mut entries = Vec::new();
+    for line in content.lines() {
+        let mut parts = line.splitn(5, ' ');
+        let (Some(old_id), Some(new_id), Some(branch), Some(timestamp)) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            continue; // 跳过格式不正确的行
+        };
+        entries.push(ReflogEntry {
+            old_id: old_id.to_string(),
+            new_id: new_id.to_string(),
+            branch: branch.to_string(),
+            timestamp: timestamp.parse().unwrap_or(0),
+            action: parts.next().unwrap_or("").to_string(),
+        });
+    }
+
+    Ok(entries)
+}
+
 /// 读取所有提交记录（按时间倒序）
 pub fn read_all_commits() -> Result<Vec<Commit>> {
-    let log_path = ".rust-git/logs/commits";
-    if !Path::new(log_path).exists() {
+    let log_path = Repository::open()?.logs_dir().join("commits");
+    if !log_path.exists() {
         return Ok(Vec::new());
     }
 
-    let log_content = fs::read_to_string(log_path)
+    let log_content = fs::read_to_string(&log_path)
         .context("读取提交日志失败")?;
     // 日志条目以空行分隔，保存格式为："[<id>] <message>\n<pretty JSON>\n\n"
     // 为兼容 Windows 回车，先规范化为 LF，再按两个 LF 分割条目
     let normalized = log_content.replace("\r\n", "\n");
+    // 单条记录损坏时跳过并警告，不影响其余记录
     let mut commits = Vec::new();
+    let mut skipped = 0;
     for entry in normalized.split("\n\n") {
         let entry = entry.trim();
         if entry.is_empty() {
             continue;
         }
         // 找到第一行结束位置，后续为 JSON 内容（可能多行）
-        if let Some(pos) = entry.find('\n') {
-            let json_part = &entry[pos + 1..];
-            let commit: Commit = serde_json::from_str(json_part)
-                .context("解析提交记录失败（JSON 解析错误）")?;
-            commits.push(commit);
-        } else {
-            // 如果没有换行，跳过格式不正确的条目
-            continue;
+        let (header, json_part) = entry.split_once('\n').unwrap_or((entry, ""));
+        match serde_json::from_str::<Commit>(json_part) {
+            Ok(mut commit) => {
+                if commit.committer_timestamp == 0 {
+                    commit.committer_timestamp = commit.timestamp;
+                }
+                commits.push(commit);
+            }
+            Err(err) => {
+                eprintln!("警告：跳过无法解析的提交记录 {}（{}）", header, err);
+                skipped += 1;
+            }
         }
     }
+    if skipped > 0 {
+        eprintln!("警告：提交日志中共有 {} 条记录无法解析，已跳过", skipped);
+    }
 
-    // 按时间戳倒序（最新提交在前）
-    commits.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    // 按时间戳倒序（最新提交在前）；同一秒内的提交按写入日志的先后倒序
+    commits.reverse();
+    commits.sort_by_key(|c| std::cmp::Reverse(c.timestamp));
     Ok(commits)
 }
 
@@ -136,14 +506,46 @@ pub fn format_commit(commit: &Commit) -> String {
     let time_dt = chrono::Local
         .timestamp_opt(commit.timestamp, 0)
         .single()
-        .unwrap_or_else(|| chrono::Local::now());
+        .unwrap_or_else(chrono::Local::now);
     let time = time_dt.format("%Y-%m-%d %H:%M:%S %z").to_string();
+    // 多行提交信息逐行缩进
+    let message: Vec<String> = commit.message
+        .lines()
+        .map(|line| if line.is_empty() { String::new() } else { format!("    {}", line) })
+        .collect();
+    // 合并提交额外列出各父提交的短哈希
+    let merge_line = if commit.parents.len() > 1 {
+        let short_ids: Vec<&str> = commit.parents.iter().map(|parent| &parent[..parent.len().min(7)]).collect();
+        format!("Merge: {}\n", short_ids.join(" "))
+    } else {
+        String::new()
+    };
     format!(
-        "commit {}\nAuthor: {}\nDate:   {}\n\n    {}\n",
-        commit.id, commit.author, time, commit.message
+        "{}\n{}Author: {}\nDate:   {}\n\n{}\n",
+        color::yellow(&format!("commit {}", commit.id)), merge_line, commit.author, time, message.join("\n")
     )
 }
 
+/// 格式化附注标签信息（标签名、创建者、时间、标签信息）
+pub fn format_tag(tag: &Tag) -> String {
+    let time_dt = chrono::Local
+        .timestamp_opt(tag.timestamp, 0)
+        .single()
+        .unwrap_or_else(chrono::Local::now);
+    let time = time_dt.format("%Y-%m-%d %H:%M:%S %z").to_string();
+    format!(
+        "{}\nTagger: {}\nDate:   {}\n\n{}\n",
+        color::yellow(&format!("tag {}", tag.name)), tag.tagger, time, tag.message.trim_end()
+    )
+}
+
+/// 格式化提交信息为单行（短哈希 + 提交信息首行）
+pub fn format_commit_oneline(commit: &Commit) -> String {
+    let short_id = &commit.id[..commit.id.len().min(7)];
+    let subject = commit.message.lines().next().unwrap_or("");
+    format!("{} {}", color::yellow(short_id), subject)
+}
+
 /// 更新分支的最新提交（提交时调用）
 pub fn update_branch_commit(branch_name: &str, commit_id: &str) -> Result<()> {
     crate::utils::fs::update_branch(branch_name, commit_id)
@@ -0,0 +1,294 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use crate::error::GitError;
+use crate::utils::config;
+use crate::utils::fs as utils_fs;
+use crate::utils::metadata::IndexEntry;
+
+/// 仓库内部目录名
+pub const GIT_DIR_NAME: &str = ".rust-git";
+
+/// rust-git 仓库：集中管理仓库根目录及 .rust-git 下各文件的路径
+#[derive(Debug, Clone)]
+pub struct Repository {
+    root: PathBuf,    // 仓库根目录（工作区顶层；裸仓库即仓库目录本身）
+    git_dir: PathBuf, // 仓库数据目录（普通仓库为 root/.rust-git）
+    bare: bool,       // 是否为裸仓库
+}
+
+impl Repository {
+    /// 从当前目录向上查找并打开仓库（支持在子目录中执行）
+    pub fn open() -> Result<Self> {
+        let current_dir = std::env::current_dir().context("获取当前目录失败")?;
+        Self::discover(&current_dir)
+    }
+
+    /// 从 start 目录向上查找包含 .rust-git 的目录；找不到时检查 start 是否为裸仓库
+    pub fn discover(start: &Path) -> Result<Self> {
+        let mut dir = start.to_path_buf();
+        loop {
+            let git_dir = dir.join(GIT_DIR_NAME);
+            if git_dir.is_dir() {
+                return Ok(Repository { root: dir, git_dir, bare: false });
+            }
+            if !dir.pop() {
+                break;
+            }
+        }
+
+        if is_bare_dir(start) {
+            return Ok(Repository {
+                root: start.to_path_buf(),
+                git_dir: start.to_path_buf(),
+                bare: true,
+            });
+        }
+
+        Err(GitError::NotARepository.into())
+    }
+
+    /// 仓库根目录
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// 仓库数据目录（.rust-git）
+    pub fn git_dir(&self) -> &Path {
+        &self.git_dir
+    }
+
+    /// 是否为裸仓库
+    pub fn is_bare(&self) -> bool {
+        self.bare
+    }
+
+    /// 需要工作区的命令在裸仓库中拒绝执行
+    pub fn ensure_work_tree(&self) -> Result<()> {
+        if self.bare {
+            return Err(GitError::BareRepository.into());
+        }
+        Ok(())
+    }
+
+    /// 对象存储目录
+    pub fn objects_dir(&self) -> PathBuf {
+        self.git_dir.join("objects")
+    }
+
+    /// 暂存区文件路径
+    pub fn index_path(&self) -> PathBuf {
+        self.git_dir.join("index")
+    }
+
+    /// HEAD 文件路径
+    pub fn head_path(&self) -> PathBuf {
+        self.git_dir.join("HEAD")
+    }
+
+    /// 分支引用目录（refs/heads）
+    pub fn heads_dir(&self) -> PathBuf {
+        self.git_dir.join("refs/heads")
+    }
+
+    /// 标签引用目录（refs/tags）
+    pub fn tags_dir(&self) -> PathBuf {
+        self.git_dir.join("refs/tags")
+    }
+
+    /// 分支引用文件路径（refs/heads/<name>）
+    pub fn branch_ref(&self, name: &str) -> PathBuf {
+        self.heads_dir().join(name)
+    }
+
+    /// 标签引用文件路径（refs/tags/<name>）
+    pub fn tag_ref(&self, name: &str) -> PathBuf {
+        self.tags_dir().join(name)
+    }
+
+    /// 日志目录（提交日志与引用日志）
+    pub fn logs_dir(&self) -> PathBuf {
+        self.git_dir.join("logs")
+    }
+
+    /// 钩子脚本目录（hooks）
+    pub fn hooks_dir(&self) -> PathBuf {
+        self.git_dir.join("hooks")
+    }
+
+    /// 记录上一次切换前 HEAD 位置的文件路径（供 `checkout -` 使用）
+    pub fn previous_head_path(&self) -> PathBuf {
+        self.git_dir.join("PREVIOUS_HEAD")
+    }
+
+    /// 未完成合并时记录被合并提交的文件路径（MERGE_HEAD）
+    pub fn merge_head_path(&self) -> PathBuf {
+        self.git_dir.join("MERGE_HEAD")
+    }
+
+    /// 未完成合并时记录冲突文件列表的文件路径（每行一个相对路径）
+    pub fn merge_conflicts_path(&self) -> PathBuf {
+        self.git_dir.join("MERGE_CONFLICTS")
+    }
+
+    /// 仓库配置文件路径
+    pub fn config_path(&self) -> PathBuf {
+        self.git_dir.join("config")
+    }
+
+    /// 读取暂存区为强类型条目列表（兼容旧格式：非数组的暂存区视为空；无法解析的条目报错并指明该条目，
+    /// 旧版本写入的未标准化路径如 `./src//main.rs` 按标准化写法返回）
+    pub fn index(&self) -> Result<Vec<IndexEntry>> {
+        let index_content = fs::read_to_string(self.index_path())
+            .context("读取暂存区 index 文件失败")?;
+        let index: serde_json::Value = serde_json::from_str(&index_content)
+            .context("解析 index 文件失败（JSON 格式错误）")?;
+        let entries = match index {
+            serde_json::Value::Array(entries) => entries,
+            _ => return Ok(Vec::new()),
+        };
+        entries
+            .into_iter()
+            .enumerate()
+            .map(|(position, entry)| {
+                let raw = entry.to_string();
+                let mut entry = serde_json::from_value::<IndexEntry>(entry)
+                    .context(format!("解析 index 文件第 {} 个条目失败：{}", position + 1, raw))?;
+                entry.path = utils_fs::normalize_path(&entry.path);
+                Ok(entry)
+            })
+            .collect()
+    }
+
+    /// 将强类型条目列表写入暂存区
+    pub fn write_index(&self, entries: &[IndexEntry]) -> Result<()> {
+        let index_content = serde_json::to_string_pretty(entries)
+            .context("序列化 index 失败")?;
+        utils_fs::write_atomic(&self.index_path(), index_content)
+            .context("写入 index 文件失败")?;
+        Ok(())
+    }
+
+    /// 解析 HEAD 指向的提交ID（跟随分支引用或读取分离 HEAD 中的提交ID；尚无提交时返回 None）
+    pub fn head(&self) -> Result<Option<String>> {
+        let head_path = self.head_path();
+        if !head_path.exists() {
+            return Ok(None);
+        }
+
+        let head_content = fs::read_to_string(&head_path)
+            .context("读取 HEAD 失败")?
+            .trim()
+            .to_string();
+        let commit_id = match head_content.strip_prefix("ref: ") {
+            // 符号引用：读取对应引用文件（不存在表示尚无提交）
+            Some(ref_name) => {
+                let ref_path = self.git_dir.join(ref_name.trim());
+                if !ref_path.exists() {
+                    return Ok(None);
+                }
+                fs::read_to_string(&ref_path)
+                    .context(format!("读取引用 {} 失败", ref_name.trim()))?
+                    .trim()
+                    .to_string()
+            }
+            // 分离 HEAD：直接存储提交ID
+            None => head_content,
+        };
+
+        Ok(Some(commit_id).filter(|id| !id.is_empty()))
+    }
+
+    /// 获取当前分支名（HEAD 缺失或为空时为默认分支；分离 HEAD 状态返回 `DETACHED_HEAD`）
+    pub fn current_branch(&self) -> Result<String> {
+        let head_path = self.head_path();
+        if !head_path.exists() {
+            return Ok(utils_fs::DEFAULT_BRANCH.to_string());
+        }
+
+        let head_content = fs::read_to_string(&head_path)
+            .context("读取 HEAD 失败")?;
+        // HEAD 格式：ref: refs/heads/[分支名]
+        let branch = if head_content.starts_with("ref: ") {
+            head_content.trim_start_matches("ref: refs/heads/").trim().to_string()
+        } else if head_content.trim().is_empty() {
+            utils_fs::DEFAULT_BRANCH.to_string()
+        } else {
+            // HEAD 直接存储提交ID：分离 HEAD 状态
+            utils_fs::DETACHED_HEAD.to_string()
+        };
+
+        Ok(branch)
+    }
+
+    /// 将 HEAD 指向分支（符号引用）
+    pub fn set_head_branch(&self, branch_name: &str) -> Result<()> {
+        utils_fs::write_atomic(&self.head_path(), format!("ref: refs/heads/{}", branch_name))
+            .context("更新 HEAD 指向分支失败")
+    }
+
+    /// 将 HEAD 直接指向提交（分离 HEAD 状态）
+    pub fn set_head_detached(&self, commit_id: &str) -> Result<()> {
+        utils_fs::write_atomic(&self.head_path(), commit_id)
+            .context("更新 HEAD 指向提交失败")
+    }
+
+    /// 将用户输入的路径（相对当前目录或绝对路径）转换为相对仓库根目录的标准化路径
+    pub fn relative_path(&self, path: &str) -> Result<String> {
+        // 文件可能已从工作区删除，此时无法 canonicalize，直接基于当前目录拼接
+        let abs_path = if Path::new(path).exists() {
+            utils_fs::get_absolute_path(path)?
+        } else {
+            std::env::current_dir()
+                .context("获取当前目录失败")?
+                .join(path)
+        };
+        self.strip_root(&abs_path)
+    }
+
+    /// 检查绝对路径（按词法消除 `.` 和 `..` 之后）是否位于仓库根目录内，否则返回友好的错误
+    pub fn ensure_inside(&self, abs_path: &Path) -> Result<PathBuf> {
+        let mut normalized = PathBuf::new();
+        for component in abs_path.components() {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    normalized.pop();
+                }
+                other => normalized.push(other),
+            }
+        }
+
+        if !normalized.starts_with(&self.root) {
+            return Err(GitError::PathOutsideRepo {
+                path: normalized,
+                root: self.root.clone(),
+            }
+            .into());
+        }
+        Ok(normalized)
+    }
+
+    /// 将仓库内的绝对路径转换为相对仓库根目录的标准化路径
+    pub fn strip_root(&self, abs_path: &Path) -> Result<String> {
+        let normalized = self.ensure_inside(abs_path)?;
+        let rel_path = normalized.strip_prefix(&self.root)
+            .context(format!(
+                "文件 {} 不在 rust-git 仓库目录 {} 下",
+                abs_path.display(),
+                self.root.display()
+            ))?
+            .to_str()
+            .ok_or_else(|| anyhow::anyhow!("路径转换为字符串失败：{}", abs_path.display()))?;
+        Ok(utils_fs::normalize_path(rel_path))
+    }
+}
+
+/// 检查目录是否为裸仓库（由 `init --bare` 创建，config 中 core.bare = true）
+fn is_bare_dir(dir: &Path) -> bool {
+    dir.join("objects").is_dir()
+        && config::read_config_file(&dir.join("config"))
+            .ok()
+            .and_then(|repo_config| repo_config.get("core.bare").and_then(|value| config::parse_bool(value)))
+            .unwrap_or(false)
+}
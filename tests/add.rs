@@ -0,0 +1,121 @@
+mod common;
+
+use common::TestRepo;
+
+#[test]
+fn add_multiple_paths() {
+    let repo = TestRepo::new();
+    repo.write("a.txt", "a\n");
+    repo.write("b.txt", "b\n");
+
+    repo.ok(&["add", "a.txt", "b.txt"]);
+    assert_eq!(repo.staged_paths(), ["a.txt", "b.txt"]);
+}
+
+#[test]
+fn add_skips_ignored_files() {
+    let repo = TestRepo::new();
+    repo.write(".rust-gitignore", "*.log\n");
+    repo.write("keep.txt", "keep\n");
+    repo.write("debug.log", "noise\n");
+    repo.write("logs/trace.log", "noise\n");
+
+    repo.ok(&["add", "."]);
+    let staged = repo.staged_paths().join("\n");
+    assert!(staged.contains("keep.txt"), "{}", staged);
+    assert!(!staged.contains(".log"), "{}", staged);
+}
+
+#[test]
+fn add_and_rm_reject_paths_outside_repository() {
+    let outside = TestRepo::empty();
+    outside.write("secret.txt", "secret\n");
+    let repo = TestRepo::new();
+    let escaped = format!("../{}/secret.txt", outside.root().file_name().unwrap().to_string_lossy());
+
+    let err = repo.fail(&["add", &escaped]);
+    assert!(err.contains("path is outside the repository"), "{}", err);
+    let err = repo.fail(&["rm", &escaped]);
+    assert!(err.contains("path is outside the repository"), "{}", err);
+    assert_eq!(outside.read("secret.txt"), "secret\n");
+    assert_eq!(repo.ok(&["ls-files"]), "");
+}
+
+#[test]
+fn add_all_stages_deletions() {
+    let repo = TestRepo::new();
+    repo.write("keep.txt", "keep\n");
+    repo.write("gone.txt", "gone\n");
+    repo.ok(&["add", "keep.txt", "gone.txt"]);
+    repo.ok(&["commit", "first"]);
+    std::fs::remove_file(repo.path("gone.txt")).unwrap();
+    repo.write("new.txt", "new\n");
+
+    repo.ok(&["add", "-A"]);
+    assert_eq!(repo.ok(&["ls-files"]), "keep.txt\nnew.txt\n");
+}
+
+#[test]
+fn add_dry_run_leaves_index_unchanged() {
+    let repo = TestRepo::new();
+    repo.write("dir/a.txt", "a\n");
+    repo.write("dir/sub/b.txt", "b\n");
+    let index_before = repo.read(".rust-git/index");
+
+    let out = repo.ok(&["add", "-n", "dir"]);
+    assert!(out.contains("dir/a.txt") && out.contains("dir/sub/b.txt"), "{}", out);
+    assert_eq!(repo.read(".rust-git/index"), index_before);
+    assert_eq!(repo.ok(&["ls-files"]), "");
+}
+
+#[test]
+fn add_verbose_lists_each_file() {
+    let repo = TestRepo::new();
+    repo.write("dir/a.txt", "a\n");
+    repo.write("dir/b.txt", "b\n");
+
+    let verbose = repo.ok(&["add", "-v", "dir"]);
+    assert!(verbose.contains("添加：dir/a.txt") && verbose.contains("添加：dir/b.txt"), "{}", verbose);
+
+    repo.write("dir/c.txt", "c\n");
+    let normal = repo.ok(&["add", "dir"]);
+    assert!(!normal.contains("添加："), "{}", normal);
+}
+
+#[test]
+fn add_patch_stages_only_selected_hunk() {
+    let repo = TestRepo::new();
+    let original: String = (1..=20).map(|i| format!("{}\n", i)).collect();
+    repo.commit_file("a.txt", &original, "first");
+    let modified = original.replacen("2\n", "two\n", 1).replacen("19\n", "nineteen\n", 1);
+    repo.write("a.txt", &modified);
+
+    let err = repo.fail(&["add", "-p", "a.txt", "--hunks", "3"]);
+    assert!(err.contains("超出范围"), "{}", err);
+
+    repo.ok(&["add", "-p", "a.txt", "--hunks", "1"]);
+    let staged = repo.ok(&["ls-files", "-s"]);
+    let blob = &staged.lines().next().unwrap()[7..47];
+    assert_eq!(repo.ok(&["cat-file", "-p", blob]), original.replacen("2\n", "two\n", 1));
+    // 工作区保持不变，第二块仍为未暂存的修改
+    assert_eq!(repo.read("a.txt"), modified);
+    assert_eq!(repo.ok(&["status", "--porcelain"]), "MM a.txt\n");
+}
+
+#[test]
+fn add_patch_matches_index_entry_ignoring_case() {
+    let repo = TestRepo::new();
+    repo.commit_file("file.txt", "1\n2\n3\n", "first");
+    // 模拟大小写不敏感的文件系统：工作区中的文件名大小写与暂存区不同
+    std::fs::rename(repo.path("file.txt"), repo.path("File.txt")).unwrap();
+    repo.write("File.txt", "one\n2\n3\n");
+
+    let err = repo.fail(&["add", "-p", "File.txt", "--hunks", "1"]);
+    assert!(err.contains("未被跟踪"), "{}", err);
+
+    repo.ok(&["config", "core.ignorecase", "true"]);
+    repo.ok(&["add", "-p", "File.txt", "--hunks", "1"]);
+    let staged = repo.ok(&["ls-files", "-s"]);
+    assert!(staged.ends_with("\tfile.txt\n"), "{}", staged);
+    assert_eq!(repo.ok(&["cat-file", "-p", &staged[7..47]]), "one\n2\n3\n");
+}
@@ -0,0 +1,29 @@
+mod common;
+
+use common::TestRepo;
+use rust_git::api::{self, FileState, StatusEntry};
+use rust_git::utils::repository::Repository;
+
+#[test]
+fn api_status_returns_structured_entries() {
+    let repo = TestRepo::new();
+    repo.commit_file("tracked.txt", "v1\n", "first");
+    repo.write("tracked.txt", "v2\n");
+    repo.write("staged.txt", "staged\n");
+    repo.ok(&["add", "staged.txt"]);
+    repo.write("loose.txt", "loose\n");
+
+    let _cwd = repo.enter();
+    let opened = Repository::open().unwrap();
+    let entries = api::status(&opened).unwrap();
+    assert_eq!(
+        entries,
+        [
+            StatusEntry { path: "loose.txt".to_string(), staged: None, unstaged: Some(FileState::Untracked) },
+            StatusEntry { path: "staged.txt".to_string(), staged: Some(FileState::Added), unstaged: None },
+            StatusEntry { path: "tracked.txt".to_string(), staged: None, unstaged: Some(FileState::Modified) },
+        ]
+    );
+    assert_eq!(api::ls_files(&opened).unwrap().len(), 2);
+    assert_eq!(api::log(&opened, None).unwrap()[0].message, "first");
+}
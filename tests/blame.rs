@@ -0,0 +1,20 @@
+mod common;
+
+use common::TestRepo;
+
+#[test]
+fn blame_attributes_lines_to_commits() {
+    let repo = TestRepo::new();
+    let first = repo.commit_file("f.txt", "keep\nold\n", "first");
+    let second = repo.commit_file("f.txt", "keep\nnew\nadded\n", "second");
+
+    let out = repo.ok(&["blame", "f.txt"]);
+    let owners: Vec<(&str, &str)> = out
+        .lines()
+        .map(|line| (&line[..7], line.rsplit(") ").next().unwrap()))
+        .collect();
+    assert_eq!(
+        owners,
+        [(&first[..7], "keep"), (&second[..7], "new"), (&second[..7], "added")]
+    );
+}
@@ -0,0 +1,177 @@
+mod common;
+
+use common::TestRepo;
+use rust_git::utils::fs as utils_fs;
+
+/// 列出本地分支名（去掉当前分支标记）
+fn branches(repo: &TestRepo) -> Vec<String> {
+    repo.ok(&["branch"])
+        .lines()
+        .skip(1)
+        .map(|line| line[2..].to_string())
+        .collect()
+}
+
+#[test]
+fn rename_current_branch_updates_head() {
+    let repo = TestRepo::new();
+    let head = repo.commit_file("a.txt", "a\n", "first");
+
+    repo.ok(&["branch", "-m", "main"]);
+    assert_eq!(repo.read(".rust-git/HEAD").trim(), "ref: refs/heads/main");
+    assert_eq!(branches(&repo), ["main"]);
+    assert_eq!(repo.rev("main"), head);
+}
+
+#[test]
+fn rename_other_branch_keeps_head() {
+    let repo = TestRepo::new();
+    let head = repo.commit_file("a.txt", "a\n", "first");
+    repo.ok(&["branch", "feat"]);
+
+    repo.ok(&["branch", "feat", "-m", "topic"]);
+    assert_eq!(repo.read(".rust-git/HEAD").trim(), "ref: refs/heads/master");
+    let mut names = branches(&repo);
+    names.sort();
+    assert_eq!(names, ["master", "topic"]);
+    assert_eq!(repo.rev("topic"), head);
+}
+
+#[test]
+fn branch_before_first_commit_fails() {
+    let repo = TestRepo::new();
+
+    let err = repo.fail(&["branch", "feat"]);
+    assert!(err.contains("cannot create branch: no commits yet"), "{}", err);
+    assert!(!repo.path(".rust-git/refs/heads/feat").exists());
+}
+
+#[test]
+fn branch_list_verbose_and_pattern() {
+    let repo = TestRepo::new();
+    let head = repo.commit_file("a.txt", "a\n", "first subject");
+    repo.ok(&["branch", "feature-x"]);
+    repo.ok(&["branch", "feature-y"]);
+    repo.ok(&["branch", "hotfix"]);
+
+    let verbose = repo.ok(&["branch", "-v"]);
+    let master = verbose.lines().find(|line| line.starts_with("* master")).expect(&verbose);
+    assert!(master.contains(&head[..7]) && master.ends_with("first subject"), "{}", verbose);
+
+    let filtered = repo.ok(&["branch", "--list", "feature-*"]);
+    let names: Vec<&str> = filtered.lines().skip(1).map(|line| line.trim()).collect();
+    assert_eq!(names, ["feature-x", "feature-y"]);
+}
+
+#[test]
+fn invalid_branch_names_are_rejected() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "a\n", "first");
+
+    for name in [".hidden", "foo..bar", "x.lock", "a b", "tip~1", "HEAD", "../escape"] {
+        let err = repo.fail(&["branch", name]);
+        assert!(err.contains("不合法"), "{}: {}", name, err);
+    }
+    assert_eq!(branches(&repo), ["master"]);
+}
+
+#[test]
+fn valid_branch_names_are_accepted() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "a\n", "first");
+
+    for name in ["feature-1", "release_2.0", "v1.lock-free", "UPPER"] {
+        repo.ok(&["branch", name]);
+    }
+    assert_eq!(branches(&repo), ["UPPER", "feature-1", "master", "release_2.0", "v1.lock-free"]);
+}
+
+#[test]
+fn branch_names_cannot_escape_heads_dir() {
+    let repo = TestRepo::new();
+    let head = repo.commit_file("a.txt", "a\n", "first");
+    repo.ok(&["tag", "v1"]);
+
+    let err = repo.fail(&["branch", "../../HEAD", "-m", "x"]);
+    assert!(err.contains("不合法"), "{}", err);
+    let err = repo.fail(&["branch", "-d", "../tags/v1"]);
+    assert!(err.contains("不合法"), "{}", err);
+    let err = repo.fail(&["branch", "-D", "../../HEAD"]);
+    assert!(err.contains("不合法"), "{}", err);
+    let err = repo.fail(&["merge", "../tags/v1"]);
+    assert!(err.contains("不合法"), "{}", err);
+
+    assert_eq!(repo.read(".rust-git/HEAD").trim(), "ref: refs/heads/master");
+    assert_eq!(repo.ok(&["tag"]), "v1\n");
+    assert_eq!(repo.rev("master"), head);
+    assert!(!repo.path(".rust-git/refs/heads/x").exists());
+}
+
+#[test]
+fn ref_name_rules() {
+    for name in ["master", "feature/x", "a.b", "v1.0-rc1"] {
+        assert!(utils_fs::is_valid_ref_name(name), "{}", name);
+    }
+    for name in ["", "@", ".hidden", "foo..bar", "x.lock", "dir/.x", "end.", "/lead", "trail/", "a//b", "a@{1}", "a:b"] {
+        assert!(!utils_fs::is_valid_ref_name(name), "{}", name);
+    }
+}
+
+#[test]
+fn branch_named_like_old_temp_file_survives_commit() {
+    let repo = TestRepo::new();
+    let first = repo.commit_file("a.txt", "1\n", "first");
+    repo.ok(&["branch", "master.tmp"]);
+    repo.commit_file("a.txt", "2\n", "second");
+
+    assert_eq!(repo.rev("master.tmp"), first);
+    assert_eq!(branches(&repo), ["master", "master.tmp"]);
+}
+
+#[test]
+fn leftover_temp_files_are_not_listed_as_refs() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "1\n", "first");
+    repo.ok(&["tag", "v1"]);
+    repo.write(".rust-git/refs/heads/.master.999.0.lock", "");
+    repo.write(".rust-git/refs/tags/.v1.999.0.lock", "");
+
+    assert_eq!(branches(&repo), ["master"]);
+    assert_eq!(repo.ok(&["tag"]), "v1\n");
+    repo.ok(&["gc"]);
+}
+
+#[test]
+fn delete_unmerged_branch_requires_force() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "a\n", "first");
+    repo.ok(&["branch", "merged"]);
+    repo.ok(&["branch", "feat"]);
+    repo.ok(&["checkout", "feat"]);
+    repo.commit_file("f.txt", "f\n", "feature only");
+    repo.ok(&["checkout", "master"]);
+
+    // 提交已包含在其他分支中的分支可以直接删除
+    repo.ok(&["branch", "-d", "merged"]);
+
+    let err = repo.fail(&["branch", "-d", "feat"]);
+    assert!(err.contains("未合并"), "{}", err);
+    assert!(err.contains("branch -D feat"), "{}", err);
+    assert_eq!(branches(&repo), ["feat", "master"]);
+
+    repo.ok(&["branch", "-D", "feat"]);
+    assert_eq!(branches(&repo), ["master"]);
+}
+
+#[test]
+fn delete_with_force_flag_removes_unmerged_branch() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "a\n", "first");
+    repo.ok(&["branch", "feat"]);
+    repo.ok(&["checkout", "feat"]);
+    repo.commit_file("f.txt", "f\n", "feature only");
+    repo.ok(&["checkout", "master"]);
+
+    repo.ok(&["branch", "-d", "feat", "-f"]);
+    assert_eq!(branches(&repo), ["master"]);
+}
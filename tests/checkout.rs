@@ -0,0 +1,73 @@
+mod common;
+
+use common::TestRepo;
+
+#[test]
+fn checkout_from_subdirectory_writes_relative_to_root() {
+    let repo = TestRepo::new();
+    repo.write("sub/keep.txt", "keep\n");
+    repo.write("top.txt", "v1\n");
+    repo.ok(&["add", "sub/keep.txt", "top.txt"]);
+    repo.ok(&["commit", "first"]);
+    repo.ok(&["branch", "feat"]);
+    repo.ok(&["checkout", "feat"]);
+    repo.commit_file("top.txt", "v2\n", "feature");
+    repo.ok(&["checkout", "master"]);
+    assert_eq!(repo.read("top.txt"), "v1\n");
+
+    repo.ok_in("sub", &["checkout", "feat"]);
+    assert_eq!(repo.read("top.txt"), "v2\n");
+    assert!(!repo.path("sub/top.txt").exists());
+}
+
+#[test]
+fn checkout_commit_id_detaches_head() {
+    let repo = TestRepo::new();
+    let first = repo.commit_file("a.txt", "1\n", "first");
+    repo.commit_file("a.txt", "2\n", "second");
+
+    repo.ok(&["checkout", &first]);
+    assert_eq!(repo.read(".rust-git/HEAD").trim(), first);
+    assert_eq!(repo.read("a.txt"), "1\n");
+}
+
+#[test]
+fn checkout_refuses_to_clobber_uncommitted_changes() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "v1\n", "first");
+    repo.ok(&["branch", "feat"]);
+    repo.commit_file("a.txt", "v2\n", "second");
+    repo.write("a.txt", "local edit\n");
+
+    let err = repo.fail(&["checkout", "feat"]);
+    assert!(err.contains("a.txt"), "{}", err);
+    assert_eq!(repo.read("a.txt"), "local edit\n");
+    assert_eq!(repo.read(".rust-git/HEAD").trim(), "ref: refs/heads/master");
+}
+
+#[test]
+fn double_dash_picks_file_over_branch() {
+    let repo = TestRepo::new();
+    repo.commit_file("feat", "committed\n", "first");
+    repo.ok(&["branch", "feat"]);
+    repo.write("feat", "local edit\n");
+
+    repo.ok(&["checkout", "--", "feat"]);
+    assert_eq!(repo.read("feat"), "committed\n");
+    assert_eq!(repo.read(".rust-git/HEAD").trim(), "ref: refs/heads/master");
+}
+
+#[test]
+fn checkout_dash_toggles_between_branches() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "a\n", "first");
+    repo.ok(&["branch", "feat"]);
+    let err = repo.fail(&["checkout", "-"]);
+    assert!(err.contains("没有上一个分支"), "{}", err);
+
+    repo.ok(&["checkout", "feat"]);
+    repo.ok(&["checkout", "-"]);
+    assert_eq!(repo.read(".rust-git/HEAD").trim(), "ref: refs/heads/master");
+    repo.ok(&["checkout", "-"]);
+    assert_eq!(repo.read(".rust-git/HEAD").trim(), "ref: refs/heads/feat");
+}
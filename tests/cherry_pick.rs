@@ -0,0 +1,23 @@
+mod common;
+
+use common::TestRepo;
+
+#[test]
+fn cherry_pick_from_feature_branch() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "base\n", "base");
+    repo.ok(&["branch", "feat"]);
+    let master = repo.commit_file("b.txt", "master\n", "on master");
+    repo.ok(&["checkout", "feat"]);
+    repo.commit_file("feature.txt", "feature\n", "add feature");
+    repo.ok(&["checkout", "master"]);
+    std::fs::remove_file(repo.path("feature.txt")).unwrap();
+
+    repo.ok(&["cherry-pick", "feat"]);
+    assert_eq!(repo.read("feature.txt"), "feature\n");
+    assert_eq!(repo.read("b.txt"), "master\n");
+    assert_eq!(repo.rev("HEAD~1"), master);
+    let subject = repo.ok(&["log", "--oneline", "-n", "1"]);
+    assert!(subject.ends_with("add feature\n"), "{}", subject);
+    assert_eq!(repo.ok(&["ls-files"]), "a.txt\nb.txt\nfeature.txt\n");
+}
@@ -0,0 +1,27 @@
+mod common;
+
+use common::TestRepo;
+use rust_git::utils::color;
+
+#[test]
+fn no_color_output_has_no_ansi_escapes() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "a\n", "first");
+    repo.write("a.txt", "b\n");
+
+    for args in [&["--no-color", "log"][..], &["--no-color", "diff"], &["--no-color", "status"]] {
+        let out = repo.ok(args);
+        assert!(!out.is_empty());
+        assert!(!out.contains('\x1b'), "{:?}: {:?}", args, out);
+    }
+}
+
+#[test]
+fn init_with_no_color_disables_painting() {
+    color::set_enabled(true);
+    assert_eq!(color::green("ok"), "\x1b[32mok\x1b[0m");
+
+    color::init(true);
+    assert!(!color::enabled());
+    assert_eq!(color::green("ok"), "ok");
+}
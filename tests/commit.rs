@@ -0,0 +1,252 @@
+mod common;
+
+use common::TestRepo;
+use chrono::TimeZone;
+use rust_git::utils::{fs as utils_fs, hash, metadata};
+
+#[test]
+fn commit_advances_branch_pointer() {
+    let repo = TestRepo::new();
+    let first = repo.commit_file("a.txt", "one\n", "first");
+    repo.commit_file("a.txt", "two\n", "second");
+
+    let _cwd = repo.enter();
+    let head = utils_fs::read_branch_commit("master").unwrap();
+    assert_ne!(head, first);
+    let commit = hash::read_commit(&head).unwrap();
+    assert_eq!(commit.message.trim(), "second");
+    assert_eq!(commit.first_parent(), Some(first.as_str()));
+}
+
+#[test]
+fn commit_object_records_parent_id() {
+    let repo = TestRepo::new();
+    let first = repo.commit_file("a.txt", "one\n", "first");
+    let second = repo.commit_file("a.txt", "two\n", "second");
+
+    let _cwd = repo.enter();
+    let parent_lines = |id: &str| -> Vec<String> {
+        String::from_utf8(hash::read_object(id).unwrap())
+            .unwrap()
+            .lines()
+            .take_while(|line| !line.is_empty())
+            .filter(|line| line.starts_with("parent "))
+            .map(str::to_string)
+            .collect()
+    };
+    // 首次提交没有父提交，之后的提交记录上一个提交的哈希
+    assert!(parent_lines(&first).is_empty());
+    assert_eq!(parent_lines(&second), [format!("parent {}", first)]);
+}
+
+#[test]
+fn commit_author_comes_from_config() {
+    let repo = TestRepo::new();
+    repo.ok(&["config", "user.name", "Alice Liddell"]);
+    repo.ok(&["config", "user.email", "alice@example.com"]);
+    let id = repo.commit_file("a.txt", "a\n", "first");
+
+    let object = repo.ok(&["cat-file", "-p", &id]);
+    assert!(object.contains("author Alice Liddell <alice@example.com> "), "{}", object);
+}
+
+#[test]
+fn commit_warns_about_files_modified_after_staging() {
+    let repo = TestRepo::new();
+    repo.write("a.txt", "staged\n");
+    repo.ok(&["add", "a.txt"]);
+    repo.write("a.txt", "modified later\n");
+
+    let out = repo.ok(&["commit", "first"]);
+    assert!(out.contains("警告：以下文件在暂存后又被修改"), "{}", out);
+    assert!(out.contains("  a.txt"), "{}", out);
+    // 提交的是暂存区中的版本
+    assert_eq!(repo.ok(&["show", "HEAD"]).lines().last(), Some("+staged"));
+}
+
+#[test]
+fn commit_drops_deleted_tracked_files() {
+    let repo = TestRepo::new();
+    repo.write("a.txt", "a\n");
+    repo.write("b.txt", "b\n");
+    repo.ok(&["add", "a.txt", "b.txt"]);
+    std::fs::remove_file(repo.path("b.txt")).unwrap();
+
+    let out = repo.ok(&["commit", "only a"]);
+    assert!(out.contains("删除：b.txt"), "{}", out);
+
+    let _cwd = repo.enter();
+    let tree = hash::read_commit_tree(&utils_fs::read_branch_commit("master").unwrap()).unwrap();
+    assert_eq!(tree.keys().collect::<Vec<_>>(), ["a.txt"]);
+}
+
+#[test]
+fn failed_commit_leaves_index_unchanged() {
+    let repo = TestRepo::new();
+    repo.write("a.txt", "a\n");
+    repo.write("b.txt", "b\n");
+    repo.ok(&["add", "a.txt", "b.txt"]);
+    std::fs::remove_file(repo.path("b.txt")).unwrap();
+    // 提交日志路径被目录占用，保存提交记录失败
+    let _ = std::fs::remove_file(repo.path(".rust-git/logs/commits"));
+    std::fs::create_dir_all(repo.path(".rust-git/logs/commits")).unwrap();
+
+    repo.fail(&["commit", "drop b"]);
+    // 提交失败时不修改暂存区
+    assert_eq!(repo.staged_paths(), ["a.txt", "b.txt"]);
+}
+
+#[test]
+fn commit_author_override() {
+    let repo = TestRepo::new();
+    repo.ok(&["config", "user.name", "Committer"]);
+    repo.ok(&["config", "user.email", "committer@example.com"]);
+    repo.write("a.txt", "a\n");
+    repo.ok(&["add", "a.txt"]);
+    repo.ok(&["commit", "-q", "--author", "Grace Hopper <grace@example.com>", "imported"]);
+
+    let _cwd = repo.enter();
+    let commit = hash::read_commit(&utils_fs::read_branch_commit("master").unwrap()).unwrap();
+    assert_eq!(commit.author, "Grace Hopper <grace@example.com>");
+    assert_eq!(commit.committer, "Committer <committer@example.com>");
+}
+
+#[test]
+fn commit_date_override() {
+    let repo = TestRepo::new();
+    repo.write("a.txt", "a\n");
+    repo.ok(&["add", "a.txt"]);
+    repo.ok(&["commit", "-q", "--date", "2024-01-02T03:04:05+00:00", "imported"]);
+
+    let _cwd = repo.enter();
+    let commit = hash::read_commit(&utils_fs::read_branch_commit("master").unwrap()).unwrap();
+    assert_eq!(commit.timestamp, 1704164645);
+    let expected = chrono::Local
+        .timestamp_opt(1704164645, 0)
+        .unwrap()
+        .format("Date:   %Y-%m-%d %H:%M:%S %z")
+        .to_string();
+    assert!(metadata::format_commit(&commit).contains(&expected));
+}
+
+#[test]
+fn created_commit_round_trips() {
+    let repo = TestRepo::new();
+    let parent = repo.commit_file("a.txt", "a\n", "first");
+    repo.write("b.txt", "b\n");
+    repo.ok(&["add", "b.txt"]);
+
+    let _cwd = repo.enter();
+    let created = metadata::create_commit("second\n\nbody line", Some("Ann <ann@example.com>"), Some(1700000000)).unwrap();
+    let parsed = hash::read_commit(&created.id).unwrap();
+    assert_eq!(parsed.id, created.id);
+    assert_eq!(parsed.message, created.message);
+    assert_eq!(parsed.author, created.author);
+    assert_eq!(parsed.committer, created.committer);
+    assert_eq!(parsed.timestamp, 1700000000);
+    assert_eq!(parsed.tree_hash, created.tree_hash);
+    assert_eq!(parsed.parents, [parent]);
+}
+
+#[test]
+fn commit_object_records_author_and_committer() {
+    let repo = TestRepo::new();
+    repo.ok(&["config", "user.name", "Committer"]);
+    repo.ok(&["config", "user.email", "committer@example.com"]);
+    repo.write("a.txt", "a\n");
+    repo.ok(&["add", "a.txt"]);
+    repo.ok(&["commit", "-q", "--author", "Author <author@example.com>", "patch"]);
+
+    let object = repo.ok(&["cat-file", "-p", &repo.rev("HEAD")]);
+    assert!(object.lines().any(|line| line.starts_with("author Author <author@example.com> ")), "{}", object);
+    assert!(object.lines().any(|line| line.starts_with("committer Committer <committer@example.com> ")), "{}", object);
+}
+
+#[test]
+fn commit_subset_of_staged_paths() {
+    let repo = TestRepo::new();
+    repo.commit_file("base.txt", "base\n", "base");
+    repo.write("a.txt", "a\n");
+    repo.write("b.txt", "b\n");
+    repo.ok(&["add", "a.txt", "b.txt"]);
+
+    repo.ok(&["commit", "-q", "only a", "--", "a.txt"]);
+    assert_eq!(repo.ok(&["status", "--porcelain"]), "A  b.txt\n");
+
+    let _cwd = repo.enter();
+    let tree = hash::read_commit_tree(&utils_fs::read_branch_commit("master").unwrap()).unwrap();
+    assert_eq!(tree.keys().collect::<Vec<_>>(), ["a.txt", "base.txt"]);
+}
+
+#[test]
+fn commit_paths_leaves_other_deletions_staged() {
+    let repo = TestRepo::new();
+    repo.write("a.txt", "a\n");
+    repo.write("b.txt", "b\n");
+    repo.ok(&["add", "a.txt", "b.txt"]);
+    repo.ok(&["commit", "-q", "first"]);
+    repo.write("a.txt", "a2\n");
+    repo.ok(&["add", "a.txt"]);
+    std::fs::remove_file(repo.path("b.txt")).unwrap();
+
+    let out = repo.ok(&["commit", "only a", "--", "a.txt"]);
+    assert!(!out.contains("删除：b.txt"), "{}", out);
+    // 未指定的 b.txt 仍在暂存区和提交的目录树中
+    assert_eq!(repo.ok(&["ls-files"]), "a.txt\nb.txt\n");
+
+    let _cwd = repo.enter();
+    let tree = hash::read_commit_tree(&utils_fs::read_branch_commit("master").unwrap()).unwrap();
+    assert_eq!(tree.keys().collect::<Vec<_>>(), ["a.txt", "b.txt"]);
+}
+
+#[test]
+fn commit_message_from_file() {
+    let repo = TestRepo::new();
+    repo.write("a.txt", "a\n");
+    repo.ok(&["add", "a.txt"]);
+    repo.write("msg.txt", "Subject line\n\nBody paragraph\nsecond body line\n");
+
+    repo.ok(&["commit", "-q", "-F", "msg.txt"]);
+    let _cwd = repo.enter();
+    let commit = hash::read_commit(&utils_fs::read_branch_commit("master").unwrap()).unwrap();
+    assert_eq!(commit.message.lines().collect::<Vec<_>>(), ["Subject line", "", "Body paragraph", "second body line"]);
+}
+
+#[test]
+fn commit_rejects_empty_message() {
+    let repo = TestRepo::new();
+    repo.write("a.txt", "a\n");
+    repo.ok(&["add", "a.txt"]);
+
+    let err = repo.fail(&["commit", "   "]);
+    assert!(err.contains("提交信息为空"), "{}", err);
+    assert!(!repo.path(".rust-git/refs/heads/master").metadata().is_ok_and(|meta| meta.len() > 0));
+    repo.ok(&["commit", "-q", "--allow-empty-message", ""]);
+}
+
+#[test]
+fn allow_empty_commit_keeps_tree() {
+    let repo = TestRepo::new();
+    let first = repo.commit_file("a.txt", "a\n", "first");
+    let err = repo.fail(&["commit", "nothing"]);
+    assert!(err.contains("没有需要提交的变更"), "{}", err);
+
+    repo.ok(&["commit", "-q", "--allow-empty", "empty"]);
+    let _cwd = repo.enter();
+    let second = hash::read_commit(&utils_fs::read_branch_commit("master").unwrap()).unwrap();
+    assert_eq!(second.first_parent(), Some(first.as_str()));
+    assert_eq!(second.tree_hash, hash::read_commit(&first).unwrap().tree_hash);
+}
+
+#[test]
+fn commit_quiet_suppresses_summary() {
+    let repo = TestRepo::new();
+    repo.write("a.txt", "a\n");
+    repo.ok(&["add", "a.txt"]);
+    let out = repo.ok(&["commit", "loud"]);
+    assert!(out.contains("] loud"), "{}", out);
+
+    repo.write("a.txt", "b\n");
+    repo.ok(&["add", "a.txt"]);
+    assert_eq!(repo.ok(&["commit", "--quiet", "quiet"]), "");
+}
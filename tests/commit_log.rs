@@ -0,0 +1,21 @@
+mod common;
+
+use common::TestRepo;
+use rust_git::utils::metadata;
+
+#[test]
+fn corrupt_log_entry_is_skipped() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "1\n", "first");
+    let log_path = repo.path(".rust-git/logs/commits");
+    let mut log = std::fs::read_to_string(&log_path).unwrap();
+    log.push_str("[deadbeef] broken\n{\"id\": \"deadbeef\", \"message\": \n\n");
+    std::fs::write(&log_path, log).unwrap();
+    repo.commit_file("a.txt", "2\n", "second");
+
+    let _cwd = repo.enter();
+    let commits = metadata::read_all_commits().unwrap();
+    let messages: Vec<&str> = commits.iter().map(|commit| commit.message.as_str()).collect();
+    assert_eq!(messages.len(), 2);
+    assert!(messages.contains(&"first") && messages.contains(&"second"), "{:?}", messages);
+}
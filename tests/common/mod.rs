@@ -0,0 +1,168 @@
+//! 集成测试公共工具：在临时目录中创建仓库并调用 rust-git 可执行文件
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 同一测试进程内临时目录的序号
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// 库函数依赖当前目录定位仓库，切换当前目录的测试需串行执行
+static CWD_LOCK: Mutex<()> = Mutex::new(());
+
+/// 测试用临时仓库（离开作用域时删除整个目录）
+pub struct TestRepo {
+    root: PathBuf,
+}
+
+impl TestRepo {
+    /// 创建临时目录并在其中执行 init
+    pub fn new() -> TestRepo {
+        let repo = TestRepo::empty();
+        repo.ok(&["init"]);
+        repo
+    }
+
+    /// 只创建空的临时目录，不初始化仓库
+    pub fn empty() -> TestRepo {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.subsec_nanos())
+            .unwrap_or(0);
+        let root = std::env::temp_dir().join(format!(
+            "rust-git-test-{}-{}-{}",
+            std::process::id(),
+            NEXT_ID.fetch_add(1, Ordering::SeqCst),
+            nanos
+        ));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).expect("创建临时目录失败");
+        TestRepo { root: root.canonicalize().expect("转换临时目录为绝对路径失败") }
+    }
+
+    /// 仓库根目录
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// 仓库内相对路径对应的绝对路径
+    pub fn path(&self, rel: &str) -> PathBuf {
+        self.root.join(rel)
+    }
+
+    /// 在仓库根目录执行命令
+    pub fn run(&self, args: &[&str]) -> Output {
+        self.run_in("", args)
+    }
+
+    /// 在仓库内的子目录执行命令
+    pub fn run_in(&self, dir: &str, args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_rust-git"))
+            .args(args)
+            .current_dir(self.root.join(dir))
+            .env("RUST_BACKTRACE", "0")
+            .env("NO_COLOR", "1")
+            .output()
+            .expect("启动 rust-git 失败")
+    }
+
+    /// 执行命令并断言成功，返回标准输出
+    pub fn ok(&self, args: &[&str]) -> String {
+        self.ok_in("", args)
+    }
+
+    /// 在子目录执行命令并断言成功，返回标准输出
+    pub fn ok_in(&self, dir: &str, args: &[&str]) -> String {
+        let output = self.run_in(dir, args);
+        assert!(
+            output.status.success(),
+            "命令 {:?} 执行失败：\n{}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    }
+
+    /// 执行命令并断言失败，返回标准错误输出
+    pub fn fail(&self, args: &[&str]) -> String {
+        let output = self.run(args);
+        assert!(
+            !output.status.success(),
+            "命令 {:?} 应当失败，实际输出：\n{}",
+            args,
+            String::from_utf8_lossy(&output.stdout)
+        );
+        String::from_utf8_lossy(&output.stderr).into_owned()
+    }
+
+    /// 写入工作区文件（自动创建父目录）
+    pub fn write(&self, rel: &str, content: &str) {
+        let path = self.path(rel);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("创建父目录失败");
+        }
+        fs::write(&path, content).expect("写入文件失败");
+    }
+
+    /// 读取工作区文件
+    pub fn read(&self, rel: &str) -> String {
+        fs::read_to_string(self.path(rel)).expect("读取文件失败")
+    }
+
+    /// 写入文件、暂存并提交，返回新提交的哈希
+    pub fn commit_file(&self, rel: &str, content: &str, message: &str) -> String {
+        self.write(rel, content);
+        self.ok(&["add", rel]);
+        self.ok(&["commit", message]);
+        self.rev("HEAD")
+    }
+
+    /// 读取暂存区中的路径列表（按路径排序，与 ls-files 一致）
+    pub fn staged_paths(&self) -> Vec<String> {
+        let index: serde_json::Value =
+            serde_json::from_str(&self.read(".rust-git/index")).expect("解析暂存区失败");
+        let mut paths: Vec<String> = index
+            .as_array()
+            .expect("暂存区不是数组")
+            .iter()
+            .map(|entry| entry["path"].as_str().expect("暂存区条目缺少路径").to_string())
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    /// 将修订解析为完整哈希
+    pub fn rev(&self, rev: &str) -> String {
+        self.ok(&["rev-parse", rev]).trim().to_string()
+    }
+
+    /// 将当前目录切换到仓库根目录，供直接调用库函数的测试使用（返回值离开作用域时恢复）
+    pub fn enter(&self) -> CwdGuard {
+        let lock = CWD_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let previous = std::env::current_dir().expect("读取当前目录失败");
+        std::env::set_current_dir(&self.root).expect("切换当前目录失败");
+        CwdGuard { previous, _lock: lock }
+    }
+}
+
+impl Drop for TestRepo {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+/// 当前目录切换守卫：离开作用域时恢复原当前目录并释放锁
+pub struct CwdGuard {
+    previous: PathBuf,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl Drop for CwdGuard {
+    fn drop(&mut self) {
+        let _ = std::env::set_current_dir(&self.previous);
+    }
+}
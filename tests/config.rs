@@ -0,0 +1,25 @@
+mod common;
+
+use common::TestRepo;
+use rust_git::utils::config;
+
+#[test]
+fn config_round_trip_and_list_order() {
+    let repo = TestRepo::new();
+    repo.ok(&["config", "user.name", "Zed"]);
+    repo.ok(&["config", "core.autocrlf", "yes"]);
+    repo.ok(&["config", "user.email", "zed@example.com"]);
+
+    assert_eq!(repo.ok(&["config", "user.name"]), "Zed\n");
+    assert_eq!(
+        repo.ok(&["config", "--list"]),
+        "core.autocrlf=yes\nuser.email=zed@example.com\nuser.name=Zed\n"
+    );
+
+    let _cwd = repo.enter();
+    assert!(config::get_bool("core.autocrlf", false).unwrap());
+    assert!(!config::get_bool("core.ignorecase", false).unwrap());
+    assert_eq!(config::get_str("user.email", "").unwrap(), "zed@example.com");
+    config::set("user.name", "not bool").unwrap();
+    assert!(config::get_bool("user.name", false).is_err());
+}
@@ -0,0 +1,30 @@
+mod common;
+
+use common::TestRepo;
+use rust_git::utils::hash::{self, ObjectType};
+
+#[test]
+fn count_objects_counts_known_objects() {
+    let repo = TestRepo::new();
+    assert!(repo.ok(&["count-objects"]).starts_with("count: 0, size: 0 bytes"));
+
+    // 一次提交产生 blob、tree、commit 各一个对象
+    let commit = repo.commit_file("a.txt", "hello\n", "first");
+    let out = repo.ok(&["count-objects", "-v"]);
+    let lines: Vec<&str> = out.lines().collect();
+    assert!(lines[0].starts_with("count: 3, size: "), "{}", out);
+    assert!(lines.iter().any(|line| line.starts_with("blob: 1,")), "{}", out);
+    assert!(lines.iter().any(|line| line.starts_with("tree: 1,")), "{}", out);
+    assert!(lines.iter().any(|line| line.starts_with("commit: 1,")), "{}", out);
+
+    // 无法识别对象头的对象归为 unknown
+    let bogus = "ab".repeat(20);
+    repo.write(&format!(".rust-git/objects/{}/{}", &bogus[..2], &bogus[2..]), "no header");
+    let out = repo.ok(&["count-objects", "-v"]);
+    assert!(out.starts_with("count: 4, size: "), "{}", out);
+    assert!(out.contains("unknown: 1,"), "{}", out);
+
+    let _cwd = repo.enter();
+    assert_eq!(hash::read_object_type(&commit).unwrap(), Some(ObjectType::Commit));
+    assert_eq!(hash::read_object_type(&bogus).unwrap(), None);
+}
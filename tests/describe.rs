@@ -0,0 +1,15 @@
+mod common;
+
+use common::TestRepo;
+
+#[test]
+fn describe_counts_commits_since_tag() {
+    let repo = TestRepo::new();
+    let tagged = repo.commit_file("a.txt", "1\n", "c1");
+    repo.ok(&["tag", "v1.0"]);
+    repo.commit_file("a.txt", "2\n", "c2");
+    let head = repo.commit_file("a.txt", "3\n", "c3");
+
+    assert_eq!(repo.ok(&["describe"]), format!("v1.0-2-g{}\n", &head[..7]));
+    assert_eq!(repo.ok(&["describe", &tagged]), "v1.0\n");
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::TestRepo;
+
+#[test]
+fn diff_shows_added_line() {
+    let repo = TestRepo::new();
+    repo.write("a.txt", "one\n");
+    repo.ok(&["add", "a.txt"]);
+    repo.write("a.txt", "one\ntwo\n");
+
+    let out = repo.ok(&["diff"]);
+    assert!(out.contains("+++ b/a.txt"), "{}", out);
+    assert!(out.lines().any(|line| line == "+two"), "{}", out);
+}
+
+#[test]
+fn diff_shows_removed_line() {
+    let repo = TestRepo::new();
+    repo.write("a.txt", "one\ntwo\n");
+    repo.ok(&["add", "a.txt"]);
+    repo.write("a.txt", "one\n");
+
+    let out = repo.ok(&["diff"]);
+    assert!(out.lines().any(|line| line == "-two"), "{}", out);
+    assert!(!out.lines().any(|line| line.starts_with('+') && !line.starts_with("+++")), "{}", out);
+}
+
+#[test]
+fn diff_of_unchanged_file_is_empty() {
+    let repo = TestRepo::new();
+    repo.write("a.txt", "one\n");
+    repo.ok(&["add", "a.txt"]);
+
+    assert_eq!(repo.ok(&["diff"]), "");
+}
+
+#[test]
+fn diff_between_two_commits() {
+    let repo = TestRepo::new();
+    let old = repo.commit_file("a.txt", "one\n", "first");
+    repo.write("a.txt", "uno\n");
+    repo.write("b.txt", "new\n");
+    repo.ok(&["add", "a.txt", "b.txt"]);
+    repo.ok(&["commit", "second"]);
+
+    let out = repo.ok(&["diff", &old, "HEAD"]);
+    let a = out.find("diff --git a/a.txt b/a.txt").expect(&out);
+    let b = out.find("diff --git a/b.txt b/b.txt").expect(&out);
+    assert!(a < b);
+    assert!(out[a..b].lines().any(|line| line == "-one"), "{}", out);
+    assert!(out[a..b].lines().any(|line| line == "+uno"), "{}", out);
+    assert!(out[b..].lines().any(|line| line == "+new"), "{}", out);
+    assert!(!out[b..].lines().any(|line| line.starts_with('-') && !line.starts_with("---")), "{}", out);
+}
+
+#[test]
+fn diff_stat_summary() {
+    let repo = TestRepo::new();
+    let old = repo.commit_file("a.txt", "1\n2\n3\n4\n", "first");
+    repo.write("a.txt", "1\ntwo\n3\n");
+    repo.write("b.txt", "x\ny\n");
+    repo.ok(&["add", "a.txt", "b.txt"]);
+    repo.ok(&["commit", "second"]);
+
+    let out = repo.ok(&["diff", "--stat", &old, "HEAD"]);
+    assert_eq!(
+        out.lines().last(),
+        Some(" 2 files changed, 3 insertions(+), 2 deletions(-)"),
+        "{}",
+        out
+    );
+}
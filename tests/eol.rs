@@ -0,0 +1,60 @@
+mod common;
+
+use common::TestRepo;
+use rust_git::utils::{config, eol};
+
+/// 暂存区中 path 对应的 blob 内容
+fn staged_blob(repo: &TestRepo, path: &str) -> String {
+    let staged = repo.ok(&["ls-files", "-s"]);
+    let line = staged.lines().find(|line| line.split_whitespace().last() == Some(path)).unwrap();
+    repo.ok(&["cat-file", "-p", &line[7..47]])
+}
+
+#[test]
+fn line_endings_round_trip_verbatim_without_autocrlf() {
+    let repo = TestRepo::new();
+    repo.write("crlf.txt", "a\r\nb\r\n");
+    repo.write("lf.txt", "a\nb\n");
+    repo.ok(&["add", "crlf.txt", "lf.txt"]);
+    repo.ok(&["commit", "-q", "first"]);
+    assert_eq!(staged_blob(&repo, "crlf.txt"), "a\r\nb\r\n");
+    assert_eq!(staged_blob(&repo, "lf.txt"), "a\nb\n");
+
+    std::fs::remove_file(repo.path("crlf.txt")).unwrap();
+    std::fs::remove_file(repo.path("lf.txt")).unwrap();
+    repo.ok(&["restore", "crlf.txt", "lf.txt"]);
+    assert_eq!(repo.read("crlf.txt"), "a\r\nb\r\n");
+    assert_eq!(repo.read("lf.txt"), "a\nb\n");
+}
+
+#[test]
+fn autocrlf_stores_lf_and_checks_out_crlf() {
+    let repo = TestRepo::new();
+    repo.ok(&["config", "core.autocrlf", "true"]);
+    repo.write("a.txt", "a\r\nb\r\n");
+    repo.write("bin.dat", "\0\r\n");
+    repo.ok(&["add", "a.txt", "bin.dat"]);
+    repo.ok(&["commit", "-q", "first"]);
+    assert_eq!(staged_blob(&repo, "a.txt"), "a\nb\n");
+    // 含 NUL 的二进制文件不转换
+    assert_eq!(staged_blob(&repo, "bin.dat"), "\0\r\n");
+
+    std::fs::remove_file(repo.path("a.txt")).unwrap();
+    std::fs::remove_file(repo.path("bin.dat")).unwrap();
+    repo.ok(&["restore", "a.txt", "bin.dat"]);
+    assert_eq!(repo.read("a.txt"), "a\r\nb\r\n");
+    assert_eq!(repo.read("bin.dat"), "\0\r\n");
+    let status = repo.ok(&["status", "--porcelain"]);
+    assert!(status.is_empty(), "{}", status);
+}
+
+#[test]
+fn autocrlf_cache_follows_config_changes() {
+    let repo = TestRepo::new();
+    let _cwd = repo.enter();
+    assert!(!eol::autocrlf().unwrap());
+    config::set("core.autocrlf", "true").unwrap();
+    assert!(eol::autocrlf().unwrap());
+    config::set("core.autocrlf", "false").unwrap();
+    assert!(!eol::autocrlf().unwrap());
+}
@@ -0,0 +1,33 @@
+mod common;
+
+use common::TestRepo;
+use rust_git::error::GitError;
+use rust_git::utils::fs as utils_fs;
+
+/// 取出错误链中的 GitError
+fn git_error(err: anyhow::Error) -> GitError {
+    err.downcast::<GitError>().expect("应为 GitError")
+}
+
+#[test]
+fn errors_carry_specific_variants() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "a\n", "first");
+
+    let _cwd = repo.enter();
+    utils_fs::create_branch("feat").unwrap();
+    assert_eq!(git_error(utils_fs::create_branch("feat").unwrap_err()), GitError::BranchExists("feat".to_string()));
+    assert_eq!(git_error(utils_fs::read_branch_commit("nope").unwrap_err()), GitError::BranchNotFound("nope".to_string()));
+    assert_eq!(git_error(utils_fs::create_branch("bad..name").unwrap_err()), GitError::InvalidRefName("bad..name".to_string()));
+
+    utils_fs::create_tag("v1", None).unwrap();
+    assert_eq!(git_error(utils_fs::create_tag("v1", None).unwrap_err()), GitError::TagExists("v1".to_string()));
+    assert_eq!(git_error(utils_fs::delete_tag("v2").unwrap_err()), GitError::TagNotFound("v2".to_string()));
+}
+
+#[test]
+fn not_a_repository_error() {
+    let dir = TestRepo::empty();
+    let err = dir.fail(&["status"]);
+    assert!(err.contains(&GitError::NotARepository.to_string()), "{}", err);
+}
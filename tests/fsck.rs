@@ -0,0 +1,22 @@
+mod common;
+
+use common::TestRepo;
+
+#[test]
+fn fsck_flags_corrupted_object() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "hello\n", "first");
+    assert!(repo.ok(&["fsck"]).contains("未发现问题"));
+
+    let blob = "ce013625030ba8dba906f756967f9e9ca394464a";
+    let path = repo.path(&format!(".rust-git/objects/{}/{}", &blob[..2], &blob[2..]));
+    let mut bytes = std::fs::read(&path).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0xff;
+    std::fs::write(&path, bytes).unwrap();
+
+    let output = repo.run(&["fsck"]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(&format!("损坏的对象：{}", blob)), "{}", stdout);
+}
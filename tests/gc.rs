@@ -0,0 +1,126 @@
+mod common;
+
+use common::TestRepo;
+use rust_git::commands::{fsck, gc};
+use rust_git::utils::hash;
+
+/// 对象文件路径（相对仓库根目录）
+fn object_file(id: &str) -> String {
+    format!(".rust-git/objects/{}/{}", &id[..2], &id[2..])
+}
+
+#[test]
+fn gc_removes_orphan_blob_and_keeps_committed_ones() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "hello\n", "first");
+    let committed = "ce013625030ba8dba906f756967f9e9ca394464a";
+    repo.write("orphan.txt", "orphan\n");
+    repo.ok(&["add", "orphan.txt"]);
+    let orphan = hash::hash_object(hash::ObjectType::Blob, b"orphan\n");
+    repo.ok(&["reset", "orphan.txt"]);
+    assert!(repo.path(&object_file(&orphan)).is_file());
+
+    repo.ok(&["gc"]);
+    assert!(!repo.path(&object_file(&orphan)).exists());
+    assert!(repo.path(&object_file(committed)).is_file());
+    assert_eq!(repo.read("a.txt"), "hello\n");
+    assert!(repo.ok(&["fsck"]).contains("未发现问题"));
+}
+
+#[test]
+fn gc_stats_count_known_orphans() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "a\n", "first");
+
+    let _cwd = repo.enter();
+    let before = fsck::check_objects().unwrap();
+    assert_eq!((before.checked, before.corrupt, before.dangling), (3, 0, 0));
+
+    let mut orphans = vec![
+        hash::store_object(hash::ObjectType::Blob, b"orphan one\n").unwrap(),
+        hash::store_object(hash::ObjectType::Blob, b"orphan two\n").unwrap(),
+    ];
+    orphans.sort();
+    let orphan_bytes: u64 = orphans
+        .iter()
+        .map(|id| std::fs::metadata(hash::object_path(id).unwrap()).unwrap().len())
+        .sum();
+
+    let planned = gc::collect_garbage(true).unwrap();
+    assert_eq!(planned.scanned, 5);
+    assert_eq!(planned.reachable, 3);
+    assert_eq!(planned.removed, orphans);
+    assert_eq!(planned.bytes_reclaimed, orphan_bytes);
+    assert!(hash::object_exists(&orphans[0]));
+
+    let done = gc::collect_garbage(false).unwrap();
+    assert_eq!(done.removed, orphans);
+    assert!(!hash::object_exists(&orphans[0]));
+    assert_eq!(gc::collect_garbage(false).unwrap().removed.len(), 0);
+}
+
+#[test]
+fn gc_keeps_merge_head_during_conflicted_merge() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "1\n2\n3\n", "base");
+    repo.ok(&["branch", "feat"]);
+    repo.ok(&["checkout", "feat"]);
+    let theirs = repo.commit_file("a.txt", "1\ntheirs\n3\n", "theirs");
+    repo.ok(&["checkout", "master"]);
+    repo.commit_file("a.txt", "1\nours\n3\n", "ours");
+    repo.fail(&["merge", "feat"]);
+
+    // 删除被合并的分支后，MERGE_HEAD 是其提交唯一的引用
+    repo.ok(&["branch", "-D", "feat"]);
+    repo.ok(&["gc"]);
+    assert!(repo.path(&object_file(&theirs)).is_file());
+
+    repo.write("a.txt", "1\nboth\n3\n");
+    repo.ok(&["add", "a.txt"]);
+    repo.ok(&["commit", "-q", "merge"]);
+    assert!(repo.ok(&["fsck"]).contains("未发现问题"));
+}
+
+#[test]
+fn prune_removes_only_empty_object_dirs() {
+    let repo = TestRepo::new();
+    let commit = repo.commit_file("a.txt", "a\n", "first");
+    let empty_dir = repo.path(".rust-git/objects/zz");
+    // 提交哈希随时间变化，取一个未被现有对象占用的子目录名
+    let unused = (0..=255u8)
+        .map(|byte| format!(".rust-git/objects/{:02x}", byte))
+        .find(|dir| !repo.path(dir).exists())
+        .unwrap();
+    std::fs::create_dir(repo.path(&unused)).unwrap();
+    std::fs::create_dir(&empty_dir).unwrap();
+    repo.write(".rust-git/objects/notes.txt", "keep\n");
+
+    let _cwd = repo.enter();
+    assert_eq!(gc::prune_empty_object_dirs().unwrap(), 1);
+    assert!(!repo.path(&unused).exists());
+    // 非两位十六进制名称的目录和其他文件保持不变
+    assert!(empty_dir.is_dir());
+    assert!(repo.path(".rust-git/objects/notes.txt").is_file());
+    assert!(repo.path(".rust-git/objects").is_dir());
+    assert!(repo.path(&object_file(&commit)).is_file());
+    assert_eq!(gc::prune_empty_object_dirs().unwrap(), 0);
+}
+
+#[test]
+fn gc_prunes_dir_emptied_by_removed_object() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "a\n", "first");
+    repo.write("orphan.txt", "orphan\n");
+    repo.ok(&["add", "orphan.txt"]);
+    repo.ok(&["reset", "orphan.txt"]);
+    let _cwd = repo.enter();
+    let orphan = hash::hash_object(hash::ObjectType::Blob, b"orphan\n");
+    let orphan_dir = repo.path(&format!(".rust-git/objects/{}", &orphan[..2]));
+    // 孤立对象独占其子目录时，gc 删除对象后一并删除该目录
+    let shared = std::fs::read_dir(&orphan_dir).unwrap().count() > 1;
+
+    let stats = gc::collect_garbage(false).unwrap();
+    assert!(stats.removed.contains(&orphan));
+    assert_eq!(orphan_dir.exists(), shared);
+    assert_eq!(stats.pruned_dirs, usize::from(!shared));
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use common::TestRepo;
+
+#[cfg(unix)]
+#[test]
+fn failing_pre_commit_hook_aborts_commit() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let repo = TestRepo::new();
+    repo.write(".rust-git/hooks/pre-commit", "#!/bin/sh\necho rejected >&2\nexit 3\n");
+    let hook = repo.path(".rust-git/hooks/pre-commit");
+    std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+    repo.write("a.txt", "a\n");
+    repo.ok(&["add", "a.txt"]);
+
+    let err = repo.fail(&["commit", "blocked"]);
+    assert!(err.contains("pre-commit 钩子未通过"), "{}", err);
+    assert!(err.contains("退出码 3"), "{}", err);
+    assert_eq!(repo.read(".rust-git/refs/heads/master"), "");
+    assert_eq!(repo.ok(&["status", "--porcelain"]), "A  a.txt\n");
+
+    std::fs::write(&hook, "#!/bin/sh\nexit 0\n").unwrap();
+    repo.ok(&["commit", "allowed"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn rejected_commit_leaves_index_unchanged() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let repo = TestRepo::new();
+    repo.write("a.txt", "a\n");
+    repo.write("b.txt", "b\n");
+    repo.ok(&["add", "a.txt", "b.txt"]);
+    repo.ok(&["commit", "first"]);
+    repo.write(".rust-git/hooks/commit-msg", "#!/bin/sh\nexit 1\n");
+    let hook = repo.path(".rust-git/hooks/commit-msg");
+    std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    std::fs::remove_file(repo.path("b.txt")).unwrap();
+    let err = repo.fail(&["commit", "drop b"]);
+    assert!(err.contains("commit-msg 钩子未通过"), "{}", err);
+    // 被拒绝的提交不修改暂存区
+    assert_eq!(repo.ok(&["ls-files"]), "a.txt\nb.txt\n");
+    repo.write("b.txt", "b\n");
+    let status = repo.ok(&["status", "--porcelain"]);
+    assert!(status.is_empty(), "{}", status);
+}
@@ -0,0 +1,147 @@
+mod common;
+
+use common::TestRepo;
+use rust_git::utils::fs as utils_fs;
+use rust_git::utils::hash;
+use rust_git::utils::metadata::{self, IndexEntry};
+
+#[test]
+fn malformed_index_entry_is_reported() {
+    let repo = TestRepo::new();
+    repo.write(
+        ".rust-git/index",
+        r#"[{"path":"a.txt","hash":"ce013625030ba8dba906f756967f9e9ca394464a"},{"path":"b.txt"}]"#,
+    );
+
+    let _cwd = repo.enter();
+    let err = format!("{:#}", utils_fs::read_index_typed().unwrap_err());
+    assert!(err.contains("第 2 个条目"), "{}", err);
+    assert!(err.contains("b.txt"), "{}", err);
+}
+
+#[cfg(unix)]
+#[test]
+fn executable_mode_survives_round_trip() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let repo = TestRepo::new();
+    repo.write("run.sh", "#!/bin/sh\necho hi\n");
+    std::fs::set_permissions(repo.path("run.sh"), std::fs::Permissions::from_mode(0o755)).unwrap();
+    repo.ok(&["add", "run.sh"]);
+    {
+        let _cwd = repo.enter();
+        assert_eq!(utils_fs::read_index_typed().unwrap()[0].mode, 0o100755);
+    }
+    repo.ok(&["commit", "script"]);
+
+    std::fs::remove_file(repo.path("run.sh")).unwrap();
+    repo.ok(&["checkout", "--", "run.sh"]);
+    let mode = std::fs::metadata(repo.path("run.sh")).unwrap().permissions().mode();
+    assert_eq!(mode & 0o111, 0o111);
+}
+
+#[test]
+fn unchanged_stat_skips_rehashing() {
+    let repo = TestRepo::new();
+    repo.write("a.txt", "a\n");
+
+    let _cwd = repo.enter();
+    let path = repo.path("a.txt");
+    // 哈希故意与内容不符：只有不读取文件内容时才会判定为一致
+    let mut entry = IndexEntry {
+        path: "a.txt".to_string(),
+        hash: "0000000000000000000000000000000000000000".to_string(),
+        mode: metadata::MODE_REGULAR,
+        size: 0,
+        mtime: 0,
+    };
+    entry.refresh_stat(&path).unwrap();
+    assert!(entry.matches_file(&path).unwrap());
+
+    entry.clear_stat();
+    assert!(!entry.matches_file(&path).unwrap());
+
+    entry.refresh_stat(&path).unwrap();
+    repo.write("a.txt", "changed\n");
+    assert!(!entry.matches_file(&path).unwrap());
+}
+
+#[test]
+fn ignorecase_merges_paths_differing_only_in_case() {
+    let repo = TestRepo::new();
+    repo.write("README", "upper\n");
+    repo.write("readme", "lower\n");
+
+    repo.ok(&["add", "README"]);
+    repo.ok(&["add", "readme"]);
+    assert_eq!(repo.ok(&["ls-files"]), "README\nreadme\n");
+
+    repo.ok(&["reset"]);
+    repo.ok(&["config", "core.ignorecase", "true"]);
+    repo.ok(&["add", "README"]);
+    repo.ok(&["add", "readme"]);
+    let staged = repo.ok(&["ls-files", "-s"]);
+    assert_eq!(staged.lines().count(), 1, "{}", staged);
+    // 保留首次暂存时的路径写法，内容更新为后一次暂存的文件
+    assert!(staged.ends_with("\tREADME\n"), "{}", staged);
+    let lower = hash::hash_object(hash::ObjectType::Blob, b"lower\n");
+    assert!(staged.contains(&lower), "{}", staged);
+}
+
+#[test]
+fn stale_index_lock_is_reported() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "a\n", "first");
+    repo.write("b.txt", "b\n");
+    repo.write(".rust-git/index.lock", "12345\n");
+    let index_before = repo.read(".rust-git/index");
+
+    let commands: [&[&str]; 7] = [
+        &["add", "b.txt"],
+        &["rm", "--cached", "a.txt"],
+        &["reset"],
+        &["mv", "a.txt", "c.txt"],
+        &["restore", "--staged", "a.txt"],
+        &["commit", "--allow-empty", "blocked"],
+        &["merge", "master"],
+    ];
+    for args in commands {
+        let err = repo.fail(args);
+        assert!(err.contains("index is locked"), "{:?}: {}", args, err);
+        assert!(err.contains("index.lock"), "{:?}: {}", args, err);
+    }
+    assert_eq!(repo.read(".rust-git/index"), index_before);
+    assert!(repo.path(".rust-git/index.lock").exists());
+
+    std::fs::remove_file(repo.path(".rust-git/index.lock")).unwrap();
+    repo.ok(&["add", "b.txt"]);
+    assert!(!repo.path(".rust-git/index.lock").exists());
+}
+
+#[test]
+fn failed_atomic_write_keeps_original_index() {
+    use std::io::Write;
+
+    let repo = TestRepo::new();
+    repo.write("a.txt", "a\n");
+    repo.ok(&["add", "a.txt"]);
+    let index_before = repo.read(".rust-git/index");
+
+    let _cwd = repo.enter();
+    let index_path = repo.path(".rust-git/index");
+    let err = utils_fs::write_atomic_with(&index_path, |file| {
+        file.write_all(b"[{\"path\": \"trunc")?;
+        Err(std::io::Error::other("injected failure"))
+    })
+    .unwrap_err();
+    assert!(format!("{:#}", err).contains("injected failure"), "{:#}", err);
+    assert_eq!(repo.read(".rust-git/index"), index_before);
+
+    // 临时文件已清理
+    let leftovers: Vec<String> = std::fs::read_dir(repo.path(".rust-git"))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.ends_with(utils_fs::TEMP_FILE_SUFFIX))
+        .collect();
+    assert!(leftovers.is_empty(), "{:?}", leftovers);
+}
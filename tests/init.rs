@@ -0,0 +1,67 @@
+mod common;
+
+use common::TestRepo;
+
+#[test]
+fn init_creates_repository_layout() {
+    let repo = TestRepo::empty();
+    repo.ok(&["init"]);
+    for sub in ["objects", "refs/heads"] {
+        assert!(repo.path(".rust-git").join(sub).is_dir(), "缺少目录 {}", sub);
+    }
+    assert_eq!(repo.read(".rust-git/index"), "[]");
+
+    // 目录已存在时再次初始化同样成功，已有对象保持不变
+    let head = repo.commit_file("a.txt", "a\n", "first");
+    repo.ok(&["init"]);
+    assert!(repo.path(&format!(".rust-git/objects/{}/{}", &head[..2], &head[2..])).is_file());
+}
+
+#[test]
+fn init_into_target_directory() {
+    let dir = TestRepo::empty();
+    dir.ok(&["init", "nested/project"]);
+
+    let git_dir = dir.path("nested/project/.rust-git");
+    for sub in ["objects", "refs/heads", "refs/tags", "logs"] {
+        assert!(git_dir.join(sub).is_dir(), "缺少目录 {}", sub);
+    }
+    assert_eq!(std::fs::read_to_string(git_dir.join("HEAD")).unwrap(), "ref: refs/heads/master");
+    assert_eq!(std::fs::read_to_string(git_dir.join("index")).unwrap(), "[]");
+    assert!(!dir.path(".rust-git").exists());
+}
+
+#[test]
+fn bare_repository_rejects_work_tree_commands() {
+    let dir = TestRepo::empty();
+    dir.ok(&["init", "--bare"]);
+    assert!(dir.path("objects").is_dir());
+    assert!(dir.path("HEAD").is_file());
+    assert!(!dir.path(".rust-git").exists());
+
+    dir.write("a.txt", "a\n");
+    let err = dir.fail(&["add", "a.txt"]);
+    assert!(err.contains("裸仓库"), "{}", err);
+    let err = dir.fail(&["status"]);
+    assert!(err.contains("裸仓库"), "{}", err);
+}
+
+#[test]
+fn init_with_initial_branch_main() {
+    let repo = TestRepo::empty();
+    repo.ok(&["init", "--initial-branch", "main"]);
+    assert_eq!(repo.read(".rust-git/HEAD"), "ref: refs/heads/main");
+
+    repo.commit_file("a.txt", "a\n", "first");
+    assert!(repo.path(".rust-git/refs/heads/main").is_file());
+    assert!(!repo.path(".rust-git/refs/heads/master").exists());
+
+    // 默认分支名不再特殊：master 分支与其他分支一样可以删除
+    repo.ok(&["branch", "master"]);
+    repo.ok(&["branch", "-d", "master"]);
+    let err = repo.fail(&["branch", "-d", "main"]);
+    assert!(err.contains("无法删除当前分支"), "{}", err);
+
+    let err = TestRepo::empty().fail(&["init", "--initial-branch", "../bad"]);
+    assert!(err.contains("引用名不合法"), "{}", err);
+}
@@ -0,0 +1,141 @@
+mod common;
+
+use common::TestRepo;
+
+/// 在同一文件上依次创建 count 个提交（提交信息为 c1、c2……）
+fn linear_history(repo: &TestRepo, count: usize) {
+    for i in 1..=count {
+        repo.commit_file("a.txt", &format!("{}\n", i), &format!("c{}", i));
+    }
+}
+
+#[test]
+fn log_oneline_prints_one_line_per_commit() {
+    let repo = TestRepo::new();
+    linear_history(&repo, 3);
+
+    let out = repo.ok(&["log", "--oneline"]);
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines.len(), 3, "{}", out);
+    assert!(lines[0].ends_with(" c3"), "{}", out);
+    assert!(lines[2].ends_with(" c1"), "{}", out);
+}
+
+#[test]
+fn log_max_count_limits_output() {
+    let repo = TestRepo::new();
+    linear_history(&repo, 5);
+
+    let out = repo.ok(&["log", "--oneline", "-n", "2"]);
+    let subjects: Vec<&str> = out.lines().map(|line| &line[8..]).collect();
+    assert_eq!(subjects, ["c5", "c4"]);
+}
+
+#[test]
+fn log_follows_current_branch_only() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "base\n", "base");
+    repo.ok(&["branch", "feat"]);
+    repo.commit_file("a.txt", "master\n", "on master");
+    repo.ok(&["checkout", "feat"]);
+    repo.commit_file("b.txt", "feat\n", "on feat");
+
+    let feat = repo.ok(&["log", "--oneline"]);
+    assert!(feat.contains("on feat") && feat.contains("base"), "{}", feat);
+    assert!(!feat.contains("on master"), "{}", feat);
+
+    repo.ok(&["checkout", "master"]);
+    let master = repo.ok(&["log", "--oneline"]);
+    assert!(master.contains("on master") && master.contains("base"), "{}", master);
+    assert!(!master.contains("on feat"), "{}", master);
+}
+
+#[test]
+fn log_graph_draws_merge_edges() {
+    let repo = TestRepo::new();
+    let base = repo.commit_file("a.txt", "base\n", "base");
+    repo.ok(&["branch", "feat"]);
+    repo.write("b.txt", "master\n");
+    repo.ok(&["add", "b.txt"]);
+    repo.ok(&["commit", "-q", "--date", "1000000000", "on master"]);
+    repo.ok(&["checkout", "feat"]);
+    repo.write("c.txt", "feat\n");
+    repo.ok(&["add", "c.txt"]);
+    repo.ok(&["commit", "-q", "--date", "1000000100", "on feat"]);
+    repo.ok(&["checkout", "master"]);
+    // checkout 不会删除目标分支中不存在的文件
+    std::fs::remove_file(repo.path("c.txt")).unwrap();
+    repo.ok(&["merge", "feat"]);
+
+    let out = repo.ok(&["log", "--graph", "--oneline"]);
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines.len(), 6, "{}", out);
+    assert!(lines[0].starts_with("* ") && lines[0].ends_with("Merge branch 'feat'"), "{}", out);
+    assert_eq!(lines[1], "|\\");
+    assert!(lines[2].starts_with("| * ") && lines[2].ends_with("on feat"), "{}", out);
+    assert!(lines[3].starts_with("* | ") && lines[3].ends_with("on master"), "{}", out);
+    assert_eq!(lines[4], "|/");
+    assert_eq!(lines[5], format!("* {} base", &base[..7]));
+}
+
+#[test]
+fn log_path_filters_history() {
+    let repo = TestRepo::new();
+    repo.write("a.txt", "a1\n");
+    repo.write("b.txt", "b1\n");
+    repo.ok(&["add", "a.txt", "b.txt"]);
+    repo.ok(&["commit", "both"]);
+    repo.commit_file("a.txt", "a2\n", "only a");
+
+    let subjects = |path: &str| -> Vec<String> {
+        repo.ok(&["log", "--oneline", path]).lines().map(|line| line[8..].to_string()).collect()
+    };
+    assert_eq!(subjects("a.txt"), ["only a", "both"]);
+    assert_eq!(subjects("b.txt"), ["both"]);
+}
+
+#[test]
+fn log_name_only_lists_files_per_commit() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "a\n", "one");
+    repo.write("b.txt", "b\n");
+    repo.write("c.txt", "c\n");
+    repo.ok(&["add", "b.txt", "c.txt"]);
+    repo.ok(&["commit", "-q", "two"]);
+
+    let out = repo.ok(&["log", "--name-only"]);
+    let files: Vec<Vec<&str>> = out
+        .split("commit ")
+        .filter(|block| !block.is_empty())
+        .map(|block| {
+            block
+                .lines()
+                .skip_while(|line| !line.is_empty())
+                .filter(|line| !line.is_empty() && !line.starts_with("    "))
+                .collect()
+        })
+        .collect();
+    assert_eq!(files, vec![vec!["b.txt", "c.txt"], vec!["a.txt"]], "{}", out);
+}
+
+#[test]
+fn log_since_until_filters_by_commit_time() {
+    let repo = TestRepo::new();
+    for (i, date) in ["2024-01-01T12:00:00Z", "2024-02-01T12:00:00Z", "2024-03-01T12:00:00Z"].iter().enumerate() {
+        repo.write("a.txt", &format!("{}\n", i));
+        repo.ok(&["add", "a.txt"]);
+        repo.ok(&["commit", "-q", "--date", date, &format!("c{}", i + 1)]);
+    }
+    let subjects = |args: &[&str]| -> Vec<String> {
+        let mut full = vec!["log", "--oneline"];
+        full.extend_from_slice(args);
+        repo.ok(&full).lines().map(|line| line[8..].to_string()).collect()
+    };
+
+    assert_eq!(subjects(&["--since", "2024-01-15", "--until", "2024-02-15"]), ["c2"]);
+    assert_eq!(subjects(&["--since", "2024-02-01T12:00:00Z"]), ["c3", "c2"]);
+    // 两端均包含；YYYY-MM-DD 作为上界时包含当天
+    assert_eq!(subjects(&["--until", "2024-02-01"]), ["c2", "c1"]);
+    assert_eq!(subjects(&["--until", "1704110400"]), ["c1"]);
+    assert!(subjects(&["--since", "2025-01-01"]).is_empty());
+}
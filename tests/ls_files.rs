@@ -0,0 +1,16 @@
+mod common;
+
+use common::TestRepo;
+
+#[test]
+fn ls_files_lists_index_sorted() {
+    let repo = TestRepo::new();
+    repo.write("zeta.txt", "z\n");
+    repo.write("alpha.txt", "a\n");
+    repo.write("dir/mid.txt", "m\n");
+    repo.ok(&["add", "zeta.txt", "dir/mid.txt", "alpha.txt"]);
+
+    assert_eq!(repo.ok(&["ls-files"]), "alpha.txt\ndir/mid.txt\nzeta.txt\n");
+    let staged = repo.ok(&["ls-files", "-s"]);
+    assert!(staged.lines().all(|line| line.starts_with("100644 ")), "{}", staged);
+}
@@ -0,0 +1,145 @@
+mod common;
+
+use common::TestRepo;
+use rust_git::utils::hash;
+
+#[test]
+fn fast_forward_merge() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "a\n", "first");
+    repo.ok(&["branch", "feat"]);
+    repo.ok(&["checkout", "feat"]);
+    let feature = repo.commit_file("b.txt", "b\n", "feature");
+    repo.ok(&["checkout", "master"]);
+
+    let out = repo.ok(&["merge", "feat"]);
+    assert!(out.contains("快进合并"), "{}", out);
+    assert_eq!(repo.rev("master"), feature);
+    assert_eq!(repo.read("b.txt"), "b\n");
+}
+
+#[test]
+fn fast_forward_refuses_to_lose_local_changes() {
+    let repo = TestRepo::new();
+    let base = repo.commit_file("a.txt", "a\n", "first");
+    repo.ok(&["branch", "feat"]);
+    repo.ok(&["checkout", "feat"]);
+    repo.commit_file("a.txt", "feature\n", "feature");
+    repo.write("b.txt", "feature b\n");
+    repo.ok(&["add", "b.txt"]);
+    repo.ok(&["commit", "add b"]);
+    repo.ok(&["checkout", "master"]);
+    std::fs::remove_file(repo.path("b.txt")).unwrap();
+
+    // 已跟踪文件的本地修改
+    repo.write("a.txt", "local edit\n");
+    let err = repo.fail(&["merge", "feat"]);
+    assert!(err.contains("未提交的修改"), "{}", err);
+    assert_eq!(repo.read("a.txt"), "local edit\n");
+    assert_eq!(repo.rev("master"), base);
+
+    // 会被目标分支覆盖的未跟踪文件
+    repo.write("a.txt", "a\n");
+    repo.write("b.txt", "untracked b\n");
+    let err = repo.fail(&["merge", "feat"]);
+    assert!(err.contains("未跟踪的文件将被合并覆盖"), "{}", err);
+    assert!(err.contains("b.txt"), "{}", err);
+    assert_eq!(repo.read("b.txt"), "untracked b\n");
+    assert_eq!(repo.rev("master"), base);
+}
+
+/// 从共同的 a.txt 分叉：master 与 feat 分别用给定内容各提交一次，最后停在 master
+fn diverge(repo: &TestRepo, base: &str, ours: &str, theirs: &str) -> (String, String) {
+    repo.commit_file("a.txt", base, "base");
+    repo.ok(&["branch", "feat"]);
+    repo.ok(&["checkout", "feat"]);
+    let theirs = repo.commit_file("a.txt", theirs, "theirs");
+    repo.ok(&["checkout", "master"]);
+    let ours = repo.commit_file("a.txt", ours, "ours");
+    (ours, theirs)
+}
+
+#[test]
+fn three_way_merge_combines_separate_changes() {
+    let repo = TestRepo::new();
+    let (ours, _) = diverge(&repo, "1\n2\n3\n4\n5\n", "one\n2\n3\n4\n5\n", "1\n2\n3\n4\nfive\n");
+
+    let out = repo.ok(&["merge", "feat"]);
+    assert!(out.contains("Merge branch 'feat'"), "{}", out);
+    assert_eq!(repo.read("a.txt"), "one\n2\n3\n4\nfive\n");
+    assert_eq!(repo.rev("HEAD~1"), ours);
+    assert!(!repo.path(".rust-git/MERGE_HEAD").exists());
+    let status = repo.ok(&["status", "--porcelain"]);
+    assert!(status.is_empty(), "{}", status);
+}
+
+#[test]
+fn three_way_merge_reports_conflict() {
+    let repo = TestRepo::new();
+    let (ours, _) = diverge(&repo, "1\n2\n3\n", "1\nours\n3\n", "1\ntheirs\n3\n");
+
+    let err = repo.fail(&["merge", "feat"]);
+    assert!(err.contains("存在冲突"), "{}", err);
+    assert!(err.contains("a.txt"), "{}", err);
+    let content = repo.read("a.txt");
+    assert!(content.contains("<<<<<<<"), "{}", content);
+    assert!(content.contains("ours\n=======\ntheirs\n"), "{}", content);
+    assert!(content.contains(">>>>>>>"), "{}", content);
+    // 冲突时不创建合并提交
+    assert_eq!(repo.rev("HEAD"), ours);
+    assert!(repo.path(".rust-git/MERGE_HEAD").is_file());
+}
+
+#[test]
+fn merge_commit_parents_parse_back() {
+    let repo = TestRepo::new();
+    let (ours, theirs) = diverge(&repo, "1\n2\n3\n4\n5\n", "one\n2\n3\n4\n5\n", "1\n2\n3\n4\nfive\n");
+    repo.ok(&["merge", "feat"]);
+    let merge = repo.rev("HEAD");
+
+    let raw = repo.ok(&["cat-file", "-p", &merge]);
+    let parent_lines: Vec<&str> = raw.lines().filter(|line| line.starts_with("parent ")).collect();
+    assert_eq!(parent_lines, [format!("parent {}", ours), format!("parent {}", theirs)]);
+
+    let _cwd = repo.enter();
+    let commit = hash::read_commit(&merge).unwrap();
+    assert_eq!(commit.parents, [ours.clone(), theirs]);
+    assert_eq!(commit.first_parent(), Some(ours.as_str()));
+}
+
+#[test]
+fn merge_abort_restores_pre_merge_state() {
+    let repo = TestRepo::new();
+    let (ours, _) = diverge(&repo, "1\n2\n3\n", "1\nours\n3\n", "1\ntheirs\n3\n");
+    repo.fail(&["merge", "feat"]);
+
+    let out = repo.ok(&["merge", "--abort"]);
+    assert!(out.contains("已中止合并"), "{}", out);
+    assert_eq!(repo.read("a.txt"), "1\nours\n3\n");
+    assert_eq!(repo.rev("HEAD"), ours);
+    assert!(!repo.path(".rust-git/MERGE_HEAD").exists());
+    let status = repo.ok(&["status", "--porcelain"]);
+    assert!(status.is_empty(), "{}", status);
+
+    let err = repo.fail(&["merge", "--abort"]);
+    assert!(err.contains("没有正在进行的合并"), "{}", err);
+}
+
+#[test]
+fn commit_is_blocked_until_conflicts_are_resolved() {
+    let repo = TestRepo::new();
+    let (ours, theirs) = diverge(&repo, "1\n2\n3\n", "1\nours\n3\n", "1\ntheirs\n3\n");
+    repo.fail(&["merge", "feat"]);
+
+    // 冲突标记仍在时即使已暂存也不能提交
+    repo.ok(&["add", "a.txt"]);
+    let err = repo.fail(&["commit", "-q", "merge"]);
+    assert!(err.contains("a.txt"), "{}", err);
+    assert_eq!(repo.rev("HEAD"), ours);
+
+    repo.write("a.txt", "1\nboth\n3\n");
+    repo.ok(&["add", "a.txt"]);
+    repo.ok(&["commit", "-q", "merge"]);
+    let _cwd = repo.enter();
+    assert_eq!(hash::read_commit(&repo.rev("HEAD")).unwrap().parents, [ours, theirs]);
+}
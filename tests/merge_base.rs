@@ -0,0 +1,19 @@
+mod common;
+
+use common::TestRepo;
+
+#[test]
+fn merge_base_of_diverged_branches() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "a\n", "first");
+    let shared = repo.commit_file("a.txt", "b\n", "shared");
+    repo.ok(&["branch", "feat"]);
+    repo.commit_file("m.txt", "m\n", "on master");
+    repo.ok(&["checkout", "feat"]);
+    repo.commit_file("f.txt", "f\n", "on feat");
+
+    assert_eq!(repo.ok(&["merge-base", "master", "feat"]).trim(), shared);
+    assert_eq!(repo.ok(&["merge-base", "feat", "master"]).trim(), shared);
+    // 祖先与后代的公共祖先是祖先本身
+    assert_eq!(repo.ok(&["merge-base", "feat", &shared]).trim(), shared);
+}
@@ -0,0 +1,25 @@
+mod common;
+
+use common::TestRepo;
+
+#[test]
+fn mv_renames_tracked_file() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "a\n", "first");
+
+    repo.ok(&["mv", "a.txt", "b.txt"]);
+    assert!(!repo.path("a.txt").exists());
+    assert_eq!(repo.read("b.txt"), "a\n");
+    assert_eq!(repo.staged_paths(), ["b.txt"]);
+}
+
+#[test]
+fn mv_rejects_unstaged_source() {
+    let repo = TestRepo::new();
+    repo.write("loose.txt", "loose\n");
+
+    let err = repo.fail(&["mv", "loose.txt", "moved.txt"]);
+    assert!(err.contains("文件未在暂存区中：loose.txt"), "{}", err);
+    assert!(repo.path("loose.txt").exists());
+    assert!(!repo.path("moved.txt").exists());
+}
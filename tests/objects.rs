@@ -0,0 +1,141 @@
+mod common;
+
+use common::TestRepo;
+use rust_git::error::GitError;
+use rust_git::utils::hash;
+
+#[test]
+fn hash_file_matches_git_hash_object() {
+    let repo = TestRepo::new();
+    repo.write("hello.txt", "hello\n");
+
+    // `echo hello | git hash-object --stdin`
+    let _cwd = repo.enter();
+    let id = hash::hash_file(&repo.path("hello.txt")).unwrap();
+    assert_eq!(id, "ce013625030ba8dba906f756967f9e9ca394464a");
+}
+
+#[test]
+fn stored_object_round_trips_compressed() {
+    let repo = TestRepo::new();
+    let content: Vec<u8> = (0..1024 * 1024).map(|i| (i * 31 % 251) as u8).collect();
+
+    let _cwd = repo.enter();
+    let id = hash::store_object(hash::ObjectType::Blob, &content).unwrap();
+    assert_eq!(hash::read_object(&id).unwrap(), content);
+
+    // 磁盘上的对象文件为 zlib 压缩格式
+    let raw = std::fs::read(hash::object_path(&id).unwrap()).unwrap();
+    assert_eq!(raw[0], 0x78);
+    assert!(raw.len() < content.len());
+}
+
+#[test]
+fn cat_file_blob_and_commit() {
+    let repo = TestRepo::new();
+    let commit = repo.commit_file("a.txt", "hello\n", "first");
+    let blob = "ce013625030ba8dba906f756967f9e9ca394464a";
+
+    assert_eq!(repo.ok(&["cat-file", "-t", blob]), "blob\n");
+    assert_eq!(repo.ok(&["cat-file", "-p", blob]), "hello\n");
+
+    assert_eq!(repo.ok(&["cat-file", "-t", &commit]), "commit\n");
+    let pretty = repo.ok(&["cat-file", "-p", &commit]);
+    assert!(pretty.starts_with("tree "), "{}", pretty);
+    assert!(pretty.trim_end().ends_with("first"), "{}", pretty);
+}
+
+#[test]
+fn abbreviated_hash_lookup() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "hello\n", "first");
+    let blob = "ce013625030ba8dba906f756967f9e9ca394464a";
+    // 手工放置两个共享前缀 abcd 的对象文件
+    repo.write(".rust-git/objects/ab/cd000000000000000000000000000000000000", "");
+    repo.write(".rust-git/objects/ab/cd111111111111111111111111111111111111", "");
+
+    let _cwd = repo.enter();
+    assert_eq!(hash::resolve_hash("ce0136").unwrap(), blob);
+
+    let err = hash::resolve_hash("abcd").unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<GitError>(),
+        Some(&GitError::AmbiguousPrefix { prefix: "abcd".to_string(), count: 2 })
+    );
+
+    let err = hash::resolve_hash("ce0").unwrap_err();
+    assert!(err.to_string().contains("过短"), "{}", err);
+}
+
+/// 读取目录树对象中名为 name 的子目录树哈希
+fn subtree(tree_hash: &str, name: &str) -> String {
+    let tree: serde_json::Value = serde_json::from_slice(&hash::read_object(tree_hash).unwrap()).unwrap();
+    let entry = tree.as_array().unwrap().iter().find(|entry| entry["name"] == name).unwrap();
+    assert_eq!(entry["type"], "tree");
+    entry["hash"].as_str().unwrap().to_string()
+}
+
+#[test]
+fn nested_directories_get_their_own_trees() {
+    let repo = TestRepo::new();
+    repo.write("top.txt", "top\n");
+    repo.write("src/main.rs", "fn main() {}\n");
+    repo.write("src/utils/fs.rs", "// fs\n");
+    repo.write("docs/readme.md", "docs\n");
+    repo.ok(&["add", "."]);
+    let commit = repo.commit_file("top.txt", "top\n", "nested");
+
+    let _cwd = repo.enter();
+    let root = hash::read_commit(&commit).unwrap().tree_hash;
+    let src = subtree(&root, "src");
+    let utils = subtree(&src, "utils");
+    let docs = subtree(&root, "docs");
+    assert_eq!(hash::read_object_with_type(&utils).unwrap().0, Some(hash::ObjectType::Tree));
+    let ids = std::collections::HashSet::from([&root, &src, &utils, &docs]);
+    assert_eq!(ids.len(), 4);
+
+    let paths: Vec<String> = hash::read_tree_entries(&root).unwrap().into_iter().map(|entry| entry.path).collect();
+    assert_eq!(paths, ["docs/readme.md", "src/main.rs", "src/utils/fs.rs", "top.txt"]);
+}
+
+#[test]
+fn streaming_hash_matches_one_shot_hash() {
+    let repo = TestRepo::new();
+    // 不是分块大小的整数倍，覆盖最后一个不完整的块
+    let content: Vec<u8> = (0..8 * 1024 * 1024 + 123).map(|i| (i % 253) as u8).collect();
+    std::fs::write(repo.path("large.bin"), &content).unwrap();
+
+    let _cwd = repo.enter();
+    let one_shot = hash::hash_object(hash::ObjectType::Blob, &content);
+    assert_eq!(hash::hash_file(&repo.path("large.bin")).unwrap(), one_shot);
+    assert_eq!(hash::store_file(&repo.path("large.bin")).unwrap(), one_shot);
+    assert_eq!(hash::read_object(&one_shot).unwrap(), content);
+}
+
+#[test]
+fn concurrent_store_file_uses_separate_temp_files() {
+    let repo = TestRepo::new();
+    let paths: Vec<_> = (0..8)
+        .map(|i| {
+            let rel = format!("f{}.txt", i);
+            repo.write(&rel, &format!("{}\n", i).repeat(100_000));
+            repo.path(&rel)
+        })
+        .collect();
+
+    let _cwd = repo.enter();
+    let hashes: Vec<String> = std::thread::scope(|scope| {
+        let handles: Vec<_> = paths.iter().map(|path| scope.spawn(|| hash::store_file(path).unwrap())).collect();
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+    for (i, id) in hashes.iter().enumerate() {
+        assert_eq!(hash::read_object(id).unwrap(), format!("{}\n", i).repeat(100_000).into_bytes());
+    }
+    // 临时文件全部移动到位，对象目录下没有残留
+    let leftovers: Vec<_> = std::fs::read_dir(repo.path(".rust-git/objects"))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.len() != 2)
+        .collect();
+    assert!(leftovers.is_empty(), "{:?}", leftovers);
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use common::TestRepo;
+use rust_git::utils::fs::normalize_path;
+
+#[test]
+fn normalize_messy_paths() {
+    let cases = [
+        ("./src//main.rs", "src/main.rs"),
+        ("././a.txt", "a.txt"),
+        ("src/./utils/./fs.rs", "src/utils/fs.rs"),
+        ("src\\utils\\fs.rs", "src/utils/fs.rs"),
+        ("dir///", "dir"),
+        ("/abs//path/", "/abs/path"),
+        ("plain.txt", "plain.txt"),
+        ("../up/./x", "../up/x"),
+    ];
+    for (messy, expected) in cases {
+        assert_eq!(normalize_path(messy), expected, "{}", messy);
+    }
+}
+
+#[test]
+fn add_then_rm_same_logical_path() {
+    let repo = TestRepo::new();
+    repo.write("src/main.rs", "fn main() {}\n");
+
+    repo.ok(&["add", "./src//main.rs"]);
+    assert_eq!(repo.ok(&["ls-files"]), "src/main.rs\n");
+    repo.ok(&["rm", "--cached", "src/./main.rs"]);
+    assert_eq!(repo.ok(&["ls-files"]), "");
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use common::TestRepo;
+use rust_git::utils::hash;
+
+#[test]
+fn rebase_replays_feature_commits_onto_advanced_master() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "1\n2\n3\n4\n5\n", "base");
+    repo.ok(&["branch", "feat"]);
+    repo.ok(&["checkout", "feat"]);
+    repo.write("f1.txt", "f1\n");
+    repo.ok(&["add", "f1.txt"]);
+    repo.ok(&["commit", "-q", "--date", "1700000000", "feat one"]);
+    repo.write("a.txt", "1\n2\n3\n4\nfive\n");
+    repo.ok(&["add", "a.txt"]);
+    repo.ok(&["commit", "-q", "--date", "1700000100", "feat two"]);
+    repo.ok(&["checkout", "master"]);
+    let master = repo.commit_file("a.txt", "one\n2\n3\n4\n5\n", "master");
+    repo.ok(&["checkout", "feat"]);
+
+    let out = repo.ok(&["rebase", "master"]);
+    assert!(out.contains("2 个提交"), "{}", out);
+    // 双方修改同一文件的不同区域时按行合并
+    assert_eq!(repo.read("a.txt"), "one\n2\n3\n4\nfive\n");
+    assert_eq!(repo.read("f1.txt"), "f1\n");
+    let status = repo.ok(&["status", "--porcelain"]);
+    assert!(status.is_empty(), "{}", status);
+
+    let log = repo.ok(&["log", "--oneline"]);
+    let subjects: Vec<&str> = log.lines().map(|line| &line[8..]).collect();
+    assert_eq!(subjects, ["feat two", "feat one", "master", "base"]);
+
+    let _cwd = repo.enter();
+    let two = hash::read_commit(&repo.rev("feat")).unwrap();
+    let one = hash::read_commit(two.first_parent().unwrap()).unwrap();
+    assert_eq!(one.first_parent(), Some(master.as_str()));
+    // 重放的提交保留原作者时间
+    assert_eq!((one.timestamp, two.timestamp), (1700000000, 1700000100));
+    // 提交时间为变基时的当前时间
+    assert!(two.committer_timestamp > two.timestamp);
+    drop(_cwd);
+    // log --since / --until 按提交时间筛选
+    let old = repo.ok(&["log", "--oneline", "--until", "1700000100"]);
+    assert!(old.is_empty(), "{}", old);
+}
+
+#[test]
+fn rebase_refuses_to_overwrite_untracked_files() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "a\n", "base");
+    repo.ok(&["branch", "feat"]);
+    repo.commit_file("new.txt", "from master\n", "master");
+    repo.ok(&["checkout", "feat"]);
+    let feature = repo.commit_file("f.txt", "f\n", "feature");
+    // 切换分支不会删除 master 独有的文件，此时 new.txt 在 feat 上未跟踪
+    repo.write("new.txt", "untracked\n");
+
+    let err = repo.fail(&["rebase", "master"]);
+    assert!(err.contains("未跟踪的文件将被变基覆盖"), "{}", err);
+    assert!(err.contains("new.txt"), "{}", err);
+    assert_eq!(repo.read("new.txt"), "untracked\n");
+    assert_eq!(repo.rev("feat"), feature);
+}
+
+#[test]
+fn rebase_stops_on_conflict_without_changes() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "1\n2\n3\n", "base");
+    repo.ok(&["branch", "feat"]);
+    repo.commit_file("a.txt", "1\nmaster\n3\n", "master");
+    repo.ok(&["checkout", "feat"]);
+    let feature = repo.commit_file("a.txt", "1\nfeat\n3\n", "feature");
+
+    let err = repo.fail(&["rebase", "master"]);
+    assert!(err.contains("存在冲突"), "{}", err);
+    assert_eq!(repo.rev("feat"), feature);
+    assert_eq!(repo.read("a.txt"), "1\nfeat\n3\n");
+}
@@ -0,0 +1,17 @@
+mod common;
+
+use common::TestRepo;
+
+#[test]
+fn commit_and_checkout_are_recorded() {
+    let repo = TestRepo::new();
+    let first = repo.commit_file("a.txt", "1\n", "first");
+    repo.commit_file("a.txt", "2\n", "second");
+    repo.ok(&["checkout", &first]);
+
+    let out = repo.ok(&["reflog"]);
+    let lines: Vec<&str> = out.lines().collect();
+    assert!(lines[0].contains("HEAD@{0}: checkout: moving from master to"), "{}", out);
+    assert!(lines[1].contains("HEAD@{1}: commit: second"), "{}", out);
+    assert_eq!(lines.iter().filter(|line| line.contains(": commit: ")).count(), 2, "{}", out);
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use common::TestRepo;
+use rust_git::error::GitError;
+use rust_git::utils::fs as utils_fs;
+use rust_git::utils::repository::Repository;
+
+#[test]
+fn head_of_empty_repository() {
+    let repo = TestRepo::new();
+    let opened = Repository::discover(repo.root()).unwrap();
+
+    assert_eq!(opened.head().unwrap(), None);
+    assert_eq!(opened.current_branch().unwrap(), "master");
+}
+
+#[test]
+fn head_follows_symbolic_ref() {
+    let repo = TestRepo::new();
+    let id = repo.commit_file("a.txt", "a\n", "first");
+    let opened = Repository::discover(repo.root()).unwrap();
+
+    assert_eq!(opened.head().unwrap(), Some(id));
+    assert_eq!(opened.current_branch().unwrap(), "master");
+}
+
+#[test]
+fn head_when_detached() {
+    let repo = TestRepo::new();
+    let first = repo.commit_file("a.txt", "1\n", "first");
+    repo.commit_file("a.txt", "2\n", "second");
+    repo.ok(&["checkout", &first]);
+    let opened = Repository::discover(repo.root()).unwrap();
+
+    assert_eq!(opened.head().unwrap(), Some(first));
+    assert_eq!(opened.current_branch().unwrap(), utils_fs::DETACHED_HEAD);
+}
+
+#[test]
+fn discover_from_subdirectory() {
+    let repo = TestRepo::new();
+    std::fs::create_dir_all(repo.path("src/deep/er")).unwrap();
+
+    let opened = Repository::discover(&repo.path("src/deep/er")).unwrap();
+    assert_eq!(opened.root(), repo.root());
+    assert_eq!(opened.git_dir(), repo.path(".rust-git"));
+    assert!(!opened.is_bare());
+}
+
+#[test]
+fn discover_outside_repository_fails() {
+    let dir = TestRepo::empty();
+    let err = Repository::discover(dir.root()).unwrap_err();
+    assert_eq!(err.downcast_ref::<GitError>(), Some(&GitError::NotARepository));
+}
+
+#[test]
+fn add_and_commit_from_nested_directory() {
+    let repo = TestRepo::new();
+    repo.write("src/lib/mod.rs", "// mod\n");
+    repo.write("README", "readme\n");
+
+    repo.ok_in("src/lib", &["add", "mod.rs", "../../README"]);
+    repo.ok_in("src/lib", &["commit", "from subdir"]);
+
+    assert_eq!(repo.ok(&["ls-files"]), "README\nsrc/lib/mod.rs\n");
+    assert!(repo.ok(&["log", "--oneline"]).contains("from subdir"));
+    assert!(!repo.path("src/lib/.rust-git").exists());
+}
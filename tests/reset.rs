@@ -0,0 +1,29 @@
+mod common;
+
+use common::TestRepo;
+
+#[test]
+fn reset_single_path_unstages_only_that_path() {
+    let repo = TestRepo::new();
+    repo.write("a.txt", "a\n");
+    repo.write("b.txt", "b\n");
+    repo.ok(&["add", "a.txt"]);
+    repo.ok(&["add", "b.txt"]);
+
+    repo.ok(&["reset", "a.txt"]);
+    assert_eq!(repo.staged_paths(), ["b.txt"]);
+}
+
+#[test]
+fn reset_without_path_clears_index() {
+    let repo = TestRepo::new();
+    repo.write("a.txt", "a\n");
+    repo.write("b.txt", "b\n");
+    repo.ok(&["add", "a.txt"]);
+    repo.ok(&["add", "b.txt"]);
+
+    repo.ok(&["reset"]);
+    assert!(repo.staged_paths().is_empty());
+    // 工作区文件保持不变
+    assert_eq!(repo.read("a.txt"), "a\n");
+}
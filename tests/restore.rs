@@ -0,0 +1,68 @@
+mod common;
+
+use common::TestRepo;
+
+#[test]
+fn restore_discards_working_tree_change() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "v1\n", "first");
+    repo.write("a.txt", "v2\n");
+
+    repo.ok(&["restore", "a.txt"]);
+    assert_eq!(repo.read("a.txt"), "v1\n");
+}
+
+#[test]
+fn restore_staged_unstages_but_keeps_file() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "v1\n", "first");
+    repo.write("a.txt", "v2\n");
+    repo.ok(&["add", "a.txt"]);
+
+    repo.ok(&["restore", "--staged", "a.txt"]);
+    assert_eq!(repo.read("a.txt"), "v2\n");
+    let status = repo.ok(&["status"]);
+    assert!(!status.contains("要提交的变更"), "{}", status);
+    assert!(status.contains("尚未暂存以备提交的变更：\n  修改：a.txt"), "{}", status);
+}
+
+#[test]
+fn restore_glob_pattern() {
+    let repo = TestRepo::new();
+    repo.write("a.txt", "a\n");
+    repo.write("b.txt", "b\n");
+    repo.write("c.md", "c\n");
+    repo.ok(&["add", "a.txt", "b.txt", "c.md"]);
+    repo.ok(&["commit", "first"]);
+    for file in ["a.txt", "b.txt", "c.md"] {
+        repo.write(file, "edited\n");
+    }
+
+    repo.ok(&["restore", "*.txt"]);
+    assert_eq!(repo.read("a.txt"), "a\n");
+    assert_eq!(repo.read("b.txt"), "b\n");
+    assert_eq!(repo.read("c.md"), "edited\n");
+}
+
+#[test]
+fn restore_reports_all_missing_objects() {
+    let repo = TestRepo::new();
+    repo.write("a.txt", "a\n");
+    repo.write("b.txt", "b\n");
+    repo.ok(&["add", "a.txt", "b.txt"]);
+    repo.ok(&["commit", "first"]);
+    let staged = repo.ok(&["ls-files", "-s"]);
+    let mut blobs: Vec<&str> = staged.lines().map(|line| &line[7..47]).collect();
+    blobs.sort();
+    for blob in &blobs {
+        std::fs::remove_file(repo.path(&format!(".rust-git/objects/{}/{}", &blob[..2], &blob[2..]))).unwrap();
+    }
+    repo.write("a.txt", "edited\n");
+    repo.write("b.txt", "edited\n");
+
+    let err = repo.fail(&["restore", "a.txt", "b.txt"]);
+    assert!(err.contains(&format!("repository is missing objects: {}, {}", blobs[0], blobs[1])), "{}", err);
+    // 预检失败时不恢复任何文件
+    assert_eq!(repo.read("a.txt"), "edited\n");
+    assert_eq!(repo.read("b.txt"), "edited\n");
+}
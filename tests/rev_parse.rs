@@ -0,0 +1,28 @@
+mod common;
+
+use common::TestRepo;
+
+#[test]
+fn rev_parse_head_branch_and_prefix() {
+    let repo = TestRepo::new();
+    let id = repo.commit_file("a.txt", "a\n", "first");
+    repo.ok(&["branch", "feat"]);
+
+    assert_eq!(repo.ok(&["rev-parse", "HEAD"]), format!("{}\n", id));
+    assert_eq!(repo.ok(&["rev-parse", "feat"]), format!("{}\n", id));
+    assert_eq!(repo.ok(&["rev-parse", &id[..7]]), format!("{}\n", id));
+}
+
+#[test]
+fn rev_parse_ancestry() {
+    let repo = TestRepo::new();
+    let first = repo.commit_file("a.txt", "1\n", "c1");
+    let second = repo.commit_file("a.txt", "2\n", "c2");
+    repo.commit_file("a.txt", "3\n", "c3");
+
+    assert_eq!(repo.rev("HEAD~1"), second);
+    assert_eq!(repo.rev("HEAD~2"), first);
+    assert_eq!(repo.rev("HEAD~~"), first);
+    let err = repo.fail(&["rev-parse", "HEAD~3"]);
+    assert!(err.contains("没有父提交"), "{}", err);
+}
@@ -0,0 +1,17 @@
+mod common;
+
+use common::TestRepo;
+
+#[test]
+fn revert_removes_file_added_by_commit() {
+    let repo = TestRepo::new();
+    repo.commit_file("base.txt", "base\n", "base");
+    let added = repo.commit_file("extra.txt", "extra\n", "add extra");
+
+    repo.ok(&["revert", "HEAD"]);
+    assert!(!repo.path("extra.txt").exists());
+    assert_eq!(repo.rev("HEAD~1"), added);
+    assert_eq!(repo.ok(&["ls-files"]), "base.txt\n");
+    let subject = repo.ok(&["log", "--oneline", "-n", "1"]);
+    assert!(subject.contains("Revert"), "{}", subject);
+}
@@ -0,0 +1,36 @@
+mod common;
+
+use common::TestRepo;
+
+#[test]
+fn rm_resolves_path_from_subdirectory() {
+    let repo = TestRepo::new();
+    repo.write("sub/a.txt", "a\n");
+    repo.ok(&["add", "sub/a.txt"]);
+
+    repo.ok_in("sub", &["rm", "a.txt"]);
+    assert!(repo.staged_paths().is_empty());
+    assert!(!repo.path("sub/a.txt").exists());
+}
+
+#[test]
+fn rm_cached_keeps_file_on_disk() {
+    let repo = TestRepo::new();
+    repo.write("a.txt", "a\n");
+    repo.ok(&["add", "a.txt"]);
+
+    repo.ok(&["rm", "--cached", "a.txt"]);
+    assert!(repo.staged_paths().is_empty());
+    assert_eq!(repo.read("a.txt"), "a\n");
+}
+
+#[test]
+fn rm_without_cached_deletes_file() {
+    let repo = TestRepo::new();
+    repo.write("a.txt", "a\n");
+    repo.ok(&["add", "a.txt"]);
+
+    repo.ok(&["rm", "a.txt"]);
+    assert!(repo.staged_paths().is_empty());
+    assert!(!repo.path("a.txt").exists());
+}
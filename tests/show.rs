@@ -0,0 +1,29 @@
+mod common;
+
+use common::TestRepo;
+
+#[test]
+fn show_prints_message_and_changed_paths() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "a\n", "first");
+    repo.write("b.txt", "b\n");
+    repo.write("a.txt", "a2\n");
+    repo.ok(&["add", "a.txt", "b.txt"]);
+    repo.ok(&["commit", "second change"]);
+
+    let out = repo.ok(&["show", "HEAD"]);
+    assert!(out.contains("    second change"), "{}", out);
+    assert!(out.contains("diff --git a/a.txt b/a.txt"), "{}", out);
+    assert!(out.contains("diff --git a/b.txt b/b.txt"), "{}", out);
+    assert!(!out.contains("first"), "{}", out);
+}
+
+#[test]
+fn show_at_sign_is_head() {
+    let repo = TestRepo::new();
+    let head = repo.commit_file("a.txt", "a\n", "latest");
+
+    let out = repo.ok(&["show", "@"]);
+    assert!(out.starts_with(&format!("commit {}", head)), "{}", out);
+    assert!(out.contains("    latest"), "{}", out);
+}
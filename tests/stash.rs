@@ -0,0 +1,19 @@
+mod common;
+
+use common::TestRepo;
+
+#[test]
+fn stash_and_pop_round_trip() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "committed\n", "first");
+    repo.write("a.txt", "work in progress\n");
+
+    repo.ok(&["stash"]);
+    assert_eq!(repo.read("a.txt"), "committed\n");
+    assert!(repo.ok(&["status"]).contains("工作区干净"));
+
+    repo.ok(&["stash", "--pop"]);
+    assert_eq!(repo.read("a.txt"), "work in progress\n");
+    let err = repo.fail(&["stash", "--pop"]);
+    assert!(!err.is_empty());
+}
@@ -0,0 +1,40 @@
+mod common;
+
+use common::TestRepo;
+
+#[test]
+fn modified_after_add_shows_as_modified() {
+    let repo = TestRepo::new();
+    repo.write("a.txt", "one\n");
+    repo.ok(&["add", "a.txt"]);
+    repo.write("a.txt", "one\ntwo\n");
+
+    let out = repo.ok(&["status"]);
+    let unstaged = out.split("尚未暂存以备提交的变更：").nth(1).expect("缺少未暂存变更小节");
+    assert!(unstaged.contains("修改：a.txt"), "{}", out);
+}
+
+#[test]
+fn porcelain_lines_for_added_and_modified_files() {
+    let repo = TestRepo::new();
+    repo.commit_file("tracked.txt", "v1\n", "first");
+    repo.write("tracked.txt", "v2\n");
+    repo.write("new.txt", "new\n");
+    repo.ok(&["add", "new.txt"]);
+
+    assert_eq!(repo.ok(&["status", "--porcelain"]), "A  new.txt\n M tracked.txt\n");
+}
+
+#[test]
+fn short_status_branch_header() {
+    let repo = TestRepo::new();
+    assert_eq!(repo.ok(&["status", "-s"]), "## No commits yet on master\n");
+
+    let first = repo.commit_file("a.txt", "1\n", "first");
+    repo.commit_file("a.txt", "2\n", "second");
+    repo.write("new.txt", "new\n");
+    assert_eq!(repo.ok(&["status", "-s"]), "## master\n?? new.txt\n");
+
+    repo.ok(&["checkout", &first]);
+    assert!(repo.ok(&["status", "-s"]).starts_with("## HEAD (no branch)\n"));
+}
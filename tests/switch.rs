@@ -0,0 +1,20 @@
+mod common;
+
+use common::TestRepo;
+
+#[test]
+fn switch_refuses_to_overwrite_modified_file() {
+    let repo = TestRepo::new();
+    repo.commit_file("a.txt", "v1\n", "first");
+    repo.ok(&["branch", "feat"]);
+    repo.commit_file("a.txt", "v2\n", "second");
+    repo.write("a.txt", "local edit\n");
+
+    let err = repo.fail(&["switch", "feat"]);
+    assert!(err.contains("a.txt"), "{}", err);
+    assert_eq!(repo.read("a.txt"), "local edit\n");
+    assert_eq!(repo.read(".rust-git/HEAD").trim(), "ref: refs/heads/master");
+
+    repo.ok(&["switch", "-f", "feat"]);
+    assert_eq!(repo.read("a.txt"), "v1\n");
+}
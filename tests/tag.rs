@@ -0,0 +1,59 @@
+mod common;
+
+use common::TestRepo;
+use rust_git::utils::hash;
+
+#[test]
+fn create_list_and_delete_tags() {
+    let repo = TestRepo::new();
+    let head = repo.commit_file("a.txt", "a\n", "first");
+
+    repo.ok(&["tag", "v2"]);
+    repo.ok(&["tag", "v1"]);
+    assert_eq!(repo.ok(&["tag"]), "v1\nv2\n");
+    assert_eq!(repo.rev("v1"), head);
+
+    repo.ok(&["tag", "-d", "v1"]);
+    assert_eq!(repo.ok(&["tag"]), "v2\n");
+    let err = repo.fail(&["tag", "-d", "v1"]);
+    assert!(err.contains("标签 v1 不存在"), "{}", err);
+}
+
+#[test]
+fn tag_names_cannot_escape_tags_dir() {
+    let repo = TestRepo::new();
+    let head = repo.commit_file("a.txt", "a\n", "first");
+
+    for name in ["../../HEAD", "../heads/master", ".hidden", "v1.lock"] {
+        let err = repo.fail(&["tag", "-d", name]);
+        assert!(err.contains("不合法"), "{}: {}", name, err);
+    }
+    let err = repo.fail(&["tag", "../v1"]);
+    assert!(err.contains("引用名不合法"), "{}", err);
+    let err = repo.fail(&["tag", "bad..name"]);
+    assert!(err.contains("引用名不合法：bad..name") && !err.contains("分支"), "{}", err);
+    assert_eq!(repo.read(".rust-git/HEAD").trim(), "ref: refs/heads/master");
+    assert_eq!(repo.rev("master"), head);
+}
+
+#[test]
+fn annotated_tag_message_reads_back() {
+    let repo = TestRepo::new();
+    let head = repo.commit_file("a.txt", "a\n", "first");
+
+    repo.ok(&["tag", "-a", "v1", "-m", "release one"]);
+    let tag_id = repo.read(".rust-git/refs/tags/v1").trim().to_string();
+    // 附注标签的引用指向标签对象，解析为修订时指向提交
+    assert_ne!(tag_id, head);
+    assert_eq!(repo.rev("v1"), head);
+
+    let out = repo.ok(&["show", "v1"]);
+    assert!(out.starts_with("tag v1\nTagger: "), "{}", out);
+    assert!(out.contains("\nrelease one\n"), "{}", out);
+    assert!(out.contains(&format!("commit {}", head)), "{}", out);
+
+    let _cwd = repo.enter();
+    let tag = hash::read_tag(&tag_id).unwrap();
+    assert_eq!((tag.name.as_str(), tag.object.as_str()), ("v1", head.as_str()));
+    assert_eq!(tag.message.trim_end(), "release one");
+}